@@ -0,0 +1,21 @@
+use crate::error::Result;
+
+use super::bulk::{self, BulkAction};
+
+pub struct AssignOptions {
+    pub id: Option<String>,
+    /// `key=value` filters selecting the spikes to assign instead of an ID
+    pub filters: Vec<String>,
+    /// Assignee name; `None` clears the assignee
+    pub to: Option<String>,
+    pub force: bool,
+    pub json: bool,
+}
+
+pub fn run(options: AssignOptions) -> Result<()> {
+    let action = BulkAction::Assign(options.to);
+    match options.id {
+        Some(id) => bulk::run_one(&id, action, options.json),
+        None => bulk::run_where(&options.filters, action, options.force, options.json),
+    }
+}
@@ -0,0 +1,324 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+
+use crate::error::{Error, Result};
+use crate::output::{print_json, print_spikes_table};
+use crate::spike::Spike;
+use crate::identity::load_canonical_spikes;
+use crate::storage::{find_spike_by_id, load_spikes, save_spikes};
+
+use super::list::SpikeFilter;
+
+/// A change applied to a set of spikes in one write
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkAction {
+    Resolve,
+    Unresolve,
+    Delete,
+    Tag { add: Vec<String>, remove: Vec<String> },
+    Assign(Option<String>),
+}
+
+impl BulkAction {
+    /// Short name used in JSON output
+    pub fn name(&self) -> &'static str {
+        match self {
+            BulkAction::Resolve => "resolve",
+            BulkAction::Unresolve => "unresolve",
+            BulkAction::Delete => "delete",
+            BulkAction::Tag { .. } => "tag",
+            BulkAction::Assign(Some(_)) => "assign",
+            BulkAction::Assign(None) => "unassign",
+        }
+    }
+
+    fn past_tense(&self) -> &'static str {
+        match self {
+            BulkAction::Resolve => "Resolved",
+            BulkAction::Unresolve => "Unresolved",
+            BulkAction::Delete => "Deleted",
+            BulkAction::Tag { .. } => "Tagged",
+            BulkAction::Assign(Some(_)) => "Assigned",
+            BulkAction::Assign(None) => "Unassigned",
+        }
+    }
+
+    fn prompt(&self, count: usize) -> String {
+        let noun = format!("{} spike{}", count, if count == 1 { "" } else { "s" });
+        match self {
+            BulkAction::Resolve => format!("Resolve {}?", noun),
+            BulkAction::Unresolve => format!("Mark {} as unresolved?", noun),
+            BulkAction::Delete => format!("Delete {}? This cannot be undone.", noun),
+            BulkAction::Tag { add, remove } => {
+                let mut changes = Vec::new();
+                if !add.is_empty() {
+                    changes.push(format!("add {}", add.join(", ")));
+                }
+                if !remove.is_empty() {
+                    changes.push(format!("remove {}", remove.join(", ")));
+                }
+                format!("Tag {} ({})?", noun, changes.join("; "))
+            }
+            BulkAction::Assign(Some(name)) => format!("Assign {} to {}?", noun, name),
+            BulkAction::Assign(None) => format!("Clear the assignee of {}?", noun),
+        }
+    }
+
+    /// Apply the action to every spike whose ID is in `ids`
+    pub fn apply(&self, spikes: &mut Vec<Spike>, ids: &HashSet<String>) {
+        if *self == BulkAction::Delete {
+            spikes.retain(|s| !ids.contains(&s.id));
+            return;
+        }
+
+        let now = chrono::Local::now().to_rfc3339();
+        for spike in spikes.iter_mut().filter(|s| ids.contains(&s.id)) {
+            match self {
+                BulkAction::Resolve => {
+                    // Keep the original timestamp of spikes that were already resolved
+                    if !spike.is_resolved() {
+                        spike.resolved = Some(true);
+                        spike.resolved_at = Some(now.clone());
+                    }
+                }
+                BulkAction::Unresolve => {
+                    spike.resolved = None;
+                    spike.resolved_at = None;
+                }
+                BulkAction::Delete => unreachable!(),
+                BulkAction::Tag { add, remove } => {
+                    let mut tags = spike.tags.take().unwrap_or_default();
+                    tags.retain(|t| !remove.iter().any(|r| r.eq_ignore_ascii_case(t)));
+                    for tag in add {
+                        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                            tags.push(tag.clone());
+                        }
+                    }
+                    spike.tags = (!tags.is_empty()).then_some(tags);
+                }
+                BulkAction::Assign(assignee) => spike.assignee = assignee.clone(),
+            }
+        }
+    }
+}
+
+/// IDs of the spikes matching a filter, in file order
+pub fn select_ids(spikes: &[Spike], filter: &SpikeFilter) -> Vec<String> {
+    spikes
+        .iter()
+        .filter(|s| filter.matches(s))
+        .map(|s| s.id.clone())
+        .collect()
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    loop {
+        print!("{} [y/N] ", prompt);
+        stdout.flush()?;
+
+        let mut input = String::new();
+        stdin.lock().read_line(&mut input)?;
+
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => return Ok(true),
+            "n" | "no" | "" => return Ok(false),
+            _ => continue,
+        }
+    }
+}
+
+fn print_result(action: &BulkAction, ids: &[String], json: bool) {
+    if json {
+        print_json(&serde_json::json!({
+            "action": action.name(),
+            "count": ids.len(),
+            "ids": ids
+        }));
+    } else if ids.len() == 1 {
+        println!("{} spike {}.", action.past_tense(), ids[0]);
+    } else {
+        println!("{} {} spikes.", action.past_tense(), ids.len());
+    }
+}
+
+/// Apply an action to every spike matching the `--where` expressions.
+///
+/// Previews the affected spikes and asks for confirmation unless `force` is
+/// set, then saves once.
+pub fn run_where(exprs: &[String], action: BulkAction, force: bool, json: bool) -> Result<()> {
+    let filter = SpikeFilter::parse_where(exprs)?;
+    // Match against canonical reviewers so aliases count, but save the spikes as stored
+    let canonical = load_canonical_spikes()?;
+    let ids = select_ids(&canonical, &filter);
+
+    if ids.is_empty() {
+        if json {
+            print_result(&action, &ids, json);
+        } else {
+            println!("No spikes match.");
+        }
+        return Ok(());
+    }
+
+    if !force {
+        if !json {
+            let matched: Vec<Spike> = canonical.into_iter().filter(|s| filter.matches(s)).collect();
+            print_spikes_table(&matched);
+        }
+        if !confirm(&action.prompt(ids.len()))? {
+            if json {
+                print_json(&serde_json::json!({
+                    "action": action.name(),
+                    "count": 0,
+                    "message": "Cancelled"
+                }));
+            } else {
+                println!("Cancelled.");
+            }
+            return Ok(());
+        }
+    }
+
+    let mut spikes = load_spikes()?;
+    action.apply(&mut spikes, &ids.iter().cloned().collect());
+    save_spikes(&spikes)?;
+    print_result(&action, &ids, json);
+
+    Ok(())
+}
+
+/// Apply an action to a single spike by ID or prefix
+pub fn run_one(id: &str, action: BulkAction, json: bool) -> Result<()> {
+    let mut spikes = load_spikes()?;
+    let spike = find_spike_by_id(&spikes, id)?;
+
+    action.apply(&mut spikes, &HashSet::from([spike.id.clone()]));
+    save_spikes(&spikes)?;
+
+    if json {
+        let updated = spikes
+            .iter()
+            .find(|s| s.id == spike.id)
+            .ok_or_else(|| Error::SpikeNotFound(id.to_string()))?;
+        print_json(updated);
+    } else {
+        print_result(&action, &[spike.id], json);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Rating, Reviewer};
+
+    fn spike(id: &str, page: &str, rating: Rating) -> Spike {
+        Spike {
+            url: format!("http://localhost/{}", page),
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            ..Spike::test(id, page, Some(rating))
+        }
+    }
+
+    fn ids(list: &[&str]) -> HashSet<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_select_ids_with_where_filter() {
+        let spikes = vec![
+            spike("s1", "old-pricing.html", Rating::No),
+            spike("s2", "old-pricing.html", Rating::Love),
+            spike("s3", "index.html", Rating::No),
+        ];
+        let filter = SpikeFilter::parse_where(&["page=old-pricing.html".to_string()]).unwrap();
+        assert_eq!(select_ids(&spikes, &filter), vec!["s1", "s2"]);
+
+        let filter =
+            SpikeFilter::parse_where(&["page=old-pricing.html".to_string(), "rating=no".to_string()]).unwrap();
+        assert_eq!(select_ids(&spikes, &filter), vec!["s1"]);
+    }
+
+    #[test]
+    fn test_where_text_keys_match_whole_value_unless_tilde() {
+        let spikes = vec![
+            spike("s1", "pricing.html", Rating::No),
+            spike("s2", "old-pricing.html", Rating::No),
+        ];
+        let filter = SpikeFilter::parse_where(&["page=Pricing.html".to_string()]).unwrap();
+        assert_eq!(select_ids(&spikes, &filter), vec!["s1"]);
+        assert_eq!(filter.describe(), vec!["page=Pricing.html"]);
+
+        let filter = SpikeFilter::parse_where(&["page~=pricing".to_string()]).unwrap();
+        assert_eq!(select_ids(&spikes, &filter), vec!["s1", "s2"]);
+        assert_eq!(filter.describe(), vec!["page~=pricing"]);
+
+        let filter = SpikeFilter::parse_where(&["reviewer=ali".to_string()]).unwrap();
+        assert!(select_ids(&spikes, &filter).is_empty());
+        let filter = SpikeFilter::parse_where(&["reviewer=alice".to_string()]).unwrap();
+        assert_eq!(select_ids(&spikes, &filter).len(), 2);
+    }
+
+    #[test]
+    fn test_parse_where_rejects_bad_expressions() {
+        for bad in ["page", "color=red", "rating=great", "resolved=maybe", "page=", "rating~=no"] {
+            let err = SpikeFilter::parse_where(&[bad.to_string()]).unwrap_err();
+            assert!(matches!(err, Error::InvalidFilter(_)), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_apply_resolve_keeps_existing_timestamp() {
+        let mut already = spike("s2", "a.html", Rating::No);
+        already.resolved = Some(true);
+        already.resolved_at = Some("2024-01-01T00:00:00Z".to_string());
+        let mut spikes = vec![spike("s1", "a.html", Rating::No), already, spike("s3", "a.html", Rating::No)];
+
+        BulkAction::Resolve.apply(&mut spikes, &ids(&["s1", "s2"]));
+
+        assert!(spikes[0].is_resolved());
+        assert_eq!(spikes[1].resolved_at.as_deref(), Some("2024-01-01T00:00:00Z"));
+        assert!(!spikes[2].is_resolved());
+    }
+
+    #[test]
+    fn test_apply_delete_tag_and_assign() {
+        let mut spikes = vec![
+            spike("s1", "a.html", Rating::No),
+            spike("s2", "a.html", Rating::No),
+            spike("s3", "a.html", Rating::No),
+        ];
+        spikes[0].tags = Some(vec!["copy".to_string()]);
+
+        BulkAction::Tag {
+            add: vec!["pricing".to_string(), "Copy".to_string()],
+            remove: vec![],
+        }
+        .apply(&mut spikes, &ids(&["s1", "s2"]));
+        assert_eq!(spikes[0].tags, Some(vec!["copy".to_string(), "pricing".to_string()]));
+        assert_eq!(spikes[1].tags, Some(vec!["pricing".to_string(), "Copy".to_string()]));
+
+        BulkAction::Tag {
+            add: vec![],
+            remove: vec!["PRICING".to_string(), "copy".to_string()],
+        }
+        .apply(&mut spikes, &ids(&["s2"]));
+        assert_eq!(spikes[1].tags, None);
+
+        BulkAction::Assign(Some("dana".to_string())).apply(&mut spikes, &ids(&["s3"]));
+        assert_eq!(spikes[2].assignee.as_deref(), Some("dana"));
+
+        BulkAction::Delete.apply(&mut spikes, &ids(&["s1", "s3"]));
+        assert_eq!(spikes.len(), 1);
+        assert_eq!(spikes[0].id, "s2");
+    }
+}
@@ -0,0 +1,196 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::output::{print_check_table, print_json};
+use crate::pages::{selector_for, FoundBy, Location, Site};
+use crate::spike::{Spike, SpikeType};
+use crate::storage::{load_spikes, save_spikes};
+
+pub struct CheckOptions {
+    /// HTML directory (default: the configured site directory)
+    pub dir: Option<String>,
+    pub json: bool,
+    pub flag: bool,
+    pub resolve: bool,
+}
+
+/// Whether a spike's element still exists in the current HTML
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// The selector still matches
+    Live,
+    /// The selector no longer matches, but the element was re-found
+    Moved,
+    /// The element is no longer on the page
+    Gone,
+    /// The page could not be mapped to an HTML file
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub id: String,
+    pub page: String,
+    pub selector: Option<String>,
+    pub status: CheckStatus,
+    /// HTML file the page resolved to, relative to the checked directory
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub found_by: Option<FoundBy>,
+    /// Selector for the re-found element of a moved spike
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_selector: Option<String>,
+}
+
+pub fn run(options: CheckOptions) -> Result<()> {
+    let mut spikes = load_spikes()?;
+    let dir = match &options.dir {
+        Some(dir) => PathBuf::from(dir),
+        None => Config::load()?.site_dir(),
+    };
+    let site = Site::load(&dir)?;
+    let results = check_spikes(&spikes, &site);
+
+    let mut flagged = 0;
+    let mut resolved = 0;
+    if options.flag || options.resolve {
+        let resolved_at = chrono::Local::now().to_rfc3339();
+        for spike in &mut spikes {
+            let Some(result) = results.iter().find(|r| r.id == spike.id) else {
+                continue;
+            };
+            let gone = result.status == CheckStatus::Gone;
+            if options.flag {
+                if gone && spike.stale != Some(true) {
+                    flagged += 1;
+                }
+                // Only clear flags we can vouch for; unknown pages keep theirs
+                if gone || result.status != CheckStatus::Unknown {
+                    spike.stale = gone.then_some(true);
+                }
+            }
+            if options.resolve && gone {
+                spike.resolved = Some(true);
+                spike.resolved_at = Some(resolved_at.clone());
+                resolved += 1;
+            }
+        }
+        save_spikes(&spikes)?;
+    }
+
+    if options.json {
+        print_json(&results);
+        return Ok(());
+    }
+
+    if results.is_empty() {
+        println!("No unresolved element spikes to check.");
+        return Ok(());
+    }
+
+    print_check_table(&results);
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    println!(
+        "\n{} live, {} moved, {} gone, {} unknown",
+        count(CheckStatus::Live),
+        count(CheckStatus::Moved),
+        count(CheckStatus::Gone),
+        count(CheckStatus::Unknown)
+    );
+    if options.flag {
+        println!("Flagged {} spike{} as stale.", flagged, if flagged == 1 { "" } else { "s" });
+    }
+    if options.resolve {
+        println!("Resolved {} spike{}.", resolved, if resolved == 1 { "" } else { "s" });
+    }
+
+    Ok(())
+}
+
+/// Check every unresolved element spike against the site's HTML
+pub fn check_spikes(spikes: &[Spike], site: &Site) -> Vec<CheckResult> {
+    spikes
+        .iter()
+        .filter(|s| s.spike_type == SpikeType::Element && s.selector.is_some() && !s.is_resolved())
+        .map(|spike| {
+            let page = site.resolve(spike);
+            let (status, found_by, new_selector) = match page.map(|p| p.locate(spike)) {
+                None => (CheckStatus::Unknown, None, None),
+                Some(Location::Live(_)) => (CheckStatus::Live, None, None),
+                Some(Location::Moved(element, by)) => (CheckStatus::Moved, Some(by), Some(selector_for(element))),
+                Some(Location::Gone) => (CheckStatus::Gone, None, None),
+            };
+            CheckResult {
+                id: spike.id.clone(),
+                page: spike.page.clone(),
+                selector: spike.selector.clone(),
+                status,
+                file: page.map(|p| p.path.clone()),
+                found_by,
+                new_selector,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::spike::Reviewer;
+    use tempfile::TempDir;
+
+    fn spike(id: &str, url: &str, selector: &str, text: Option<&str>) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            url: url.to_string(),
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: Some(selector.to_string()),
+            element_text: text.map(|t| t.to_string()),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            ..Spike::test(id, "Home", None)
+        }
+    }
+
+    #[test]
+    fn test_check_spikes_statuses() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("index.html"),
+            "<html><body><h1 class=\"title\">Hello</h1><p id=\"intro\">Moved copy</p></body></html>",
+        )
+        .unwrap();
+        let site = Site::load(dir.path()).unwrap();
+
+        let mut resolved = spike("s5", "http://localhost/", ".gone", None);
+        resolved.resolved = Some(true);
+        let spikes = vec![
+            spike("s1", "http://localhost/", ".title", None),
+            spike("s2", "http://localhost/index.html", ".lede", Some("Moved copy")),
+            spike("s3", "http://localhost/", ".cta", Some("Sign up")),
+            spike("s4", "http://localhost/missing.html", ".title", None),
+            resolved,
+        ];
+
+        let results = check_spikes(&spikes, &site);
+        let statuses: Vec<CheckStatus> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![CheckStatus::Live, CheckStatus::Moved, CheckStatus::Gone, CheckStatus::Unknown]
+        );
+        assert_eq!(results[1].found_by, Some(FoundBy::Text));
+        assert_eq!(results[1].new_selector.as_deref(), Some("#intro"));
+        assert_eq!(results[0].file.as_deref(), Some("index.html"));
+        assert!(results[3].file.is_none());
+    }
+}
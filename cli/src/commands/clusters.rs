@@ -0,0 +1,341 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::output::{print_clusters_table, print_json};
+use crate::selector::canonicalize;
+use crate::spike::{Rating, Spike, SpikeType};
+use crate::storage::load_spikes;
+
+use super::list::contains_ignore_case;
+
+/// Default gap between two boxes, as a fraction of viewport width, under which
+/// they are treated as the same region of the page.
+pub const DEFAULT_THRESHOLD: f64 = 0.02;
+
+pub struct ClustersOptions {
+    pub json: bool,
+    pub page: Option<String>,
+    pub threshold: f64,
+}
+
+/// Bounding box normalized to the reviewer's viewport width
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct NormalizedBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl NormalizedBox {
+    /// Normalize a spike's bounding box. Returns None for spikes without a
+    /// bounding box or a usable viewport.
+    pub fn from_spike(spike: &Spike) -> Option<Self> {
+        let bb = spike.bounding_box.as_ref()?;
+        let vw = spike.viewport.as_ref()?.width as f64;
+        if vw <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            x: bb.x / vw,
+            y: bb.y / vw,
+            width: bb.width / vw,
+            height: bb.height / vw,
+        })
+    }
+
+    /// Largest axis gap between two boxes (0 when they overlap)
+    fn gap(&self, other: &NormalizedBox) -> f64 {
+        let dx = (self.x.max(other.x) - (self.x + self.width).min(other.x + other.width)).max(0.0);
+        let dy = (self.y.max(other.y) - (self.y + self.height).min(other.y + other.height)).max(0.0);
+        dx.max(dy)
+    }
+
+    fn union(&self, other: &NormalizedBox) -> NormalizedBox {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        NormalizedBox {
+            x,
+            y,
+            width: right - x,
+            height: bottom - y,
+        }
+    }
+}
+
+/// Number of spikes per rating
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RatingCounts {
+    pub love: usize,
+    pub like: usize,
+    pub meh: usize,
+    pub no: usize,
+}
+
+impl RatingCounts {
+    pub fn add(&mut self, rating: Option<&Rating>) {
+        match rating {
+            Some(Rating::Love) => self.love += 1,
+            Some(Rating::Like) => self.like += 1,
+            Some(Rating::Meh) => self.meh += 1,
+            Some(Rating::No) => self.no += 1,
+            None => {}
+        }
+    }
+}
+
+/// A group of element spikes whose bounding boxes overlap or sit close together
+#[derive(Debug, Clone, Serialize)]
+pub struct Cluster {
+    pub page: String,
+    pub selectors: Vec<String>,
+    pub spike_ids: Vec<String>,
+    pub count: usize,
+    /// Rating closest to the average of all rated spikes in the cluster
+    pub rating: Option<Rating>,
+    pub ratings: RatingCounts,
+    pub comments: Vec<String>,
+    pub bounds: NormalizedBox,
+}
+
+impl Cluster {
+    /// Whether the cluster's page contains `page`, ignoring case (like `list --page`)
+    pub fn on_page(&self, page: &str) -> bool {
+        contains_ignore_case(&self.page, page)
+    }
+}
+
+pub fn run(options: ClustersOptions) -> Result<()> {
+    let spikes = load_spikes()?;
+
+    let mut clusters = compute_clusters(&spikes, options.threshold);
+    if let Some(ref page) = options.page {
+        clusters.retain(|c| c.on_page(page));
+    }
+
+    if options.json {
+        print_json(&clusters);
+    } else {
+        print_clusters_table(&clusters);
+    }
+
+    Ok(())
+}
+
+/// Group element spikes per page by overlapping or nearby normalized bounding
+/// boxes. Spikes without a bounding box or viewport are ignored.
+///
+/// Clusters are sorted by spike count (descending), then page.
+pub fn compute_clusters(spikes: &[Spike], threshold: f64) -> Vec<Cluster> {
+    let mut by_page: BTreeMap<&str, Vec<(&Spike, NormalizedBox)>> = BTreeMap::new();
+    for spike in spikes {
+        if spike.spike_type != SpikeType::Element {
+            continue;
+        }
+        if let Some(nb) = NormalizedBox::from_spike(spike) {
+            by_page.entry(spike.page.as_str()).or_default().push((spike, nb));
+        }
+    }
+
+    let mut clusters = Vec::new();
+    for (page, items) in by_page {
+        // Union-find over boxes within `threshold` of each other
+        let mut parent: Vec<usize> = (0..items.len()).collect();
+        for i in 0..items.len() {
+            for j in (i + 1)..items.len() {
+                if items[i].1.gap(&items[j].1) <= threshold {
+                    let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                    if a != b {
+                        parent[b] = a;
+                    }
+                }
+            }
+        }
+
+        let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for i in 0..items.len() {
+            let root = find(&mut parent, i);
+            groups.entry(root).or_default().push(i);
+        }
+
+        for members in groups.values() {
+            clusters.push(build_cluster(page, members.iter().map(|&i| &items[i])));
+        }
+    }
+
+    clusters.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.page.cmp(&b.page)));
+    clusters
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[i] = root;
+    root
+}
+
+fn build_cluster<'a>(
+    page: &str,
+    members: impl Iterator<Item = &'a (&'a Spike, NormalizedBox)>,
+) -> Cluster {
+    let mut selectors: Vec<String> = Vec::new();
+    let mut spike_ids = Vec::new();
+    let mut comments = Vec::new();
+    let mut ratings = RatingCounts::default();
+    let mut bounds: Option<NormalizedBox> = None;
+    let mut score_sum = 0.0;
+    let mut rated = 0usize;
+
+    for (spike, nb) in members {
+        if let Some(selector) = spike.selector.as_deref().map(canonicalize) {
+            if !selectors.contains(&selector) {
+                selectors.push(selector);
+            }
+        }
+        spike_ids.push(spike.id.clone());
+        if !spike.comments.is_empty() {
+            comments.push(spike.comments.clone());
+        }
+        ratings.add(spike.rating.as_ref());
+        if let Some(rating) = &spike.rating {
+            score_sum += rating.score();
+            rated += 1;
+        }
+        bounds = Some(match bounds {
+            Some(b) => b.union(nb),
+            None => *nb,
+        });
+    }
+
+    Cluster {
+        page: page.to_string(),
+        selectors,
+        count: spike_ids.len(),
+        spike_ids,
+        rating: (rated > 0).then(|| Rating::from_score(score_sum / rated as f64)),
+        ratings,
+        comments,
+        bounds: bounds.expect("cluster has at least one member"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{BoundingBox, Reviewer, Viewport};
+
+    fn element_spike(id: &str, page: &str, selector: &str, bb: (f64, f64, f64, f64), rating: Rating) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            url: format!("http://test/{}", page),
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: Some(selector.to_string()),
+            bounding_box: Some(BoundingBox {
+                x: bb.0,
+                y: bb.1,
+                width: bb.2,
+                height: bb.3,
+            }),
+            comments: format!("comment {}", id),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            viewport: Some(Viewport {
+                width: 1000,
+                height: 800,
+            }),
+            ..Spike::test(id, page, Some(rating))
+        }
+    }
+
+    #[test]
+    fn test_overlapping_boxes_cluster_together() {
+        let spikes = vec![
+            element_spike("s1", "index.html", ".card", (100.0, 100.0, 300.0, 200.0), Rating::Love),
+            element_spike("s2", "index.html", ".card  H2", (120.0, 110.0, 200.0, 40.0), Rating::Like),
+            element_spike("s3", "index.html", ".footer", (0.0, 2000.0, 1000.0, 100.0), Rating::No),
+        ];
+
+        let clusters = compute_clusters(&spikes, DEFAULT_THRESHOLD);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].count, 2);
+        assert_eq!(clusters[0].selectors, vec![".card", ".card h2"]);
+        assert_eq!(clusters[0].ratings.love, 1);
+        assert_eq!(clusters[0].ratings.like, 1);
+        assert_eq!(clusters[0].comments.len(), 2);
+        assert_eq!(clusters[1].spike_ids, vec!["s3"]);
+    }
+
+    #[test]
+    fn test_nearby_boxes_within_threshold() {
+        let spikes = vec![
+            element_spike("s1", "index.html", ".a", (0.0, 0.0, 100.0, 100.0), Rating::Meh),
+            // 10px gap at 1000px viewport = 0.01
+            element_spike("s2", "index.html", ".b", (110.0, 0.0, 100.0, 100.0), Rating::Meh),
+        ];
+
+        assert_eq!(compute_clusters(&spikes, DEFAULT_THRESHOLD).len(), 1);
+        assert_eq!(compute_clusters(&spikes, 0.005).len(), 2);
+    }
+
+    #[test]
+    fn test_clusters_are_per_page() {
+        let spikes = vec![
+            element_spike("s1", "index.html", ".a", (0.0, 0.0, 100.0, 100.0), Rating::Love),
+            element_spike("s2", "about.html", ".a", (0.0, 0.0, 100.0, 100.0), Rating::Love),
+        ];
+
+        let clusters = compute_clusters(&spikes, DEFAULT_THRESHOLD);
+        assert_eq!(clusters.len(), 2);
+        assert_ne!(clusters[0].page, clusters[1].page);
+
+        let on_index: Vec<&str> = clusters.iter().filter(|c| c.on_page("INDEX")).map(|c| c.page.as_str()).collect();
+        assert_eq!(on_index, vec!["index.html"]);
+    }
+
+    #[test]
+    fn test_normalization_uses_viewport_width() {
+        let mut narrow = element_spike("s1", "index.html", ".a", (0.0, 0.0, 375.0, 100.0), Rating::Love);
+        narrow.viewport = Some(Viewport {
+            width: 375,
+            height: 667,
+        });
+        let wide = element_spike("s2", "index.html", ".a", (0.0, 0.0, 1000.0, 100.0), Rating::Love);
+
+        let a = NormalizedBox::from_spike(&narrow).unwrap();
+        let b = NormalizedBox::from_spike(&wide).unwrap();
+        assert_eq!(a.width, 1.0);
+        assert_eq!(b.width, 1.0);
+    }
+
+    #[test]
+    fn test_spikes_without_geometry_are_skipped() {
+        let mut spike = element_spike("s1", "index.html", ".a", (0.0, 0.0, 100.0, 100.0), Rating::Love);
+        spike.bounding_box = None;
+        let mut no_viewport = element_spike("s2", "index.html", ".a", (0.0, 0.0, 100.0, 100.0), Rating::Love);
+        no_viewport.viewport = None;
+
+        assert!(compute_clusters(&[spike, no_viewport], DEFAULT_THRESHOLD).is_empty());
+    }
+
+    #[test]
+    fn test_combined_rating_is_average() {
+        let spikes = vec![
+            element_spike("s1", "index.html", ".a", (0.0, 0.0, 100.0, 100.0), Rating::Love),
+            element_spike("s2", "index.html", ".a", (0.0, 0.0, 100.0, 100.0), Rating::No),
+            element_spike("s3", "index.html", ".a", (0.0, 0.0, 100.0, 100.0), Rating::Like),
+        ];
+
+        let clusters = compute_clusters(&spikes, DEFAULT_THRESHOLD);
+        // (3 + 0 + 2) / 3 = 1.67 -> like
+        assert_eq!(clusters[0].rating, Some(Rating::Like));
+    }
+}
@@ -36,6 +36,10 @@ fn run_internal(json: bool) -> Result<Option<serde_json::Value>> {
                 "endpoint": config.effective_endpoint(),
                 "hosted": config.remote.hosted,
                 "has_token": config.remote.token.is_some()
+            },
+            "viewport": {
+                "mobile_max": config.viewport.mobile_max,
+                "tablet_max": config.viewport.tablet_max
             }
         });
         return Ok(Some(output));
@@ -61,6 +65,10 @@ fn run_internal(json: bool) -> Result<Option<serde_json::Value>> {
         println!("    (not configured)");
     }
     println!();
+    println!("  Viewport:");
+    println!("    mobile_max:    {}px", config.viewport.mobile_max);
+    println!("    tablet_max:    {}px", config.viewport.tablet_max);
+    println!();
     println!("  Widget tag attributes:");
     println!("    {}", config.widget_attributes());
     println!();
@@ -0,0 +1,331 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::identity::load_canonical_spikes;
+use crate::output::print_json;
+use crate::spike::{Rating, Spike, SpikeType};
+
+use super::dedupe::{agree_counts, agree_label};
+use super::export::{contested_elements, is_blocking, split_label};
+
+/// Files kept up to date when no `--file` is given
+pub const DEFAULT_FILES: [&str; 3] = ["AGENTS.md", "CLAUDE.md", ".cursor/rules/spikes.mdc"];
+
+const BEGIN: &str = "<!-- spikes:begin -->";
+const END: &str = "<!-- spikes:end -->";
+/// Entries listed before the rest are summarized as a count
+const MAX_ENTRIES: usize = 50;
+
+/// Cursor needs frontmatter to apply a rule file to every request
+const MDC_FRONTMATTER: &str = "---\ndescription: Open design feedback from Spikes reviewers\nalwaysApply: true\n---\n";
+
+pub struct ContextWriteOptions {
+    /// Files to update (default: `DEFAULT_FILES`)
+    pub files: Vec<PathBuf>,
+    /// Report stale files and fail instead of writing
+    pub check: bool,
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileResult {
+    pub path: String,
+    pub status: FileStatus,
+}
+
+/// Reviewer text with HTML comment delimiters defused, so a comment can't
+/// fake or hide the section markers
+fn escape(text: &str) -> String {
+    text.replace("<!--", "&lt;!--").replace("-->", "--&gt;")
+}
+
+fn entry(spike: &Spike, agreement: usize) -> String {
+    let target = match (&spike.spike_type, &spike.selector) {
+        (SpikeType::Element, Some(selector)) => format!("`{}`", escape(selector)),
+        _ => "page".to_string(),
+    };
+    let mut line = format!("- {} — **{}** from {}", target, spike.rating_str(), escape(&spike.reviewer.name));
+    let comment = escape(&spike.comments.split_whitespace().collect::<Vec<_>>().join(" "));
+    if !comment.is_empty() {
+        line.push_str(&format!(": \"{}\"", comment));
+    }
+    let id: String = spike.id.chars().take(8).collect();
+    if agreement > 0 {
+        line.push_str(&format!(" (`{}`, {})", id, agree_label(agreement)));
+    } else {
+        line.push_str(&format!(" (`{}`)", id));
+    }
+    line.push('\n');
+    line
+}
+
+/// The managed section, markers included. Depends only on the spikes, so
+/// writing twice gives the same file.
+pub fn render_section(spikes: &[Spike]) -> String {
+    let agree = agree_counts(spikes);
+    let mut blocking: Vec<&Spike> = spikes
+        .iter()
+        .filter(|s| is_blocking(s) && s.duplicate_of.is_none())
+        .collect();
+    // By page, then no before meh, then target
+    blocking.sort_by(|a, b| {
+        let rank = |s: &Spike| if s.rating == Some(Rating::No) { 0 } else { 1 };
+        a.page
+            .cmp(&b.page)
+            .then_with(|| rank(a).cmp(&rank(b)))
+            .then_with(|| a.selector.cmp(&b.selector))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    let mut section = format!("{}\n", BEGIN);
+    section.push_str("<!-- Managed by `spikes context write`. Edits between these markers are overwritten. -->\n");
+    section.push_str("## Open design feedback\n\n");
+
+    if blocking.is_empty() {
+        section.push_str("No open meh or no ratings from reviewers.\n");
+    } else {
+        section.push_str(
+            "Reviewers rated these meh or no and they are still open. Keep them in mind when changing these pages. \
+             `spikes show <id>` has the details; `spikes resolve <id>` closes one once it's addressed.\n",
+        );
+        let mut pages: BTreeMap<&str, Vec<&Spike>> = BTreeMap::new();
+        for spike in blocking.iter().take(MAX_ENTRIES) {
+            pages.entry(spike.page.as_str()).or_default().push(spike);
+        }
+        for (page, page_spikes) in &pages {
+            section.push_str(&format!("\n### {}\n\n", escape(page)));
+            for spike in page_spikes {
+                section.push_str(&entry(spike, agree.get(&spike.id).copied().unwrap_or(0)));
+            }
+        }
+        if blocking.len() > MAX_ENTRIES {
+            section.push_str(&format!(
+                "\n…and {} more. Run `spikes list --unresolved` for all of them.\n",
+                blocking.len() - MAX_ENTRIES
+            ));
+        }
+    }
+
+    let contested = contested_elements(spikes);
+    if !contested.is_empty() {
+        section.push_str("\n### Contested\n\nReviewers disagree on these. Ask before changing them:\n\n");
+        for score in &contested {
+            section.push_str(&format!("- `{}` — {}\n", escape(&score.selector), split_label(score)));
+        }
+    }
+
+    section.push_str(END);
+    section.push('\n');
+    section
+}
+
+/// New contents for a file: the managed section replaced in place, or
+/// appended when the file has none yet
+pub fn apply(path: &Path, existing: Option<&str>, section: &str) -> Result<String> {
+    let Some(existing) = existing else {
+        let is_mdc = path.extension().is_some_and(|e| e == "mdc");
+        return Ok(if is_mdc {
+            format!("{}\n{}", MDC_FRONTMATTER, section)
+        } else {
+            section.to_string()
+        });
+    };
+
+    // The end marker that closes the section, not one before it
+    let start = existing.find(BEGIN);
+    let end = match start {
+        Some(start) => existing[start..].find(END).map(|end| start + end),
+        None => existing.find(END),
+    };
+    match (start, end) {
+        (Some(start), Some(end)) => {
+            let mut after = &existing[end + END.len()..];
+            if let Some(rest) = after.strip_prefix('\n') {
+                after = rest;
+            }
+            Ok(format!("{}{}{}", &existing[..start], section, after))
+        }
+        (None, None) => {
+            let mut contents = existing.to_string();
+            if !contents.is_empty() {
+                if !contents.ends_with('\n') {
+                    contents.push('\n');
+                }
+                contents.push('\n');
+            }
+            contents.push_str(section);
+            Ok(contents)
+        }
+        _ => Err(Error::ContextMarkers(path.display().to_string())),
+    }
+}
+
+pub fn write(options: ContextWriteOptions) -> Result<()> {
+    let spikes = load_canonical_spikes()?;
+    let section = render_section(&spikes);
+    let files = if options.files.is_empty() {
+        DEFAULT_FILES.iter().map(PathBuf::from).collect()
+    } else {
+        options.files
+    };
+
+    let mut results = Vec::new();
+    for path in &files {
+        let existing = match fs::read_to_string(path) {
+            Ok(contents) => Some(contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let contents = apply(path, existing.as_deref(), &section)?;
+        let status = match &existing {
+            None => FileStatus::Created,
+            Some(old) if *old == contents => FileStatus::Unchanged,
+            Some(_) => FileStatus::Updated,
+        };
+        if !options.check && status != FileStatus::Unchanged {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, contents)?;
+        }
+        results.push(FileResult {
+            path: path.display().to_string(),
+            status,
+        });
+    }
+
+    let stale: Vec<&str> = results
+        .iter()
+        .filter(|r| r.status != FileStatus::Unchanged)
+        .map(|r| r.path.as_str())
+        .collect();
+
+    if options.json {
+        print_json(&serde_json::json!({
+            "check": options.check,
+            "files": results,
+            "stale": options.check.then_some(&stale),
+        }));
+    } else {
+        for result in &results {
+            let label = match (options.check, result.status) {
+                (_, FileStatus::Unchanged) => "up to date",
+                (true, FileStatus::Created) => "missing",
+                (true, FileStatus::Updated) => "stale",
+                (false, FileStatus::Created) => "created",
+                (false, FileStatus::Updated) => "updated",
+            };
+            println!("{:<12} {}", label, result.path);
+        }
+    }
+
+    if options.check && !stale.is_empty() {
+        return Err(Error::StaleContext(stale.join(", ")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::Reviewer;
+
+    fn spike(id: &str, page: &str, rating: Rating, selector: Option<&str>) -> Spike {
+        Spike {
+            spike_type: if selector.is_some() { SpikeType::Element } else { SpikeType::Page },
+            project_key: "acme".to_string(),
+            reviewer: Reviewer {
+                id: format!("r-{}", id),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: selector.map(String::from),
+            comments: format!("Comment {}", id),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            ..Spike::test(id, page, Some(rating))
+        }
+    }
+
+    #[test]
+    fn test_section_lists_blocking_feedback_by_page() {
+        let mut resolved = spike("s4", "index.html", Rating::No, Some(".old"));
+        resolved.resolved = Some(true);
+        let spikes = vec![
+            spike("s1", "index.html", Rating::Meh, Some(".cta")),
+            spike("s2", "index.html", Rating::No, Some(".hero")),
+            spike("s3", "about.html", Rating::Love, None),
+            resolved,
+        ];
+        let section = render_section(&spikes);
+
+        assert!(section.starts_with(BEGIN) && section.ends_with(&format!("{}\n", END)));
+        let hero = section.find("- `.hero` — **no** from Alice: \"Comment s2\" (`s2`)").unwrap();
+        let cta = section.find("- `.cta` — **meh**").unwrap();
+        assert!(hero < cta, "no comes before meh:\n{}", section);
+        assert!(!section.contains("about.html"));
+        assert!(!section.contains(".old"));
+        assert_eq!(section, render_section(&spikes));
+
+        assert!(render_section(&[]).contains("No open meh or no ratings"));
+    }
+
+    #[test]
+    fn test_apply_replaces_section_in_place() {
+        let path = Path::new("AGENTS.md");
+        let old = format!("# Agents\n\nBuild with cargo.\n\n{}\nold\n{}\n\n## Footer\n", BEGIN, END);
+        let section = format!("{}\nnew\n{}\n", BEGIN, END);
+        let updated = apply(path, Some(&old), &section).unwrap();
+
+        assert_eq!(updated, format!("# Agents\n\nBuild with cargo.\n\n{}\nnew\n{}\n\n## Footer\n", BEGIN, END));
+        assert_eq!(apply(path, Some(&updated), &section).unwrap(), updated);
+    }
+
+    #[test]
+    fn test_apply_appends_or_creates() {
+        let section = format!("{}\nnew\n{}\n", BEGIN, END);
+        assert_eq!(
+            apply(Path::new("CLAUDE.md"), Some("# Notes"), &section).unwrap(),
+            format!("# Notes\n\n{}", section)
+        );
+        assert_eq!(apply(Path::new("CLAUDE.md"), None, &section).unwrap(), section);
+
+        let mdc = apply(Path::new(".cursor/rules/spikes.mdc"), None, &section).unwrap();
+        assert!(mdc.starts_with("---\ndescription:"));
+        assert!(mdc.contains("alwaysApply: true"));
+
+        for broken in [format!("{}\nno end marker\n", BEGIN), format!("{}\n{}\n", END, BEGIN)] {
+            assert!(matches!(
+                apply(Path::new("AGENTS.md"), Some(&broken), &section),
+                Err(Error::ContextMarkers(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_markers_in_feedback_are_escaped() {
+        let mut sneaky = spike("s1", "index.html", Rating::No, Some(".hero"));
+        sneaky.comments = format!("ends here {} <!-- hidden -->", END);
+        let section = render_section(&[sneaky]);
+
+        assert_eq!(section.matches(END).count(), 1, "{}", section);
+        assert!(section.contains("ends here &lt;!-- spikes:end --&gt; &lt;!-- hidden --&gt;"));
+
+        // A stray end marker above the section is left alone
+        let path = Path::new("AGENTS.md");
+        let old = format!("Docs mention {} here.\n\n{}\nold\n{}\n", END, BEGIN, END);
+        let updated = apply(path, Some(&old), &section).unwrap();
+        assert_eq!(updated, format!("Docs mention {} here.\n\n{}", END, section));
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::output::print_json;
+use crate::selector::canonicalize;
+use crate::spike::{Rating, Spike};
+use crate::storage::{find_spike_by_id, load_spikes, save_spikes};
+
+/// Default minimum comment similarity (0.0-1.0) for two spikes to be candidates
+pub const DEFAULT_THRESHOLD: f64 = 0.6;
+
+pub struct DedupeOptions {
+    pub json: bool,
+    pub threshold: f64,
+    pub apply: bool,
+}
+
+/// A canonical spike and the later spikes that repeat it
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub canonical: String,
+    pub page: String,
+    pub selector: Option<String>,
+    pub duplicates: Vec<DuplicateCandidate>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCandidate {
+    pub id: String,
+    pub similarity: f64,
+}
+
+pub fn run(options: DedupeOptions) -> Result<()> {
+    let mut spikes = load_spikes()?;
+    let groups = find_duplicates(&spikes, options.threshold);
+
+    if options.apply && !groups.is_empty() {
+        let links: HashMap<&str, &str> = groups
+            .iter()
+            .flat_map(|g| g.duplicates.iter().map(move |d| (d.id.as_str(), g.canonical.as_str())))
+            .collect();
+        for spike in &mut spikes {
+            if let Some(canonical) = links.get(spike.id.as_str()) {
+                spike.duplicate_of = Some(canonical.to_string());
+            }
+        }
+        save_spikes(&spikes)?;
+    }
+
+    if options.json {
+        print_json(&serde_json::json!({
+            "applied": options.apply,
+            "groups": groups
+        }));
+        return Ok(());
+    }
+
+    if groups.is_empty() {
+        println!("No duplicate candidates found.");
+        return Ok(());
+    }
+
+    for group in &groups {
+        println!(
+            "{} {} — {} duplicate{}",
+            group.page,
+            group.selector.as_deref().unwrap_or("(page)"),
+            group.duplicates.len(),
+            if group.duplicates.len() == 1 { "" } else { "s" }
+        );
+        println!("  canonical  {}", group.canonical);
+        for dup in &group.duplicates {
+            println!("  duplicate  {} ({:.0}% similar)", dup.id, dup.similarity * 100.0);
+        }
+        println!();
+    }
+
+    let total: usize = groups.iter().map(|g| g.duplicates.len()).sum();
+    if options.apply {
+        println!("Linked {} spike{} to their canonical spike.", total, if total == 1 { "" } else { "s" });
+    } else {
+        println!("Run with --apply to link {} spike{} as duplicates.", total, if total == 1 { "" } else { "s" });
+    }
+
+    Ok(())
+}
+
+/// Undo links: a duplicate is unlinked from its canonical spike, and a
+/// canonical spike has all of its duplicates unlinked
+pub fn unlink(id: &str, json: bool) -> Result<()> {
+    let mut spikes = load_spikes()?;
+    let target = find_spike_by_id(&spikes, id)?;
+    let mut unlinked = Vec::new();
+    for spike in &mut spikes {
+        let linked = match &target.duplicate_of {
+            Some(_) => spike.id == target.id,
+            None => spike.duplicate_of.as_deref() == Some(target.id.as_str()),
+        };
+        if linked {
+            spike.duplicate_of = None;
+            unlinked.push(spike.id.clone());
+        }
+    }
+    if !unlinked.is_empty() {
+        save_spikes(&spikes)?;
+    }
+
+    if json {
+        print_json(&serde_json::json!({ "unlinked": unlinked }));
+    } else if unlinked.is_empty() {
+        println!("Spike {} is not linked to any duplicates.", target.id);
+    } else {
+        println!(
+            "Unlinked {} spike{}: {}",
+            unlinked.len(),
+            if unlinked.len() == 1 { "" } else { "s" },
+            unlinked.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Find spikes that repeat an earlier spike on the same page and (canonical)
+/// selector with a rating of the same polarity.
+///
+/// The earliest spike of each group is the canonical one. Spikes that are
+/// already linked, or have no comments, are never candidates, and a `love`
+/// never duplicates a `no` however similar the comments.
+pub fn find_duplicates(spikes: &[Spike], threshold: f64) -> Vec<DuplicateGroup> {
+    let mut candidates: Vec<&Spike> = spikes
+        .iter()
+        .filter(|s| s.duplicate_of.is_none() && !s.comments.trim().is_empty())
+        .collect();
+    candidates.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+    let mut claimed: HashSet<&str> = HashSet::new();
+    let mut groups = Vec::new();
+
+    for (i, canonical) in candidates.iter().enumerate() {
+        if claimed.contains(canonical.id.as_str()) {
+            continue;
+        }
+        let mut duplicates = Vec::new();
+        for other in &candidates[i + 1..] {
+            if claimed.contains(other.id.as_str())
+                || other.page != canonical.page
+                || other.selector.as_deref().map(canonicalize) != canonical.selector.as_deref().map(canonicalize)
+                || polarity(other) != polarity(canonical)
+            {
+                continue;
+            }
+            let similarity = comment_similarity(&canonical.comments, &other.comments);
+            if similarity >= threshold {
+                claimed.insert(other.id.as_str());
+                duplicates.push(DuplicateCandidate {
+                    id: other.id.clone(),
+                    similarity,
+                });
+            }
+        }
+        if !duplicates.is_empty() {
+            groups.push(DuplicateGroup {
+                canonical: canonical.id.clone(),
+                page: canonical.page.clone(),
+                selector: canonical.selector.clone(),
+                duplicates,
+            });
+        }
+    }
+
+    groups
+}
+
+/// Whether a spike is negative, positive, or unrated (`None`)
+fn polarity(spike: &Spike) -> Option<bool> {
+    spike.rating.as_ref().map(Rating::is_negative)
+}
+
+/// Jaccard similarity of the lowercase word sets of two comments.
+///
+/// A comment without words (`"!!!"`) is similar to nothing.
+pub fn comment_similarity(a: &str, b: &str) -> f64 {
+    let a = words(a);
+    let b = words(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(&b).count();
+    shared as f64 / a.union(&b).count() as f64
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Number of other reviewers whose spikes are linked as duplicates of `id`
+pub fn agree_count(spikes: &[Spike], id: &str) -> usize {
+    let canonical_reviewer = spikes.iter().find(|s| s.id == id).map(|s| s.reviewer.id.as_str());
+    spikes
+        .iter()
+        .filter(|s| s.duplicate_of.as_deref() == Some(id))
+        .map(|s| s.reviewer.id.as_str())
+        .filter(|r| Some(*r) != canonical_reviewer)
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Agreement counts for every canonical spike, keyed by spike ID
+pub fn agree_counts(spikes: &[Spike]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for spike in spikes {
+        if let Some(canonical) = &spike.duplicate_of {
+            if !counts.contains_key(canonical) {
+                counts.insert(canonical.clone(), agree_count(spikes, canonical));
+            }
+        }
+    }
+    counts
+}
+
+/// "+N reviewers agree" label
+pub fn agree_label(count: usize) -> String {
+    if count == 1 {
+        "+1 reviewer agrees".to_string()
+    } else {
+        format!("+{} reviewers agree", count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Rating, Reviewer, SpikeType};
+
+    fn spike(id: &str, reviewer: &str, selector: Option<&str>, comments: &str, timestamp: &str) -> Spike {
+        Spike {
+            spike_type: if selector.is_some() { SpikeType::Element } else { SpikeType::Page },
+            url: "http://test/index.html".to_string(),
+            reviewer: Reviewer {
+                id: reviewer.to_string(),
+                name: reviewer.to_string(),
+                email: None,
+            },
+            selector: selector.map(|s| s.to_string()),
+            comments: comments.to_string(),
+            timestamp: timestamp.to_string(),
+            ..Spike::test(id, "index.html", Some(Rating::No))
+        }
+    }
+
+    #[test]
+    fn test_comment_similarity() {
+        assert_eq!(comment_similarity("Font too small", "font too SMALL!"), 1.0);
+        assert!(comment_similarity("Font too small", "The font is too small") >= 0.6);
+        assert!(comment_similarity("Font too small", "Love the colors") < 0.2);
+        assert_eq!(comment_similarity("!!!", "???"), 0.0);
+        assert_eq!(comment_similarity("!!!", "Font too small"), 0.0);
+    }
+
+    #[test]
+    fn test_find_duplicates_same_selector() {
+        let spikes = vec![
+            spike("b", "r2", Some(".hero"), "The font is too small", "2024-01-02T00:00:00Z"),
+            spike("a", "r1", Some(".hero"), "Font too small", "2024-01-01T00:00:00Z"),
+            spike("c", "r3", Some(".hero"), "Love the colors", "2024-01-03T00:00:00Z"),
+            spike("d", "r4", Some(".footer"), "Font too small", "2024-01-04T00:00:00Z"),
+        ];
+
+        let groups = find_duplicates(&spikes, DEFAULT_THRESHOLD);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].canonical, "a", "Earliest spike should be canonical");
+        assert_eq!(groups[0].duplicates.len(), 1);
+        assert_eq!(groups[0].duplicates[0].id, "b");
+    }
+
+    #[test]
+    fn test_find_duplicates_needs_same_polarity() {
+        let mut love = spike("b", "r2", Some(".hero"), "The hero font", "2024-01-02T00:00:00Z");
+        love.rating = Some(Rating::Love);
+        let mut meh = spike("c", "r3", Some(".hero"), "The hero font", "2024-01-03T00:00:00Z");
+        meh.rating = Some(Rating::Meh);
+        let mut unrated = spike("d", "r4", Some(".hero"), "The hero font", "2024-01-04T00:00:00Z");
+        unrated.rating = None;
+        let spikes = vec![spike("a", "r1", Some(".hero"), "The hero font", "2024-01-01T00:00:00Z"), love, meh, unrated];
+
+        let groups = find_duplicates(&spikes, DEFAULT_THRESHOLD);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].canonical, "a");
+        let ids: Vec<&str> = groups[0].duplicates.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["c"], "meh is negative like no; love and unrated are not");
+    }
+
+    #[test]
+    fn test_find_duplicates_skips_linked_and_empty() {
+        let mut linked = spike("b", "r2", None, "Too busy", "2024-01-02T00:00:00Z");
+        linked.duplicate_of = Some("a".to_string());
+        let spikes = vec![
+            spike("a", "r1", None, "Too busy", "2024-01-01T00:00:00Z"),
+            linked,
+            spike("c", "r3", None, "", "2024-01-03T00:00:00Z"),
+            spike("d", "r4", None, "", "2024-01-04T00:00:00Z"),
+            spike("e", "r5", None, "!!!", "2024-01-05T00:00:00Z"),
+            spike("f", "r6", None, "???", "2024-01-06T00:00:00Z"),
+        ];
+
+        assert!(find_duplicates(&spikes, DEFAULT_THRESHOLD).is_empty());
+    }
+
+    #[test]
+    fn test_agree_count_distinct_reviewers() {
+        let mut b = spike("b", "r2", None, "Too busy", "2024-01-02T00:00:00Z");
+        b.duplicate_of = Some("a".to_string());
+        let mut c = spike("c", "r2", None, "Way too busy", "2024-01-03T00:00:00Z");
+        c.duplicate_of = Some("a".to_string());
+        let mut d = spike("d", "r3", None, "Busy", "2024-01-04T00:00:00Z");
+        d.duplicate_of = Some("a".to_string());
+        let mut own = spike("e", "r1", None, "Still busy", "2024-01-05T00:00:00Z");
+        own.duplicate_of = Some("a".to_string());
+        let spikes = vec![spike("a", "r1", None, "Too busy", "2024-01-01T00:00:00Z"), b, c, d, own];
+
+        assert_eq!(agree_count(&spikes, "a"), 2);
+        assert_eq!(agree_counts(&spikes).get("a"), Some(&2));
+        assert_eq!(agree_label(2), "+2 reviewers agree");
+        assert_eq!(agree_label(1), "+1 reviewer agrees");
+    }
+}
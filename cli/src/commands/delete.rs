@@ -4,15 +4,23 @@ use crate::error::Result;
 use crate::output::print_json;
 use crate::storage::{find_spike_by_id, load_spikes, save_spikes};
 
+use super::bulk::{self, BulkAction};
+
 pub struct DeleteOptions {
-    pub id: String,
+    pub id: Option<String>,
+    /// `key=value` filters selecting the spikes to delete instead of an ID
+    pub filters: Vec<String>,
     pub force: bool,
     pub json: bool,
 }
 
 pub fn run(options: DeleteOptions) -> Result<()> {
+    let Some(id) = options.id.as_deref() else {
+        return bulk::run_where(&options.filters, BulkAction::Delete, options.force, options.json);
+    };
+
     let mut spikes = load_spikes()?;
-    let spike = find_spike_by_id(&spikes, &options.id)?;
+    let spike = find_spike_by_id(&spikes, id)?;
     
     // If not --force, prompt for confirmation
     if !options.force {
@@ -22,7 +30,7 @@ pub fn run(options: DeleteOptions) -> Result<()> {
         loop {
             print!(
                 "Delete spike '{}' on page '{}'? [y/N] ",
-                &spike.id[..8.min(spike.id.len())],
+                spike.id.chars().take(8).collect::<String>(),
                 spike.page
             );
             stdout.flush()?;
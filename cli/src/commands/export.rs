@@ -1,9 +1,26 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::io::{self, Write};
 
+use crate::config::{Config, ViewportConfig};
 use crate::error::Result;
-use crate::spike::{Rating, SpikeType};
-use crate::storage::load_spikes;
+use crate::identity::{load_canonical_spikes, load_spikes_matching};
+use crate::pages::Site;
+use crate::spike::{Rating, Spike, SpikeType};
+use crate::summary::Summary;
+
+use super::clusters::{compute_clusters, DEFAULT_THRESHOLD};
+use super::hotspots::{compute_consensus, hotspot_groups, Consensus};
+use super::list::SpikeFilter;
+
+use template::Template;
+
+pub mod columns;
+mod html;
+mod junit;
+mod markdown;
+pub mod pack;
+mod sarif;
+pub mod template;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ExportFormat {
@@ -12,6 +29,12 @@ pub enum ExportFormat {
     Jsonl,
     CursorContext,
     ClaudeContext,
+    Clusters,
+    Brief,
+    Html,
+    Sarif,
+    Junit,
+    Markdown,
 }
 
 impl std::str::FromStr for ExportFormat {
@@ -24,77 +47,112 @@ impl std::str::FromStr for ExportFormat {
             "jsonl" => Ok(ExportFormat::Jsonl),
             "cursor-context" => Ok(ExportFormat::CursorContext),
             "claude-context" => Ok(ExportFormat::ClaudeContext),
+            "clusters" => Ok(ExportFormat::Clusters),
+            "brief" => Ok(ExportFormat::Brief),
+            "html" => Ok(ExportFormat::Html),
+            "sarif" => Ok(ExportFormat::Sarif),
+            "junit" => Ok(ExportFormat::Junit),
+            "markdown" | "md" => Ok(ExportFormat::Markdown),
             _ => Err(format!(
-                "Invalid format: {}. Use json, csv, jsonl, cursor-context, or claude-context",
+                "Invalid format: {}. Use json, csv, jsonl, cursor-context, claude-context, clusters, brief, html, sarif, junit, or markdown",
                 s
             )),
         }
     }
 }
 
-pub fn run(format: ExportFormat) -> Result<()> {
-    let spikes = load_spikes()?;
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub filter: SpikeFilter,
+    /// Render this template (path or name) instead of `format`
+    pub template: Option<String>,
+    /// Print the available templates and exit
+    pub list_templates: bool,
+    /// Trim claude-context to about this many tokens
+    pub max_tokens: Option<usize>,
+    /// CSV columns, in order (default: all of them, see `columns`)
+    pub fields: Vec<String>,
+}
+
+pub fn run(options: ExportOptions) -> Result<()> {
+    if options.list_templates {
+        for (name, origin) in template::available() {
+            println!("{:<24} {}", name, origin);
+        }
+        return Ok(());
+    }
+
+    let filter = options.filter.with_config_breakpoints()?;
+    let spikes: Vec<Spike> = load_canonical_spikes()?
+        .into_iter()
+        .filter(|s| filter.matches(s))
+        .collect();
     let stdout = io::stdout();
     let mut handle = stdout.lock();
 
-    match format {
+    if let Some(name) = &options.template {
+        let template = Template::find(name)?;
+        let context = template::context(&spikes, &Config::load()?.viewport);
+        write!(handle, "{}", template.render(&context)?)?;
+        return Ok(());
+    }
+
+    // Raw formats keep each reviewer as stored so they round-trip through import
+    let stored = || load_spikes_matching(|s| filter.matches(s));
+
+    match options.format {
         ExportFormat::Json => {
-            let json = serde_json::to_string_pretty(&spikes)?;
+            let json = serde_json::to_string_pretty(&stored()?)?;
             writeln!(handle, "{}", json)?;
         }
         ExportFormat::Jsonl => {
-            for spike in &spikes {
+            for spike in &stored()? {
                 let json = serde_json::to_string(spike)?;
                 writeln!(handle, "{}", json)?;
             }
         }
         ExportFormat::Csv => {
+            let columns = columns::select(&options.fields)?;
             let mut wtr = csv::Writer::from_writer(handle);
-            wtr.write_record([
-                "id",
-                "type",
-                "project_key",
-                "page",
-                "url",
-                "reviewer_id",
-                "reviewer_name",
-                "selector",
-                "element_text",
-                "rating",
-                "comments",
-                "timestamp",
-                "viewport_width",
-                "viewport_height",
-            ])?;
-
-            for spike in &spikes {
-                wtr.write_record([
-                    &spike.id,
-                    spike.type_str(),
-                    &spike.project_key,
-                    &spike.page,
-                    &spike.url,
-                    &spike.reviewer.id,
-                    &spike.reviewer.name,
-                    spike.selector.as_deref().unwrap_or(""),
-                    spike.element_text.as_deref().unwrap_or(""),
-                    spike.rating_str(),
-                    &spike.comments,
-                    &spike.timestamp,
-                    &spike.viewport.as_ref().map(|v| v.width.to_string()).unwrap_or_default(),
-                    &spike.viewport.as_ref().map(|v| v.height.to_string()).unwrap_or_default(),
-                ])?;
+            wtr.write_record(columns.iter().map(|c| &c.name))?;
+            for spike in &stored()? {
+                wtr.write_record(columns::cells(spike, &columns))?;
             }
             wtr.flush()?;
         }
         ExportFormat::CursorContext => {
-            let markdown = generate_cursor_context(&spikes);
+            let markdown = generate_cursor_context(&spikes, &Config::load()?.viewport);
             write!(handle, "{}", markdown)?;
         }
         ExportFormat::ClaudeContext => {
-            let markdown = generate_claude_context(&spikes);
+            let markdown = match options.max_tokens {
+                Some(max) => pack::generate_context_pack(&spikes, max),
+                None => generate_claude_context(&spikes, &Config::load()?.viewport),
+            };
             write!(handle, "{}", markdown)?;
         }
+        ExportFormat::Clusters => {
+            let clusters = compute_clusters(&spikes, DEFAULT_THRESHOLD);
+            let json = serde_json::to_string_pretty(&clusters)?;
+            writeln!(handle, "{}", json)?;
+        }
+        ExportFormat::Brief => {
+            write!(handle, "{}", generate_brief(&spikes))?;
+        }
+        ExportFormat::Html => {
+            write!(handle, "{}", html::generate_html(&spikes))?;
+        }
+        ExportFormat::Sarif => {
+            let site = Site::load(&Config::load()?.site_dir()).ok();
+            let json = serde_json::to_string_pretty(&sarif::generate_sarif(&spikes, site.as_ref()))?;
+            writeln!(handle, "{}", json)?;
+        }
+        ExportFormat::Junit => {
+            write!(handle, "{}", junit::generate_junit(&spikes))?;
+        }
+        ExportFormat::Markdown => {
+            write!(handle, "{}", markdown::generate_markdown(&spikes, &filter.describe()))?;
+        }
     }
 
     Ok(())
@@ -107,125 +165,10 @@ pub fn run(format: ExportFormat) -> Result<()> {
 /// Generate Cursor-compatible context markdown.
 ///
 /// Sections: blocking issues, hotspots, element-specific notes.
-/// Punk/zine energy in headers and taglines.
-fn generate_cursor_context(spikes: &[crate::spike::Spike]) -> String {
-    let mut output = String::new();
-
-    // Metadata header
-    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
-    let project = spikes
-        .first()
-        .map(|s| s.project_key.as_str())
-        .unwrap_or("unknown");
-
-    output.push_str("# 🎯 FEEDBACK INTEL\n\n");
-    output.push_str("_Your roadmap to glory or ruin._\n\n");
-    output.push_str(&format!("**Project:** {}\n", project));
-    output.push_str(&format!("**Total Spikes:** {}\n", spikes.len()));
-    output.push_str(&format!("**Generated:** {}\n\n", timestamp));
-    output.push_str("---\n\n");
-
-    // Blocking issues section
-    output.push_str("## 🚫 BLOCKING ISSUES\n\n");
-    output.push_str("_The vibes are off. Fix these before shipping._\n\n");
-
-    let blocking: Vec<&crate::spike::Spike> = spikes
-        .iter()
-        .filter(|s| is_blocking(s))
-        .collect();
-
-    if blocking.is_empty() {
-        output.push_str("✨ **Clean slate!** No blocking issues found.\n\n");
-    } else {
-        for spike in &blocking {
-            output.push_str(&format!("### [{}] {} on `{}`\n", 
-                &spike.id.chars().take(8).collect::<String>(),
-                spike.type_str(),
-                spike.page
-            ));
-            output.push_str(&format!("- **Rating:** {}\n", spike.rating_str()));
-            if spike.spike_type == SpikeType::Element {
-                if let Some(selector) = &spike.selector {
-                    output.push_str(&format!("- **Selector:** `{}`\n", selector));
-                }
-            }
-            if !spike.comments.is_empty() {
-                output.push_str(&format!("- **Comment:** \"{}\"\n", spike.comments));
-            }
-            output.push_str(&format!("- **Reviewer:** {}\n", spike.reviewer.name));
-            output.push('\n');
-        }
-    }
-
-    output.push_str("---\n\n");
-
-    // Hotspots section
-    output.push_str("## 🔥 FEEDBACK HOTSPOTS\n\n");
-    output.push_str("_Where the action is. Elements with the most heat._\n\n");
-
-    let hotspots = compute_hotspots(spikes);
-    if hotspots.is_empty() {
-        output.push_str("📊 **No element feedback.** Nothing's hot yet.\n\n");
-    } else {
-        for (i, (selector, count)) in hotspots.iter().enumerate() {
-            output.push_str(&format!(
-                "{}. `{}` — **{} spike{}**\n",
-                i + 1,
-                selector,
-                count,
-                if *count == 1 { "" } else { "s" }
-            ));
-        }
-        output.push('\n');
-    }
-
-    output.push_str("---\n\n");
-
-    // Element-specific notes section
-    output.push_str("## 📝 ELEMENT-SPECIFIC NOTES\n\n");
-    output.push_str("_Deep cuts on specific elements. Grouped by selector._\n\n");
-
-    let element_spikes: Vec<&crate::spike::Spike> = spikes
-        .iter()
-        .filter(|s| s.spike_type == SpikeType::Element)
-        .collect();
-
-    if element_spikes.is_empty() {
-        output.push_str("🔍 **No element feedback recorded.**\n\n");
-    } else {
-        // Group by selector
-        let mut by_selector: HashMap<String, Vec<&crate::spike::Spike>> = HashMap::new();
-        for spike in &element_spikes {
-            let selector = spike.selector.clone().unwrap_or_default();
-            by_selector.entry(selector).or_default().push(*spike);
-        }
-
-        // Sort selectors alphabetically for consistent output
-        let mut selectors: Vec<String> = by_selector.keys().cloned().collect();
-        selectors.sort();
-
-        for selector in &selectors {
-            let spikes_for_selector = &by_selector[selector];
-            output.push_str(&format!("### `{}`\n\n", selector));
-
-            for spike in spikes_for_selector {
-                let status = if spike.is_resolved() { "✅" } else { "⏳" };
-                output.push_str(&format!(
-                    "- {} **{}** — \"{}\" _({})_\n",
-                    status,
-                    spike.rating_str(),
-                    spike.comments,
-                    spike.reviewer.name
-                ));
-            }
-            output.push('\n');
-        }
-    }
-
-    output.push_str("---\n\n");
-    output.push_str("_Generated by [spikes](https://spikes.sh) — feedback that talks back._\n");
-
-    output
+/// Punk/zine energy in headers and taglines. Rendered from the bundled
+/// `cursor-context` template.
+fn generate_cursor_context(spikes: &[crate::spike::Spike], breakpoints: &ViewportConfig) -> String {
+    render_bundled("cursor-context", spikes, breakpoints)
 }
 
 // ============================================================================
@@ -235,132 +178,71 @@ fn generate_cursor_context(spikes: &[crate::spike::Spike]) -> String {
 /// Generate Claude-compatible context markdown.
 ///
 /// Sections: critical issues, feedback hotspots, element feedback.
-/// Distinct punk/zine tone from cursor-context.
-fn generate_claude_context(spikes: &[crate::spike::Spike]) -> String {
+/// Distinct punk/zine tone from cursor-context. Rendered from the bundled
+/// `claude-context` template.
+fn generate_claude_context(spikes: &[crate::spike::Spike], breakpoints: &ViewportConfig) -> String {
+    render_bundled("claude-context", spikes, breakpoints)
+}
+
+// ============================================================================
+// Brief Format
+// ============================================================================
+
+/// Generate a compact markdown digest: one summary line per page and per
+/// element, with rating mix, keywords and a representative quote.
+fn generate_brief(spikes: &[Spike]) -> String {
     let mut output = String::new();
 
-    // Metadata header
-    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
     let project = spikes
         .first()
         .map(|s| s.project_key.as_str())
         .unwrap_or("unknown");
-
-    output.push_str("# ⚡ SPIKES FEEDBACK REPORT\n\n");
-    output.push_str("_The raw truth, served fresh._\n\n");
-    output.push_str(&format!("**Project:** {}\n", project));
-    output.push_str(&format!("**Total Feedback Items:** {}\n", spikes.len()));
-    output.push_str(&format!("**Generated:** {}\n\n", timestamp));
-    output.push_str("---\n\n");
-
-    // Critical issues section
-    output.push_str("## ⚠️ CRITICAL ISSUES\n\n");
-    output.push_str("_Unresolved problems demanding attention. The must-fix list._\n\n");
-
-    let blocking: Vec<&crate::spike::Spike> = spikes
-        .iter()
-        .filter(|s| is_blocking(s))
-        .collect();
-
-    if blocking.is_empty() {
-        output.push_str("**✅ All clear.** No critical issues blocking progress.\n\n");
-    } else {
-        output.push_str(&format!("**{} critical issue{} found:**\n\n", 
-            blocking.len(),
-            if blocking.len() == 1 { "" } else { "s" }
-        ));
-
-        for spike in &blocking {
-            output.push_str(&format!("### ID: `{}`\n\n", 
-                &spike.id.chars().take(8).collect::<String>()
-            ));
-            output.push_str(&format!("- **Type:** {} on page `{}`\n", 
-                spike.type_str(),
-                spike.page
-            ));
-            output.push_str(&format!("- **Rating:** {} (negative)\n", spike.rating_str()));
-            if spike.spike_type == SpikeType::Element {
-                if let Some(selector) = &spike.selector {
-                    output.push_str(&format!("- **Target:** `{}`\n", selector));
-                }
-            }
-            if !spike.comments.is_empty() {
-                output.push_str(&format!("- **Feedback:** \"{}\"\n", spike.comments));
-            }
-            output.push_str(&format!("- **From:** {}\n", spike.reviewer.name));
-            output.push('\n');
+    let unresolved = spikes.iter().filter(|s| !s.is_resolved()).count();
+    let blocking = spikes.iter().filter(|s| is_blocking(s)).count();
+
+    output.push_str(&format!("# Spikes brief: {}\n\n", project));
+    output.push_str(&format!(
+        "{} spike{}, {} unresolved, {} blocking.\n\n",
+        spikes.len(),
+        if spikes.len() == 1 { "" } else { "s" },
+        unresolved,
+        blocking
+    ));
+
+    if spikes.is_empty() {
+        output.push_str("No feedback yet.\n");
+        return output;
+    }
+
+    let mut pages: BTreeMap<&str, Vec<&Spike>> = BTreeMap::new();
+    for spike in spikes.iter().filter(|s| s.spike_type == SpikeType::Page) {
+        pages.entry(spike.page.as_str()).or_default().push(spike);
+    }
+    if !pages.is_empty() {
+        output.push_str("## Pages\n\n");
+        for (page, page_spikes) in &pages {
+            output.push_str(&format!("- `{}`: {}\n", page, Summary::of(page_spikes).line()));
         }
+        output.push('\n');
     }
 
-    output.push_str("---\n\n");
-
-    // Hotspots section
-    output.push_str("## 📊 FEEDBACK HOTSPOTS\n\n");
-    output.push_str("_Where reviewers clustered. The conversation starters._\n\n");
-
-    let hotspots = compute_hotspots(spikes);
-    if hotspots.is_empty() {
-        output.push_str("**No element hotspots.** Reviewers haven't targeted specific elements yet.\n\n");
-    } else {
-        output.push_str("**Top feedback targets:**\n\n");
-        for (i, (selector, count)) in hotspots.iter().enumerate() {
+    let groups = hotspot_groups(spikes);
+    if !groups.is_empty() {
+        output.push_str("## Elements\n\n");
+        for group in &groups {
+            let mut on: Vec<&str> = group.spikes.iter().map(|s| s.page.as_str()).collect();
+            on.sort();
+            on.dedup();
             output.push_str(&format!(
-                "{}. `{}` — {} feedback item{}\n",
-                i + 1,
-                selector,
-                count,
-                if *count == 1 { "" } else { "s" }
+                "- `{}` on {}: {}\n",
+                group.selector,
+                on.join(", "),
+                Summary::of(&group.spikes).line()
             ));
         }
         output.push('\n');
     }
 
-    output.push_str("---\n\n");
-
-    // Element feedback section
-    output.push_str("## 🔍 ELEMENT FEEDBACK\n\n");
-    output.push_str("_Granular feedback on specific components. Organized by selector._\n\n");
-
-    let element_spikes: Vec<&crate::spike::Spike> = spikes
-        .iter()
-        .filter(|s| s.spike_type == SpikeType::Element)
-        .collect();
-
-    if element_spikes.is_empty() {
-        output.push_str("**No element-level feedback recorded.**\n\n");
-    } else {
-        // Group by selector
-        let mut by_selector: HashMap<String, Vec<&crate::spike::Spike>> = HashMap::new();
-        for spike in &element_spikes {
-            let selector = spike.selector.clone().unwrap_or_default();
-            by_selector.entry(selector).or_default().push(*spike);
-        }
-
-        // Sort selectors alphabetically
-        let mut selectors: Vec<String> = by_selector.keys().cloned().collect();
-        selectors.sort();
-
-        for selector in &selectors {
-            let spikes_for_selector = &by_selector[selector];
-            output.push_str(&format!("### Selector: `{}`\n\n", selector));
-
-            for spike in spikes_for_selector {
-                let resolved_marker = if spike.is_resolved() { "[RESOLVED] " } else { "" };
-                output.push_str(&format!(
-                    "- {}**{}** from {}: \"{}\"\n",
-                    resolved_marker,
-                    spike.rating_str(),
-                    spike.reviewer.name,
-                    spike.comments
-                ));
-            }
-            output.push('\n');
-        }
-    }
-
-    output.push_str("---\n\n");
-    output.push_str("_Spikes — structured feedback for the modern builder._\n");
-
     output
 }
 
@@ -368,8 +250,30 @@ fn generate_claude_context(spikes: &[crate::spike::Spike]) -> String {
 // Helper Functions
 // ============================================================================
 
+fn render_bundled(name: &str, spikes: &[Spike], breakpoints: &ViewportConfig) -> String {
+    Template::bundled(name)
+        .expect("bundled template exists")
+        .render(&template::context(spikes, breakpoints))
+        .expect("bundled template renders")
+}
+
+/// Polarizing elements, least agreement first
+pub fn contested_elements(spikes: &[crate::spike::Spike]) -> Vec<Consensus> {
+    compute_consensus(spikes).into_iter().filter(|c| c.polarizing).collect()
+}
+
+/// The contested element a spike belongs to, if any
+pub fn contested_for<'a>(contested: &'a [Consensus], id: &str) -> Option<&'a Consensus> {
+    contested.iter().find(|c| c.spike_ids.iter().any(|s| s == id))
+}
+
+/// "2 love/like vs 2 meh/no"
+pub fn split_label(score: &Consensus) -> String {
+    format!("{} love/like vs {} meh/no", score.positive, score.negative)
+}
+
 /// Check if a spike is blocking (unresolved with meh/no rating)
-fn is_blocking(spike: &crate::spike::Spike) -> bool {
+pub fn is_blocking(spike: &crate::spike::Spike) -> bool {
     !spike.is_resolved()
         && matches!(
             spike.rating,
@@ -377,23 +281,6 @@ fn is_blocking(spike: &crate::spike::Spike) -> bool {
         )
 }
 
-/// Compute hotspots: element-type spikes counted by selector, sorted descending
-fn compute_hotspots(spikes: &[crate::spike::Spike]) -> Vec<(String, usize)> {
-    let mut counts: HashMap<String, usize> = HashMap::new();
-
-    for spike in spikes {
-        if spike.spike_type == SpikeType::Element {
-            if let Some(selector) = &spike.selector {
-                *counts.entry(selector.clone()).or_insert(0) += 1;
-            }
-        }
-    }
-
-    let mut hotspots: Vec<(String, usize)> = counts.into_iter().collect();
-    hotspots.sort_by_key(|item| std::cmp::Reverse(item.1));
-    hotspots
-}
-
 // ============================================================================
 // Tests
 // ============================================================================
@@ -401,6 +288,7 @@ fn compute_hotspots(spikes: &[crate::spike::Spike]) -> Vec<(String, usize)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::hotspots::compute_hotspots;
     use crate::spike::{Rating, Reviewer, Spike, SpikeType, Viewport};
 
     // Helper to create a test spike
@@ -422,8 +310,10 @@ mod tests {
             reviewer: Reviewer {
                 id: "r1".to_string(),
                 name: "TestReviewer".to_string(),
+                email: None,
             },
             selector: selector.map(|s| s.to_string()),
+            xpath: None,
             element_text: None,
             bounding_box: None,
             rating,
@@ -439,6 +329,13 @@ mod tests {
             } else {
                 None
             },
+            duplicate_of: None,
+            stale: None,
+            tags: None,
+            assignee: None,
+            round: None,
+            page_hash: None,
+            issue: None,
         }
     }
 
@@ -486,6 +383,11 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_clusters_format() {
+        assert_eq!("clusters".parse::<ExportFormat>().unwrap(), ExportFormat::Clusters);
+    }
+
     #[test]
     fn test_invalid_format_lists_all_five() {
         let result = "invalid".parse::<ExportFormat>();
@@ -496,6 +398,12 @@ mod tests {
         assert!(err.contains("jsonl"), "Error should list jsonl format");
         assert!(err.contains("cursor-context"), "Error should list cursor-context format");
         assert!(err.contains("claude-context"), "Error should list claude-context format");
+        assert!(err.contains("clusters"), "Error should list clusters format");
+        assert!(err.contains("brief"), "Error should list brief format");
+        assert!(err.contains("html"), "Error should list html format");
+        assert!(err.contains("sarif"), "Error should list sarif format");
+        assert!(err.contains("junit"), "Error should list junit format");
+        assert!(err.contains("markdown"), "Error should list markdown format");
     }
 
     // ========================================
@@ -595,7 +503,7 @@ mod tests {
     #[test]
     fn test_cursor_context_empty_state() {
         let spikes = vec![];
-        let markdown = generate_cursor_context(&spikes);
+        let markdown = generate_cursor_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("# 🎯 FEEDBACK INTEL"));
         assert!(markdown.contains("No blocking issues"));
@@ -609,7 +517,7 @@ mod tests {
             create_spike("s1", SpikeType::Page, "index.html", Some(Rating::Love), None, false, "Amazing"),
             create_spike("s2", SpikeType::Page, "about.html", Some(Rating::Like), None, false, "Good"),
         ];
-        let markdown = generate_cursor_context(&spikes);
+        let markdown = generate_cursor_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("Clean slate!"));
         assert!(markdown.contains("No blocking issues"));
@@ -622,7 +530,7 @@ mod tests {
             create_spike("s2", SpikeType::Page, "about.html", Some(Rating::Meh), None, false, "Needs work"),
             create_spike("s3", SpikeType::Element, "index.html", Some(Rating::No), Some(".button"), false, "Broken"),
         ];
-        let markdown = generate_cursor_context(&spikes);
+        let markdown = generate_cursor_context(&spikes, &ViewportConfig::default());
 
         // Blocking section should have meh and no ratings
         assert!(markdown.contains("BLOCKING ISSUES"));
@@ -641,7 +549,7 @@ mod tests {
             create_spike("s1", SpikeType::Page, "index.html", Some(Rating::No), None, true, "Fixed now"),
             create_spike("s2", SpikeType::Page, "about.html", Some(Rating::Meh), None, true, "Resolved"),
         ];
-        let markdown = generate_cursor_context(&spikes);
+        let markdown = generate_cursor_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("Clean slate!"));
         assert!(markdown.contains("No blocking issues"));
@@ -654,7 +562,7 @@ mod tests {
             create_spike("s2", SpikeType::Element, "index.html", Some(Rating::No), Some(".hero"), false, "Hero broken"),
             create_spike("s3", SpikeType::Element, "about.html", Some(Rating::Like), Some(".footer"), false, "Nice footer"),
         ];
-        let markdown = generate_cursor_context(&spikes);
+        let markdown = generate_cursor_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("ELEMENT-SPECIFIC NOTES"));
         assert!(markdown.contains("### `.hero`"));
@@ -671,7 +579,7 @@ mod tests {
             create_spike("s2", SpikeType::Element, "index.html", Some(Rating::Like), Some(".hero"), false, "2"),
             create_spike("s3", SpikeType::Element, "index.html", Some(Rating::No), Some(".footer"), false, "3"),
         ];
-        let markdown = generate_cursor_context(&spikes);
+        let markdown = generate_cursor_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("FEEDBACK HOTSPOTS"));
         assert!(markdown.contains("`.hero` — **2 spikes**"));
@@ -683,7 +591,7 @@ mod tests {
         let spikes = vec![
             create_spike("s1", SpikeType::Page, "index.html", Some(Rating::No), None, false, "Bad"),
         ];
-        let markdown = generate_cursor_context(&spikes);
+        let markdown = generate_cursor_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("vibes are off"));
         assert!(markdown.contains("Where the action is"));
@@ -697,7 +605,7 @@ mod tests {
     #[test]
     fn test_claude_context_empty_state() {
         let spikes = vec![];
-        let markdown = generate_claude_context(&spikes);
+        let markdown = generate_claude_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("# ⚡ SPIKES FEEDBACK REPORT"));
         assert!(markdown.contains("No critical issues"));
@@ -711,7 +619,7 @@ mod tests {
             create_spike("s1", SpikeType::Page, "index.html", Some(Rating::Love), None, false, "Amazing"),
             create_spike("s2", SpikeType::Page, "about.html", Some(Rating::Like), None, false, "Good"),
         ];
-        let markdown = generate_claude_context(&spikes);
+        let markdown = generate_claude_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("All clear"));
         assert!(markdown.contains("No critical issues"));
@@ -724,7 +632,7 @@ mod tests {
             create_spike("s2", SpikeType::Page, "about.html", Some(Rating::Meh), None, false, "Needs work"),
             create_spike("s3", SpikeType::Element, "index.html", Some(Rating::No), Some(".button"), false, "Broken"),
         ];
-        let markdown = generate_claude_context(&spikes);
+        let markdown = generate_claude_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("CRITICAL ISSUES"));
         assert!(markdown.contains("about.html"));
@@ -737,8 +645,8 @@ mod tests {
         let spikes = vec![
             create_spike("s1", SpikeType::Page, "index.html", Some(Rating::No), None, false, "Bad"),
         ];
-        let cursor_md = generate_cursor_context(&spikes);
-        let claude_md = generate_claude_context(&spikes);
+        let cursor_md = generate_cursor_context(&spikes, &ViewportConfig::default());
+        let claude_md = generate_claude_context(&spikes, &ViewportConfig::default());
 
         // Different main headers
         assert!(cursor_md.contains("# 🎯 FEEDBACK INTEL"));
@@ -755,7 +663,7 @@ mod tests {
             create_spike("s1", SpikeType::Element, "index.html", Some(Rating::Love), Some(".hero"), true, "Fixed"),
             create_spike("s2", SpikeType::Element, "index.html", Some(Rating::No), Some(".hero"), false, "Broken"),
         ];
-        let markdown = generate_claude_context(&spikes);
+        let markdown = generate_claude_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("[RESOLVED]"));
         assert!(markdown.contains("Fixed"));
@@ -767,13 +675,105 @@ mod tests {
         let spikes = vec![
             create_spike("s1", SpikeType::Page, "index.html", Some(Rating::No), None, false, "Bad"),
         ];
-        let markdown = generate_claude_context(&spikes);
+        let markdown = generate_claude_context(&spikes, &ViewportConfig::default());
 
         assert!(markdown.contains("raw truth"));
         assert!(markdown.contains("demanding attention"));
         assert!(markdown.contains("Where reviewers clustered"));
     }
 
+    #[test]
+    fn test_context_exports_fold_duplicates_into_agreement() {
+        let mut dup1 = create_spike("s2", SpikeType::Element, "index.html", Some(Rating::No), Some(".hero"), false, "Too small");
+        dup1.duplicate_of = Some("s1".to_string());
+        dup1.reviewer.id = "r2".to_string();
+        let mut dup2 = create_spike("s3", SpikeType::Element, "index.html", Some(Rating::No), Some(".hero"), false, "Way too small");
+        dup2.duplicate_of = Some("s1".to_string());
+        dup2.reviewer.id = "r3".to_string();
+        let spikes = vec![
+            create_spike("s1", SpikeType::Element, "index.html", Some(Rating::No), Some(".hero"), false, "Font too small"),
+            dup1,
+            dup2,
+        ];
+
+        let breakpoints = ViewportConfig::default();
+        for markdown in [generate_cursor_context(&spikes, &breakpoints), generate_claude_context(&spikes, &breakpoints)] {
+            assert!(markdown.contains("+2 reviewers agree"));
+            assert!(!markdown.contains("### [s2]"));
+            assert!(!markdown.contains("### ID: `s2`"));
+        }
+        assert!(generate_claude_context(&spikes, &breakpoints).contains("**1 critical issue found:**"));
+    }
+
+    #[test]
+    fn test_context_exports_flag_contested_elements() {
+        let mut spikes = vec![
+            create_spike("s1", SpikeType::Element, "index.html", Some(Rating::Love), Some(".hero"), false, "Bold"),
+            create_spike("s2", SpikeType::Element, "index.html", Some(Rating::No), Some(".hero"), false, "Too loud"),
+        ];
+        spikes[1].reviewer.id = "r2".to_string();
+        let breakpoints = ViewportConfig::default();
+
+        let cursor_md = generate_cursor_context(&spikes, &breakpoints);
+        assert!(cursor_md.contains("## ⚔️ CONTESTED"));
+        assert!(cursor_md.contains("- `.hero` — 1 love/like vs 1 meh/no (agreement 0%)"));
+        assert!(cursor_md.contains("- **Contested:** 1 love/like vs 1 meh/no"));
+
+        let claude_md = generate_claude_context(&spikes, &breakpoints);
+        assert!(claude_md.contains("| `.hero` | 2 | 1 love/like vs 1 meh/no | 0% |"));
+
+        spikes[0].rating = Some(Rating::Meh);
+        let claude_md = generate_claude_context(&spikes, &breakpoints);
+        assert!(claude_md.contains("**No contested elements.**"));
+        assert!(!claude_md.contains("- **Contested:**"));
+    }
+
+    #[test]
+    fn test_context_exports_break_down_by_viewport() {
+        let mut phone = create_spike("s2", SpikeType::Page, "index.html", Some(Rating::No), None, false, "Cramped");
+        phone.viewport = Some(Viewport { width: 390, height: 844 });
+        let spikes = vec![
+            create_spike("s1", SpikeType::Page, "index.html", Some(Rating::Love), None, false, "Good"),
+            phone,
+        ];
+        let breakpoints = ViewportConfig::default();
+
+        let cursor_md = generate_cursor_context(&spikes, &breakpoints);
+        assert!(cursor_md.contains("## 📱 VIEWPORT BREAKDOWN"));
+        assert!(cursor_md.contains("- **mobile** (≤767px) — 1 spike, 1 meh/no"));
+        assert!(cursor_md.contains("- **desktop** (≥1024px) — 1 spike, 0 meh/no"));
+
+        let claude_md = generate_claude_context(&spikes, &breakpoints);
+        assert!(claude_md.contains("| mobile | ≤767px | 1 | 1 | 0.00 (no) |"));
+
+        let mut no_viewports = spikes.clone();
+        for spike in &mut no_viewports {
+            spike.viewport = None;
+        }
+        assert!(!generate_claude_context(&no_viewports, &breakpoints).contains("BY VIEWPORT"));
+    }
+
+    // ========================================
+    // brief format tests
+    // ========================================
+
+    #[test]
+    fn test_brief_summarizes_pages_and_elements() {
+        let spikes = vec![
+            create_spike("s1", SpikeType::Element, "index.html", Some(Rating::No), Some(".hero"), false, "Headline font too small"),
+            create_spike("s2", SpikeType::Element, "index.html", Some(Rating::Meh), Some(".hero"), false, "Small headline font"),
+            create_spike("s3", SpikeType::Element, "index.html", Some(Rating::Love), Some(".cta"), true, "Great button"),
+            create_spike("s4", SpikeType::Page, "about.html", Some(Rating::Like), None, false, "Nice story"),
+        ];
+        let brief = generate_brief(&spikes);
+
+        assert!(brief.contains("4 spikes, 3 unresolved, 2 blocking."));
+        assert!(brief.contains("- `about.html`: 1 spike (1 like) · \"Nice story\""));
+        assert!(brief.contains("- `.hero` on index.html: 2 spikes (1 no, 1 meh) · font, headline, small · \"Headline font too small\""));
+        assert!(brief.find("`.hero`").unwrap() < brief.find("`.cta`").unwrap());
+        assert!(generate_brief(&[]).contains("No feedback yet."));
+    }
+
     // ========================================
     // Metadata tests
     // ========================================
@@ -784,8 +784,8 @@ mod tests {
             create_spike("s1", SpikeType::Page, "index.html", Some(Rating::Love), None, false, "Good"),
         ];
 
-        let cursor_md = generate_cursor_context(&spikes);
-        let claude_md = generate_claude_context(&spikes);
+        let cursor_md = generate_cursor_context(&spikes, &ViewportConfig::default());
+        let claude_md = generate_claude_context(&spikes, &ViewportConfig::default());
 
         // Both should have project, count, timestamp
         assert!(cursor_md.contains("**Project:**"));
@@ -803,7 +803,7 @@ mod tests {
             create_spike("s1", SpikeType::Page, "index.html", Some(Rating::Love), None, false, "Good"),
         ];
 
-        let cursor_md = generate_cursor_context(&spikes);
+        let cursor_md = generate_cursor_context(&spikes, &ViewportConfig::default());
         assert!(cursor_md.contains("test-project"));
     }
 }
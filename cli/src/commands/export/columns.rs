@@ -0,0 +1,250 @@
+//! Flat, one row per spike: the columns of `export --format csv`, which
+//! `spikes import` reads back.
+//!
+//! Columns come from the JSON schema of `Spike`, in the order it declares its
+//! fields. Nested objects are flattened by joining their snake_case keys with
+//! `_`, so `reviewer.name` is `reviewer_name` and `boundingBox.x` is
+//! `bounding_box_x`. Lists (tags) are JSON arrays, and an empty cell is an
+//! absent value.
+
+use schemars::schema_for;
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::spike::Spike;
+
+/// How a cell is read back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Text,
+    Number,
+    Bool,
+    List,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    /// CSV header, e.g. `reviewer_name`
+    pub name: String,
+    /// Keys leading to the value in the spike's JSON, e.g. `["reviewer", "name"]`
+    pub path: Vec<String>,
+    pub kind: Kind,
+}
+
+/// `projectKey` → `project_key`
+fn snake_case(key: &str) -> String {
+    let mut name = String::new();
+    for c in key.chars() {
+        if c.is_uppercase() {
+            name.push('_');
+        }
+        name.extend(c.to_lowercase());
+    }
+    name
+}
+
+/// Follow `$ref`s and drop the `null` of optional fields
+fn resolve<'a>(schema: &'a Value, defs: &'a Value) -> &'a Value {
+    if let Some(name) = schema["$ref"].as_str().and_then(|r| r.rsplit('/').next()) {
+        return resolve(&defs[name], defs);
+    }
+    match schema["anyOf"].as_array() {
+        Some(variants) => variants
+            .iter()
+            .find(|v| v["type"] != "null")
+            .map_or(schema, |v| resolve(v, defs)),
+        None => schema,
+    }
+}
+
+/// Push a column for every leaf of `schema`, in declaration order
+fn leaves(schema: &Value, defs: &Value, path: Vec<String>, out: &mut Vec<Column>) {
+    let schema = resolve(schema, defs);
+    if let Some(properties) = schema["properties"].as_object() {
+        for (key, property) in properties {
+            let mut path = path.clone();
+            path.push(key.clone());
+            leaves(property, defs, path, out);
+        }
+        return;
+    }
+    let types: Vec<&str> = match &schema["type"] {
+        Value::String(t) => vec![t.as_str()],
+        Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let kind = if types.contains(&"array") {
+        Kind::List
+    } else if types.contains(&"integer") || types.contains(&"number") {
+        Kind::Number
+    } else if types.contains(&"boolean") {
+        Kind::Bool
+    } else {
+        Kind::Text
+    };
+    out.push(Column {
+        name: path.iter().map(|key| snake_case(key)).collect::<Vec<_>>().join("_"),
+        path,
+        kind,
+    });
+}
+
+/// Every column, in `Spike` field order
+pub fn columns() -> Vec<Column> {
+    let schema = schema_for!(Spike).to_value();
+    let mut columns = Vec::new();
+    leaves(&schema, &schema["$defs"], Vec::new(), &mut columns);
+    columns
+}
+
+/// Lowercase letters and digits only, so "Reviewer Name", "reviewerName"
+/// and "reviewer_name" all match the same column
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// The column with this name, ignoring case, spaces and underscores
+pub fn find(name: &str) -> Option<Column> {
+    let name = normalize(name);
+    columns().into_iter().find(|c| normalize(&c.name) == name)
+}
+
+/// Columns for `--fields`, in the order given. A nested field such as
+/// `reviewer` or `bounding_box` selects all of its columns.
+pub fn select(fields: &[String]) -> Result<Vec<Column>> {
+    let all = columns();
+    if fields.is_empty() {
+        return Ok(all);
+    }
+    let mut selected: Vec<Column> = Vec::new();
+    for field in fields {
+        let name = normalize(field);
+        let mut matches: Vec<&Column> = all.iter().filter(|c| normalize(&c.name) == name).collect();
+        if matches.is_empty() {
+            matches = all.iter().filter(|c| normalize(&c.path[0]) == name).collect();
+        }
+        if matches.is_empty() {
+            return Err(Error::UnknownField(format!(
+                "{}. Fields: {}",
+                field.trim(),
+                all.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
+            )));
+        }
+        for column in matches {
+            if !selected.contains(column) {
+                selected.push(column.clone());
+            }
+        }
+    }
+    Ok(selected)
+}
+
+/// A spike's cells for the given columns
+pub fn cells(spike: &Spike, columns: &[Column]) -> Vec<String> {
+    let value = serde_json::to_value(spike).unwrap_or(Value::Null);
+    columns
+        .iter()
+        .map(|column| {
+            let cell = column.path.iter().fold(&value, |v, key| &v[key.as_str()]);
+            match cell {
+                Value::Null => String::new(),
+                Value::String(s) => s.clone(),
+                Value::Array(_) => cell.to_string(),
+                other => other.to_string(),
+            }
+        })
+        .collect()
+}
+
+impl Kind {
+    /// Read a cell (or any string value) back into JSON. Text is kept as is;
+    /// a list is a JSON array, or for hand-written cells, separated by `,`
+    /// or `;`.
+    pub fn parse(self, value: Value) -> std::result::Result<Value, String> {
+        let Value::String(text) = value else {
+            return Ok(value);
+        };
+        let text = match self {
+            Kind::Text => return Ok(json!(text)),
+            Kind::List if text.trim_start().starts_with('[') => {
+                return serde_json::from_str::<Vec<String>>(&text)
+                    .map(|items| json!(items))
+                    .map_err(|e| format!("expected a JSON array of strings: {}", e));
+            }
+            _ => text.trim(),
+        };
+        match self {
+            Kind::Text => Ok(json!(text)),
+            Kind::Number => match (text.parse::<u64>(), text.parse::<f64>()) {
+                (Ok(n), _) => Ok(json!(n)),
+                (_, Ok(n)) if n.is_finite() => Ok(json!(n)),
+                _ => Err(format!("expected a number, got '{}'", text)),
+            },
+            Kind::Bool => match text.to_lowercase().as_str() {
+                "true" | "yes" | "1" | "resolved" => Ok(json!(true)),
+                "false" | "no" | "0" | "open" => Ok(json!(false)),
+                _ => Err(format!("expected true or false, got '{}'", text)),
+            },
+            Kind::List => Ok(json!(text
+                .split([',', ';'])
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .collect::<Vec<_>>())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_cover_every_field_in_struct_order() {
+        let names: Vec<String> = columns().into_iter().map(|c| c.name).collect();
+        assert_eq!(
+            names,
+            [
+                "id", "type", "project_key", "page", "url",
+                "reviewer_id", "reviewer_name", "reviewer_email",
+                "selector", "xpath", "element_text",
+                "bounding_box_x", "bounding_box_y", "bounding_box_width", "bounding_box_height",
+                "rating", "comments", "timestamp", "viewport_width", "viewport_height",
+                "resolved", "resolved_at", "duplicate_of", "stale", "tags", "assignee", "round", "page_hash",
+                "issue_tracker", "issue_id", "issue_project", "issue_url", "issue_state",
+            ]
+        );
+        assert_eq!(find("Reviewer Name").unwrap().path, ["reviewer", "name"]);
+        assert_eq!(find("boundingBoxX").unwrap().kind, Kind::Number);
+        assert_eq!(find("viewport_width").unwrap().kind, Kind::Number);
+        assert_eq!(find("resolved").unwrap().kind, Kind::Bool);
+        assert_eq!(find("tags").unwrap().kind, Kind::List);
+        assert_eq!(find("rating").unwrap().kind, Kind::Text);
+    }
+
+    #[test]
+    fn test_parse_trims_only_numbers_and_bools() {
+        assert_eq!(Kind::Text.parse(json!("  spaced  ")).unwrap(), json!("  spaced  "));
+        assert_eq!(Kind::Number.parse(json!(" 390 ")).unwrap(), json!(390));
+        assert_eq!(Kind::Bool.parse(json!(" yes")).unwrap(), json!(true));
+        assert_eq!(Kind::List.parse(json!(r#"["a,b"," c"]"#)).unwrap(), json!(["a,b", " c"]));
+        assert_eq!(Kind::List.parse(json!("a, b;c")).unwrap(), json!(["a", "b", "c"]));
+        assert!(Kind::List.parse(json!("[1, 2")).is_err());
+    }
+
+    #[test]
+    fn test_select_orders_and_expands_nested_fields() {
+        let names = |fields: &[&str]| -> Vec<String> {
+            let fields: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+            select(&fields).unwrap().into_iter().map(|c| c.name).collect()
+        };
+        assert_eq!(names(&["rating", "id"]), ["rating", "id"]);
+        assert_eq!(names(&["viewport", "viewport_width"]), ["viewport_width", "viewport_height"]);
+        assert_eq!(names(&["projectKey"]), ["project_key"]);
+
+        let err = select(&["nope".to_string()]).unwrap_err().to_string();
+        assert!(err.starts_with("Unknown field: nope. Fields: id, type,"), "got: {}", err);
+    }
+}
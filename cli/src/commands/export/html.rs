@@ -0,0 +1,244 @@
+//! Self-contained HTML report for sending feedback to people without the
+//! CLI. Styles, filter script and overlay diagrams are all inline, so the
+//! file works offline.
+
+use std::collections::BTreeMap;
+
+use crate::spike::{Rating, Spike, SpikeType};
+
+use crate::commands::heatmap::{build_heatmap, escape_xml, hex, rating_color, render_svg, DEFAULT_WIDTH};
+use super::is_blocking;
+
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 0; color: #222; background: #fafafa; }
+header, main { max-width: 1100px; margin: 0 auto; padding: 0 24px; }
+header { padding-top: 32px; }
+h1 { margin-bottom: 4px; }
+.meta { color: #666; margin-top: 0; }
+.totals span { display: inline-block; margin-right: 16px; }
+nav ul { padding-left: 20px; }
+.filters { position: sticky; top: 0; background: #fafafa; padding: 12px 0; border-bottom: 1px solid #ddd; display: flex; gap: 16px; flex-wrap: wrap; align-items: center; }
+section.page { margin: 32px 0; }
+.overlay svg { max-width: 100%; height: auto; border: 1px solid #ddd; }
+table { width: 100%; border-collapse: collapse; background: #fff; }
+th, td { text-align: left; padding: 8px; border-bottom: 1px solid #eee; vertical-align: top; }
+.badge { display: inline-block; padding: 2px 8px; border-radius: 10px; color: #fff; font-size: 12px; font-weight: 600; }
+.status-open { color: #c0392b; }
+.status-resolved { color: #27ae60; }
+code { font-size: 12px; }
+.hidden { display: none; }
+"#;
+
+const SCRIPT: &str = r#"
+function applyFilters() {
+  var rating = document.getElementById('filter-rating').value;
+  var status = document.getElementById('filter-status').value;
+  var text = document.getElementById('filter-text').value.toLowerCase();
+  document.querySelectorAll('tr.spike').forEach(function (row) {
+    var show = (!rating || row.dataset.rating === rating)
+      && (!status || row.dataset.status === status)
+      && (!text || row.textContent.toLowerCase().indexOf(text) !== -1);
+    row.classList.toggle('hidden', !show);
+  });
+  document.querySelectorAll('section.page').forEach(function (section) {
+    var visible = section.querySelectorAll('tr.spike:not(.hidden)').length;
+    section.querySelector('.shown').textContent = visible;
+  });
+}
+document.querySelectorAll('.filters select, .filters input').forEach(function (el) {
+  el.addEventListener('input', applyFilters);
+});
+"#;
+
+/// Anchor id for a page section
+fn anchor(page: &str) -> String {
+    let slug: String = page
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    format!("page-{}", slug)
+}
+
+fn badge(rating: Option<&Rating>) -> String {
+    format!(
+        "<span class=\"badge\" style=\"background:{}\">{}</span>",
+        hex(rating_color(rating)),
+        rating.map(|r| r.to_string()).unwrap_or_else(|| "unrated".to_string())
+    )
+}
+
+fn spike_row(spike: &Spike) -> String {
+    let target = match (&spike.spike_type, &spike.selector) {
+        (SpikeType::Element, Some(selector)) => format!("<code>{}</code>", escape_xml(selector)),
+        _ => "page".to_string(),
+    };
+    let status = match (spike.is_resolved(), &spike.resolved_at) {
+        (true, Some(at)) => format!("<span class=\"status-resolved\">Resolved {}</span>", escape_xml(at)),
+        (true, None) => "<span class=\"status-resolved\">Resolved</span>".to_string(),
+        (false, _) if is_blocking(spike) => "<span class=\"status-open\">Open (blocking)</span>".to_string(),
+        (false, _) => "<span class=\"status-open\">Open</span>".to_string(),
+    };
+    format!(
+        "<tr class=\"spike\" data-rating=\"{}\" data-status=\"{}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>\n",
+        spike.rating_str(),
+        if spike.is_resolved() { "resolved" } else { "open" },
+        badge(spike.rating.as_ref()),
+        target,
+        escape_xml(&spike.comments),
+        escape_xml(&spike.reviewer.name),
+        status,
+        escape_xml(&spike.id.chars().take(8).collect::<String>())
+    )
+}
+
+/// Generate the HTML report: totals, page index, filters, and per page an
+/// overlay diagram of element boxes and a table of spikes.
+pub(super) fn generate_html(spikes: &[Spike]) -> String {
+    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+    let project = spikes
+        .first()
+        .map(|s| s.project_key.as_str())
+        .unwrap_or("unknown");
+
+    let mut pages: BTreeMap<&str, Vec<&Spike>> = BTreeMap::new();
+    for spike in spikes {
+        pages.entry(spike.page.as_str()).or_default().push(spike);
+    }
+    let resolved = spikes.iter().filter(|s| s.is_resolved()).count();
+    let blocking = spikes.iter().filter(|s| is_blocking(s)).count();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>Feedback report: {}</title>\n", escape_xml(project)));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+
+    html.push_str("<header>\n");
+    html.push_str(&format!("<h1>Feedback report: {}</h1>\n", escape_xml(project)));
+    html.push_str(&format!("<p class=\"meta\">Generated {}</p>\n", timestamp));
+    html.push_str(&format!(
+        "<p class=\"totals\"><span><strong>{}</strong> spikes</span><span><strong>{}</strong> open</span><span><strong>{}</strong> resolved</span><span><strong>{}</strong> blocking</span></p>\n",
+        spikes.len(),
+        spikes.len() - resolved,
+        resolved,
+        blocking
+    ));
+    let ratings = [Some(Rating::Love), Some(Rating::Like), Some(Rating::Meh), Some(Rating::No), None];
+    html.push_str("<p class=\"totals\">");
+    for rating in &ratings {
+        let count = spikes.iter().filter(|s| s.rating == *rating).count();
+        html.push_str(&format!("<span>{} {}</span>", badge(rating.as_ref()), count));
+    }
+    html.push_str("</p>\n");
+
+    if !pages.is_empty() {
+        html.push_str("<nav><ul>\n");
+        for (page, page_spikes) in &pages {
+            html.push_str(&format!(
+                "<li><a href=\"#{}\">{}</a> ({})</li>\n",
+                anchor(page),
+                escape_xml(page),
+                page_spikes.len()
+            ));
+        }
+        html.push_str("</ul></nav>\n");
+    }
+    html.push_str("</header>\n<main>\n");
+
+    if pages.is_empty() {
+        html.push_str("<p>No feedback yet.</p>\n");
+    } else {
+        html.push_str("<div class=\"filters\">\n");
+        html.push_str("<label>Rating <select id=\"filter-rating\"><option value=\"\">All</option>");
+        for (value, label) in [("love", "love"), ("like", "like"), ("meh", "meh"), ("no", "no"), ("-", "unrated")] {
+            html.push_str(&format!("<option value=\"{}\">{}</option>", value, label));
+        }
+        html.push_str("</select></label>\n");
+        html.push_str("<label>Status <select id=\"filter-status\"><option value=\"\">All</option><option value=\"open\">Open</option><option value=\"resolved\">Resolved</option></select></label>\n");
+        html.push_str("<label>Search <input id=\"filter-text\" type=\"search\"></label>\n");
+        html.push_str("</div>\n");
+    }
+
+    for (page, page_spikes) in &pages {
+        html.push_str(&format!("<section class=\"page\" id=\"{}\">\n", anchor(page)));
+        html.push_str(&format!(
+            "<h2>{}</h2>\n<p class=\"meta\"><span class=\"shown\">{}</span> of {} spike{}</p>\n",
+            escape_xml(page),
+            page_spikes.len(),
+            page_spikes.len(),
+            if page_spikes.len() == 1 { "" } else { "s" }
+        ));
+
+        let owned: Vec<Spike> = page_spikes.iter().map(|s| (*s).clone()).collect();
+        let heatmap = build_heatmap(&owned, DEFAULT_WIDTH);
+        if !heatmap.boxes.is_empty() {
+            html.push_str("<div class=\"overlay\">\n");
+            html.push_str(&render_svg(&heatmap, page));
+            html.push_str("</div>\n");
+        }
+
+        html.push_str("<table>\n<thead><tr><th>Rating</th><th>Target</th><th>Comment</th><th>Reviewer</th><th>Status</th><th>ID</th></tr></thead>\n<tbody>\n");
+        for spike in page_spikes {
+            html.push_str(&spike_row(spike));
+        }
+        html.push_str("</tbody>\n</table>\n</section>\n");
+    }
+
+    html.push_str("</main>\n");
+    html.push_str(&format!("<script>{}</script>\n", SCRIPT));
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{BoundingBox, Reviewer, Viewport};
+
+    fn spike(id: &str, page: &str, rating: Option<Rating>, selector: Option<&str>, comments: &str) -> Spike {
+        Spike {
+            spike_type: if selector.is_some() { SpikeType::Element } else { SpikeType::Page },
+            project_key: "acme".to_string(),
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: selector.map(String::from),
+            comments: comments.to_string(),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            ..Spike::test(id, page, rating)
+        }
+    }
+
+    #[test]
+    fn test_html_report_sections_and_overlay() {
+        let mut hero = spike("s1", "index.html", Some(Rating::No), Some(".hero"), "Too <b>loud</b>");
+        hero.bounding_box = Some(BoundingBox { x: 0.0, y: 0.0, width: 640.0, height: 200.0 });
+        hero.viewport = Some(Viewport { width: 1280, height: 800 });
+        let mut about = spike("s2", "about.html", Some(Rating::Love), None, "Nice");
+        about.resolved = Some(true);
+        about.resolved_at = Some("2024-01-16T10:00:00Z".to_string());
+        let html = generate_html(&[hero, about]);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Feedback report: acme</title>"));
+        assert!(html.contains("<a href=\"#page-about-html\">about.html</a> (1)"));
+        assert!(html.contains("<section class=\"page\" id=\"page-index-html\">"));
+        // One overlay, for the page with a bounding box
+        assert_eq!(html.matches("<svg").count(), 1);
+        assert!(html.contains("Too &lt;b&gt;loud&lt;/b&gt;"));
+        assert!(html.contains("data-rating=\"no\" data-status=\"open\""));
+        assert!(html.contains("Open (blocking)"));
+        assert!(html.contains("Resolved 2024-01-16T10:00:00Z"));
+        assert!(html.contains("id=\"filter-rating\""));
+        assert!(!html.contains("<link") && !html.contains("src=\"http"), "report must be self-contained");
+    }
+
+    #[test]
+    fn test_html_report_empty_state() {
+        let html = generate_html(&[]);
+        assert!(html.contains("No feedback yet."));
+        assert!(!html.contains("filter-rating\""));
+    }
+}
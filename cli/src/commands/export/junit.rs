@@ -0,0 +1,118 @@
+//! JUnit XML output for CI test reporters: each page is a testsuite and
+//! each spike a testcase. Blocking spikes fail; everything else passes.
+
+use std::collections::BTreeMap;
+
+use crate::commands::heatmap::escape_xml;
+use crate::spike::{Spike, SpikeType};
+
+use super::is_blocking;
+
+/// Stable testcase name, so dashboards can track a spike across runs
+fn case_name(spike: &Spike) -> String {
+    let target = match (&spike.spike_type, &spike.selector) {
+        (SpikeType::Element, Some(selector)) => selector.as_str(),
+        _ => "page",
+    };
+    format!("{} ({})", target, spike.id)
+}
+
+fn testcase(spike: &Spike) -> String {
+    let open = format!(
+        "    <testcase classname=\"{}\" name=\"{}\"",
+        escape_xml(&spike.page),
+        escape_xml(&case_name(spike))
+    );
+    if !is_blocking(spike) {
+        return format!("{}/>\n", open);
+    }
+    let comment = spike.comments.trim();
+    format!(
+        "{}>\n      <failure type=\"{}\" message=\"{}\">Rated {} by {} at {}{}</failure>\n    </testcase>\n",
+        open,
+        spike.rating_str(),
+        escape_xml(if comment.is_empty() { "(no comment)" } else { comment }),
+        spike.rating_str(),
+        escape_xml(&spike.reviewer.name),
+        escape_xml(&spike.timestamp),
+        if comment.is_empty() {
+            String::new()
+        } else {
+            format!(": {}", escape_xml(comment))
+        }
+    )
+}
+
+/// Build a JUnit report with one testsuite per page, in page order
+pub(super) fn generate_junit(spikes: &[Spike]) -> String {
+    let mut pages: BTreeMap<&str, Vec<&Spike>> = BTreeMap::new();
+    for spike in spikes {
+        pages.entry(spike.page.as_str()).or_default().push(spike);
+    }
+    let failures = spikes.iter().filter(|s| is_blocking(s)).count();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"spikes\" tests=\"{}\" failures=\"{}\">\n",
+        spikes.len(),
+        failures
+    ));
+    for (page, page_spikes) in &pages {
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">\n",
+            escape_xml(page),
+            page_spikes.len(),
+            page_spikes.iter().filter(|s| is_blocking(s)).count()
+        ));
+        for spike in page_spikes {
+            xml.push_str(&testcase(spike));
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Rating, Reviewer};
+
+    fn spike(id: &str, page: &str, rating: Option<Rating>, resolved: bool, comments: &str) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: Some(".hero > h1".to_string()),
+            comments: comments.to_string(),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            resolved: resolved.then_some(true),
+            ..Spike::test(id, page, rating)
+        }
+    }
+
+    #[test]
+    fn test_junit_pages_are_suites_and_blocking_spikes_fail() {
+        let spikes = vec![
+            spike("s1", "index.html", Some(Rating::No), false, "Use \"bigger\" <text>"),
+            spike("s2", "index.html", Some(Rating::Meh), true, "Fixed"),
+            spike("s3", "about.html", Some(Rating::Love), false, ""),
+            spike("s4", "about.html", None, false, "Hmm"),
+        ];
+        let xml = generate_junit(&spikes);
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"spikes\" tests=\"4\" failures=\"1\">"));
+        // Suites in page order
+        assert!(xml.find("name=\"about.html\"").unwrap() < xml.find("name=\"index.html\"").unwrap());
+        assert!(xml.contains("<testsuite name=\"index.html\" tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("<testsuite name=\"about.html\" tests=\"2\" failures=\"0\""));
+        assert!(xml.contains(
+            "<testcase classname=\"index.html\" name=\".hero &gt; h1 (s1)\">\n      <failure type=\"no\" message=\"Use &quot;bigger&quot; &lt;text&gt;\">"
+        ));
+        assert!(xml.contains("<testcase classname=\"index.html\" name=\".hero &gt; h1 (s2)\"/>"));
+        assert_eq!(xml.matches("<failure").count(), 1);
+    }
+}
@@ -0,0 +1,253 @@
+//! Neutral markdown report for PR descriptions and wikis: a summary, a
+//! table of contents by page, and per page the page feedback and one
+//! section per element.
+
+use std::collections::BTreeMap;
+
+use crate::selector::group_elements;
+use crate::spike::{Rating, Spike, SpikeType};
+
+/// Heading anchor as GitHub generates it: lowercase, punctuation dropped,
+/// spaces turned into hyphens
+fn slug(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Reviewer text made safe to inline: no raw HTML, and Markdown syntax
+/// characters shown as typed
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\\' | '`' | '*' | '_' | '[' | ']' | '|' | '~' | '#' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// "Alice (2), Bob (1)", most active first
+fn reviewer_list(spikes: &[&Spike]) -> String {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for spike in spikes {
+        *counts.entry(spike.reviewer.name.as_str()).or_default() += 1;
+    }
+    let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    counts
+        .iter()
+        .map(|(name, n)| format!("{} ({})", escape(name), n))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// "3 spikes · 2 open · 1 resolved"
+fn status_line(spikes: &[&Spike]) -> String {
+    let resolved = spikes.iter().filter(|s| s.is_resolved()).count();
+    format!(
+        "{} spike{} · {} open · {} resolved",
+        spikes.len(),
+        if spikes.len() == 1 { "" } else { "s" },
+        spikes.len() - resolved,
+        resolved
+    )
+}
+
+fn feedback_item(spike: &Spike) -> String {
+    let date = spike.timestamp.get(..10).unwrap_or(&spike.timestamp);
+    let comment = if spike.comments.trim().is_empty() {
+        "_(no comment)_".to_string()
+    } else {
+        escape(&spike.comments.trim().replace('\n', " "))
+    };
+    format!(
+        "- **{}** — {}, {}: {}{}\n",
+        spike.rating_str(),
+        escape(&spike.reviewer.name),
+        date,
+        comment,
+        if spike.is_resolved() { " _(resolved)_" } else { "" }
+    )
+}
+
+/// Generate the markdown report. `filters` lists the active list filters
+/// in `--where` form and is shown under the title.
+pub(super) fn generate_markdown(spikes: &[Spike], filters: &[String]) -> String {
+    let mut output = String::new();
+
+    let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+    let project = spikes
+        .first()
+        .map(|s| s.project_key.as_str())
+        .unwrap_or("unknown");
+
+    output.push_str(&format!("# Feedback report: {}\n\n", project));
+    output.push_str(&format!("Generated {}.", timestamp));
+    if !filters.is_empty() {
+        output.push_str(&format!(" Filtered by {}.", filters.join(", ")));
+    }
+    output.push_str("\n\n");
+
+    let all: Vec<&Spike> = spikes.iter().collect();
+    output.push_str("## Summary\n\n");
+    if all.is_empty() {
+        output.push_str("No feedback.\n");
+        return output;
+    }
+
+    let open: Vec<&Spike> = all.iter().copied().filter(|s| !s.is_resolved()).collect();
+    let resolved: Vec<&Spike> = all.iter().copied().filter(|s| s.is_resolved()).collect();
+    output.push_str("| Rating | Open | Resolved | Total |\n");
+    output.push_str("|--------|------|----------|-------|\n");
+    for rating in [Some(Rating::Love), Some(Rating::Like), Some(Rating::Meh), Some(Rating::No), None] {
+        let count = |spikes: &[&Spike]| spikes.iter().filter(|s| s.rating == rating).count();
+        let total = count(&all);
+        if total == 0 {
+            continue;
+        }
+        output.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            rating.as_ref().map(|r| r.to_string()).unwrap_or_else(|| "unrated".to_string()),
+            count(&open),
+            count(&resolved),
+            total
+        ));
+    }
+    output.push_str(&format!(
+        "| **All** | **{}** | **{}** | **{}** |\n\n",
+        open.len(),
+        resolved.len(),
+        all.len()
+    ));
+    output.push_str(&format!("**Reviewers:** {}\n\n", reviewer_list(&all)));
+
+    let mut pages: BTreeMap<&str, Vec<&Spike>> = BTreeMap::new();
+    for spike in spikes {
+        pages.entry(spike.page.as_str()).or_default().push(spike);
+    }
+
+    output.push_str("## Contents\n\n");
+    for (page, page_spikes) in &pages {
+        output.push_str(&format!(
+            "- [{}](#{}) — {}\n",
+            page,
+            slug(page),
+            status_line(page_spikes)
+        ));
+    }
+    output.push('\n');
+
+    for (page, page_spikes) in &pages {
+        output.push_str(&format!("## {}\n\n", page));
+        output.push_str(&format!("{}\n\n", status_line(page_spikes)));
+        output.push_str(&format!("**Reviewers:** {}\n\n", reviewer_list(page_spikes)));
+
+        let page_level: Vec<&Spike> = page_spikes
+            .iter()
+            .copied()
+            .filter(|s| s.spike_type == SpikeType::Page)
+            .collect();
+        if !page_level.is_empty() {
+            output.push_str("### Page feedback\n\n");
+            for spike in &page_level {
+                output.push_str(&feedback_item(spike));
+            }
+            output.push('\n');
+        }
+
+        let mut groups = group_elements(page_spikes.iter().copied());
+        groups.sort_by(|a, b| a.selector.cmp(&b.selector));
+        for group in &groups {
+            output.push_str(&format!("### `{}`\n\n", group.selector));
+            output.push_str(&format!(
+                "{} · Reviewers: {}\n\n",
+                status_line(&group.spikes),
+                reviewer_list(&group.spikes)
+            ));
+            for spike in &group.spikes {
+                output.push_str(&feedback_item(spike));
+            }
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::Reviewer;
+
+    fn spike(id: &str, page: &str, reviewer: &str, rating: Rating, selector: Option<&str>, resolved: bool) -> Spike {
+        Spike {
+            spike_type: if selector.is_some() { SpikeType::Element } else { SpikeType::Page },
+            project_key: "acme".to_string(),
+            reviewer: Reviewer {
+                id: reviewer.to_string(),
+                name: reviewer.to_string(),
+                email: None,
+            },
+            selector: selector.map(String::from),
+            comments: format!("Comment {}", id),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            resolved: resolved.then_some(true),
+            ..Spike::test(id, page, Some(rating))
+        }
+    }
+
+    #[test]
+    fn test_markdown_report_structure() {
+        let spikes = vec![
+            spike("s1", "index.html", "Alice", Rating::No, Some(".hero"), false),
+            spike("s2", "index.html", "Bob", Rating::Like, Some(".hero"), true),
+            spike("s3", "index.html", "Alice", Rating::Meh, None, false),
+            spike("s4", "About Us", "Bob", Rating::Love, None, false),
+        ];
+        let md = generate_markdown(&spikes, &["round=v2".to_string()]);
+
+        assert!(md.starts_with("# Feedback report: acme\n"));
+        assert!(md.contains("Filtered by round=v2."));
+        assert!(md.contains("| no | 1 | 0 | 1 |"));
+        assert!(md.contains("| **All** | **3** | **1** | **4** |"));
+        assert!(md.contains("**Reviewers:** Alice (2), Bob (2)"));
+        assert!(md.contains("- [About Us](#about-us) — 1 spike · 1 open · 0 resolved"));
+        assert!(md.contains("- [index.html](#indexhtml) — 3 spikes · 2 open · 1 resolved"));
+        assert!(md.contains("### `.hero`\n\n2 spikes · 1 open · 1 resolved · Reviewers: Alice (1), Bob (1)"));
+        assert!(md.contains("- **like** — Bob, 2024-01-15: Comment s2 _(resolved)_"));
+        assert!(md.contains("### Page feedback\n\n- **meh** — Alice, 2024-01-15: Comment s3\n"));
+    }
+
+    #[test]
+    fn test_markdown_escapes_reviewer_text() {
+        let mut s = spike("s1", "index.html", "*Eve*", Rating::Meh, None, false);
+        s.comments = "Meh <b> [link](x) `code` | _it_".to_string();
+        let md = generate_markdown(&[s], &[]);
+
+        assert!(md.contains("**Reviewers:** \\*Eve\\* (1)"), "{}", md);
+        assert!(md.contains(
+            "- **meh** — \\*Eve\\*, 2024-01-15: Meh &lt;b&gt; \\[link\\](x) \\`code\\` \\| \\_it\\_\n"
+        ));
+        assert!(!md.contains("<b>"));
+    }
+
+    #[test]
+    fn test_markdown_report_empty() {
+        let md = generate_markdown(&[], &[]);
+        assert!(md.contains("No feedback."));
+        assert!(!md.contains("Filtered by"));
+    }
+}
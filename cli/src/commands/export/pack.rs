@@ -0,0 +1,370 @@
+//! Token-budgeted claude-context for agents with small context windows.
+//!
+//! Blocking spikes come first, then hotspots, contested elements and the
+//! most recent open feedback. Everything starts in a one-line form; leftover
+//! budget then buys blocking spikes their full detail before recent feedback
+//! is added. What didn't fit is counted in an "Omitted" section. Tokens are
+//! estimated at four characters each, which errs high for English prose.
+
+use crate::commands::dedupe::{agree_counts, agree_label};
+use crate::commands::hotspots::hotspot_groups;
+use crate::spike::{Rating, Spike, SpikeType};
+use crate::summary::Summary;
+
+use super::{contested_elements, contested_for, is_blocking, split_label};
+
+/// Budget when none is given
+pub const DEFAULT_MAX_TOKENS: usize = 4000;
+/// Kept back for the closing "Omitted" section
+const FOOTER_RESERVE: usize = 100;
+/// Comments in one-line entries are cut at this many characters
+const SHORT_COMMENT_CHARS: usize = 100;
+
+/// Rough token count of `text`
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+fn short(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= SHORT_COMMENT_CHARS {
+        return text;
+    }
+    let cut: String = text.chars().take(SHORT_COMMENT_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn short_id(spike: &Spike) -> String {
+    spike.id.chars().take(8).collect()
+}
+
+fn target(spike: &Spike) -> String {
+    match (&spike.spike_type, &spike.selector) {
+        (SpikeType::Element, Some(selector)) => format!("`{}` ({})", selector, spike.page),
+        _ => format!("page `{}`", spike.page),
+    }
+}
+
+fn plural(n: usize, word: &str) -> String {
+    format!("{} {}{}", n, word, if n == 1 { "" } else { "s" })
+}
+
+/// Lines of one section, added while the budget lasts
+struct Section {
+    heading: &'static str,
+    lines: Vec<String>,
+}
+
+impl Section {
+    fn new(heading: &'static str) -> Self {
+        Section { heading, lines: Vec::new() }
+    }
+
+    /// Add `line` if it fits, paying for the heading with the first line
+    fn push(&mut self, line: String, budget: &mut usize) -> bool {
+        let mut cost = estimate_tokens(&line);
+        if self.lines.is_empty() {
+            cost += estimate_tokens(self.heading);
+        }
+        if cost > *budget {
+            return false;
+        }
+        *budget -= cost;
+        self.lines.push(line);
+        true
+    }
+
+    /// Add lines in order until one doesn't fit; returns how many were added
+    fn fill(&mut self, lines: impl IntoIterator<Item = String>, budget: &mut usize) -> usize {
+        let mut added = 0;
+        for line in lines {
+            if !self.push(line, budget) {
+                break;
+            }
+            added += 1;
+        }
+        added
+    }
+
+    fn render(&self, output: &mut String) {
+        if self.lines.is_empty() {
+            return;
+        }
+        output.push_str(self.heading);
+        for line in &self.lines {
+            output.push_str(line);
+        }
+        // Full entries end in a blank line already
+        if !output.ends_with("\n\n") {
+            output.push('\n');
+        }
+    }
+}
+
+/// Generate claude-context trimmed to about `max_tokens` tokens
+pub fn generate_context_pack(spikes: &[Spike], max_tokens: usize) -> String {
+    let agree = agree_counts(spikes);
+    let contested = contested_elements(spikes);
+    let agreement = |spike: &Spike| agree.get(&spike.id).copied().unwrap_or(0);
+
+    let project = spikes.first().map(|s| s.project_key.as_str()).unwrap_or("unknown");
+    let canonical: Vec<&Spike> = spikes.iter().filter(|s| s.duplicate_of.is_none()).collect();
+    let resolved = canonical.iter().filter(|s| s.is_resolved()).count();
+    let duplicates = spikes.len() - canonical.len();
+
+    // No before meh, then the most agreed-with, then the newest
+    let mut blocking: Vec<&Spike> = canonical.iter().copied().filter(|s| is_blocking(s)).collect();
+    blocking.sort_by(|a, b| {
+        let rank = |s: &Spike| if s.rating == Some(Rating::No) { 0 } else { 1 };
+        rank(a)
+            .cmp(&rank(b))
+            .then_with(|| agreement(b).cmp(&agreement(a)))
+            .then_with(|| b.timestamp.cmp(&a.timestamp))
+    });
+    let mut recent: Vec<&Spike> = canonical
+        .iter()
+        .copied()
+        .filter(|s| !s.is_resolved() && !is_blocking(s))
+        .collect();
+    recent.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    let hotspots = hotspot_groups(spikes);
+
+    let mut output = String::from("# ⚡ SPIKES CONTEXT PACK\n\n");
+    output.push_str(&format!("**Project:** {}\n", project));
+    output.push_str(&format!(
+        "**Feedback:** {}, {} unresolved, {} blocking\n",
+        plural(canonical.len(), "spike"),
+        canonical.len() - resolved,
+        blocking.len()
+    ));
+    output.push_str(&format!(
+        "**Generated:** {}\n\n",
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
+    ));
+    let mut budget = max_tokens.saturating_sub(estimate_tokens(&output) + FOOTER_RESERVE);
+
+    let extras = |spike: &Spike| {
+        let mut extras = Vec::new();
+        if agreement(spike) > 0 {
+            extras.push(agree_label(agreement(spike)));
+        }
+        if let Some(score) = contested_for(&contested, &spike.id) {
+            extras.push(format!("contested: {}", split_label(score)));
+        }
+        extras
+    };
+    let short_blocking = |spike: &Spike| {
+        let mut line = format!(
+            "- `{}` **{}** on {} from {}",
+            short_id(spike),
+            spike.rating_str(),
+            target(spike),
+            spike.reviewer.name
+        );
+        if !spike.comments.trim().is_empty() {
+            line.push_str(&format!(": \"{}\"", short(&spike.comments)));
+        }
+        for extra in extras(spike) {
+            line.push_str(&format!(" · {}", extra));
+        }
+        line.push('\n');
+        line
+    };
+    let full_blocking = |spike: &Spike| {
+        let mut block = format!("### ID: `{}`\n\n", short_id(spike));
+        block.push_str(&format!("- **Type:** {} on page `{}`\n", spike.type_str(), spike.page));
+        block.push_str(&format!("- **Rating:** {} (negative)\n", spike.rating_str()));
+        if let (SpikeType::Element, Some(selector)) = (&spike.spike_type, &spike.selector) {
+            block.push_str(&format!("- **Target:** `{}`\n", selector));
+        }
+        if !spike.comments.trim().is_empty() {
+            block.push_str(&format!("- **Feedback:** \"{}\"\n", spike.comments));
+        }
+        block.push_str(&format!("- **From:** {}\n", spike.reviewer.name));
+        if agreement(spike) > 0 {
+            block.push_str(&format!("- **Agreement:** {}\n", agree_label(agreement(spike))));
+        }
+        if let Some(score) = contested_for(&contested, &spike.id) {
+            block.push_str(&format!("- **Contested:** {}\n", split_label(score)));
+        }
+        block.push('\n');
+        block
+    };
+
+    let mut critical = Section::new("## ⚠️ CRITICAL ISSUES\n\n");
+    let shown_blocking = critical.fill(blocking.iter().map(|s| short_blocking(s)), &mut budget);
+
+    let mut hot = Section::new("## 📊 FEEDBACK HOTSPOTS\n\n");
+    let shown_hotspots = hot.fill(
+        hotspots.iter().enumerate().map(|(i, group)| {
+            format!("{}. `{}` — {}\n", i + 1, group.selector, Summary::of(&group.spikes).line())
+        }),
+        &mut budget,
+    );
+
+    let mut split = Section::new("## ⚖️ CONTESTED ELEMENTS\n\n_No consensus. Talk to the humans before changing these._\n\n");
+    let shown_contested = split.fill(
+        contested.iter().map(|score| {
+            format!(
+                "- `{}` — {}, {:.0}% agreement\n",
+                score.selector,
+                split_label(score),
+                score.agreement * 100.0
+            )
+        }),
+        &mut budget,
+    );
+
+    // Leftover budget buys full detail, most important issue first. Full
+    // entries stay ahead of the one-line ones.
+    let mut detailed = 0;
+    for (i, spike) in blocking.iter().take(shown_blocking).enumerate() {
+        let full = full_blocking(spike);
+        let extra = estimate_tokens(&full).saturating_sub(estimate_tokens(&critical.lines[i]));
+        if extra > budget {
+            break;
+        }
+        budget -= extra;
+        critical.lines[i] = full;
+        detailed += 1;
+    }
+
+    let mut latest = Section::new("## 🕒 RECENT FEEDBACK\n\n");
+    let shown_recent = latest.fill(
+        recent.iter().map(|spike| {
+            let date = spike.timestamp.get(..10).unwrap_or(&spike.timestamp);
+            let mut line = format!("- **{}** on {} from {}, {}", spike.rating_str(), target(spike), spike.reviewer.name, date);
+            if !spike.comments.trim().is_empty() {
+                line.push_str(&format!(": \"{}\"", short(&spike.comments)));
+            }
+            line.push('\n');
+            line
+        }),
+        &mut budget,
+    );
+
+    if canonical.is_empty() {
+        output.push_str("No feedback yet.\n\n");
+    }
+    if blocking.is_empty() && !canonical.is_empty() {
+        output.push_str("**✅ All clear.** No critical issues blocking progress.\n\n");
+    }
+    for section in [&critical, &hot, &split, &latest] {
+        section.render(&mut output);
+    }
+
+    let mut omitted = Vec::new();
+    if shown_blocking < blocking.len() {
+        omitted.push(plural(blocking.len() - shown_blocking, "critical issue"));
+    }
+    if detailed < shown_blocking {
+        omitted.push(format!("details of {} shown in short form", plural(shown_blocking - detailed, "critical issue")));
+    }
+    if shown_hotspots < hotspots.len() {
+        omitted.push(plural(hotspots.len() - shown_hotspots, "hotspot"));
+    }
+    if shown_contested < contested.len() {
+        omitted.push(plural(contested.len() - shown_contested, "contested element"));
+    }
+    if shown_recent < recent.len() {
+        omitted.push(plural(recent.len() - shown_recent, "older open spike"));
+    }
+    if resolved > 0 {
+        omitted.push(plural(resolved, "resolved spike"));
+    }
+    if duplicates > 0 {
+        omitted.push(format!("{} (counted as agreement)", plural(duplicates, "duplicate")));
+    }
+
+    if !omitted.is_empty() {
+        output.push_str("## ✂️ OMITTED\n\n");
+        output.push_str(&format!(
+            "To fit ~{} tokens: {}. Run `spikes export --format claude-context` for everything.\n\n",
+            max_tokens,
+            omitted.join("; ")
+        ));
+    }
+    output.push_str("_Spikes — structured feedback for the modern builder._\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::Reviewer;
+
+    fn spike(id: &str, rating: Rating, selector: &str, timestamp: &str) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            project_key: "acme".to_string(),
+            reviewer: Reviewer {
+                id: format!("r-{}", id),
+                name: format!("Reviewer {}", id),
+                email: None,
+            },
+            selector: Some(selector.to_string()),
+            comments: format!("Comment for {} with a few words about the {} element", id, selector),
+            timestamp: timestamp.to_string(),
+            ..Spike::test(id, "index.html", Some(rating))
+        }
+    }
+
+    fn project() -> Vec<Spike> {
+        let mut spikes = Vec::new();
+        for i in 0..40 {
+            let rating = match i % 4 {
+                0 => Rating::No,
+                1 => Rating::Meh,
+                2 => Rating::Like,
+                _ => Rating::Love,
+            };
+            spikes.push(spike(
+                &format!("s{:02}", i),
+                rating,
+                &format!(".card-{}", i % 7),
+                &format!("2024-01-{:02}T10:00:00Z", i % 28 + 1),
+            ));
+        }
+        spikes[1].resolved = Some(true);
+        spikes
+    }
+
+    #[test]
+    fn test_pack_with_room_keeps_everything_in_full() {
+        let pack = generate_context_pack(&project(), 100_000);
+        assert_eq!(pack.matches("### ID:").count(), 19);
+        assert!(pack.contains("## 🕒 RECENT FEEDBACK"));
+        // Only the resolved spike is left out
+        assert!(pack.contains("To fit ~100000 tokens: 1 resolved spike."));
+    }
+
+    #[test]
+    fn test_pack_stays_within_budget_and_states_omissions() {
+        let spikes = project();
+        for max in [300, 800, 2000] {
+            let pack = generate_context_pack(&spikes, max);
+            assert!(estimate_tokens(&pack) <= max, "{} tokens for a budget of {}", estimate_tokens(&pack), max);
+            assert!(pack.contains("## ✂️ OMITTED"), "budget {}:\n{}", max, pack);
+        }
+
+        let pack = generate_context_pack(&spikes, 800);
+        // Blocking spikes come before anything else, no ratings first
+        assert!(pack.contains("## ⚠️ CRITICAL ISSUES"));
+        assert!(pack.contains("`s36` **no**") || pack.contains("### ID: `s36`"), "{}", pack);
+        assert!(!pack.contains("## 🕒 RECENT FEEDBACK"));
+        assert!(pack.contains("older open spikes"));
+    }
+
+    #[test]
+    fn test_pack_upgrades_blocking_spikes_in_priority_order() {
+        let spikes = vec![
+            spike("meh1", Rating::Meh, ".a", "2024-01-03T10:00:00Z"),
+            spike("no-old", Rating::No, ".b", "2024-01-01T10:00:00Z"),
+            spike("no-new", Rating::No, ".c", "2024-01-02T10:00:00Z"),
+        ];
+        let pack = generate_context_pack(&spikes, 1000);
+        let order: Vec<usize> = ["no-new", "no-old", "meh1"].iter().map(|id| pack.find(id).unwrap()).collect();
+        assert!(order[0] < order[1] && order[1] < order[2], "{}", pack);
+        assert!(!pack.contains("## ✂️ OMITTED"));
+    }
+}
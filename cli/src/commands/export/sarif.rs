@@ -0,0 +1,192 @@
+//! SARIF 2.1.0 output, so code-scanning UIs show unresolved feedback next
+//! to linter findings. Element spikes point at their element's start tag
+//! when the page can be found in the project directory.
+
+use serde_json::{json, Value};
+
+use crate::pages::Site;
+use crate::spike::{Rating, Spike, SpikeType};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// One rule per rating, so scanners can group and filter by it
+const RULES: [(&str, &str); 5] = [
+    ("spikes/no", "Reviewer rated this no"),
+    ("spikes/meh", "Reviewer rated this meh"),
+    ("spikes/like", "Reviewer rated this like"),
+    ("spikes/love", "Reviewer rated this love"),
+    ("spikes/unrated", "Reviewer left unrated feedback"),
+];
+
+fn rule_id(rating: Option<&Rating>) -> String {
+    format!("spikes/{}", rating.map(|r| r.to_string()).unwrap_or_else(|| "unrated".to_string()))
+}
+
+/// SARIF level: no is an error, meh a warning, everything else a note
+fn level(rating: Option<&Rating>) -> &'static str {
+    match rating {
+        Some(Rating::No) => "error",
+        Some(Rating::Meh) => "warning",
+        _ => "note",
+    }
+}
+
+fn message(spike: &Spike) -> String {
+    let target = match (&spike.spike_type, &spike.selector) {
+        (SpikeType::Element, Some(selector)) => selector.as_str(),
+        _ => "page",
+    };
+    let comment = if spike.comments.trim().is_empty() {
+        "(no comment)"
+    } else {
+        spike.comments.trim()
+    };
+    format!("{} — {} on {} ({})", comment, spike.reviewer.name, target, spike.rating_str())
+}
+
+fn location(spike: &Spike, site: Option<&Site>) -> Value {
+    let page = site.and_then(|site| site.resolve(spike));
+    let mut physical = json!({
+        "artifactLocation": { "uri": page.map(|p| p.path.as_str()).unwrap_or(&spike.page) }
+    });
+    if let Some(page) = page {
+        let (line, column) = page
+            .source_location(spike)
+            .map(|l| (l.line, l.column))
+            .unwrap_or((1, 1));
+        physical["region"] = json!({ "startLine": line, "startColumn": column });
+    }
+
+    let mut location = json!({ "physicalLocation": physical });
+    if let (SpikeType::Element, Some(selector)) = (&spike.spike_type, &spike.selector) {
+        location["logicalLocations"] = json!([{ "fullyQualifiedName": selector, "kind": "element" }]);
+    }
+    location
+}
+
+/// Build a SARIF log with a result per unresolved spike.
+///
+/// Duplicates are left out; their canonical spike stands for them. With a
+/// `site`, results point at the HTML file and, for elements, the line of
+/// the element's start tag.
+pub(super) fn generate_sarif(spikes: &[Spike], site: Option<&Site>) -> Value {
+    let results: Vec<Value> = spikes
+        .iter()
+        .filter(|s| !s.is_resolved() && s.duplicate_of.is_none())
+        .map(|spike| {
+            json!({
+                "ruleId": rule_id(spike.rating.as_ref()),
+                "level": level(spike.rating.as_ref()),
+                "message": { "text": message(spike) },
+                "locations": [location(spike, site)],
+                "partialFingerprints": { "spikeId/v1": spike.id },
+                "properties": {
+                    "spikeId": spike.id,
+                    "reviewer": spike.reviewer.name,
+                    "rating": spike.rating_str(),
+                    "timestamp": spike.timestamp,
+                    "url": spike.url,
+                }
+            })
+        })
+        .collect();
+
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|(id, description)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "spikes",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://spikes.sh",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::Reviewer;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn spike(id: &str, rating: Option<Rating>, selector: Option<&str>) -> Spike {
+        Spike {
+            spike_type: if selector.is_some() { SpikeType::Element } else { SpikeType::Page },
+            url: "http://localhost:3847/index.html".to_string(),
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: selector.map(String::from),
+            comments: "Too small".to_string(),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            ..Spike::test(id, "index.html", rating)
+        }
+    }
+
+    #[test]
+    fn test_sarif_maps_ratings_and_skips_resolved() {
+        let mut resolved = spike("s3", Some(Rating::No), None);
+        resolved.resolved = Some(true);
+        let spikes = vec![spike("s1", Some(Rating::No), Some(".hero")), spike("s2", Some(Rating::Love), None), resolved];
+        let sarif = generate_sarif(&spikes, None);
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 5);
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "spikes/no");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[0]["message"]["text"], "Too small — Alice on .hero (no)");
+        assert_eq!(results[0]["locations"][0]["logicalLocations"][0]["fullyQualifiedName"], ".hero");
+        // Without the site there is no region to point at
+        assert_eq!(results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], "index.html");
+        assert!(results[0]["locations"][0]["physicalLocation"].get("region").is_none());
+        assert_eq!(results[1]["level"], "note");
+    }
+
+    #[test]
+    fn test_sarif_resolves_element_lines() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("docs")).unwrap();
+        std::fs::write(
+            dir.path().join("docs/index.html"),
+            "<html>\n<body>\n  <p>Intro</p>\n  <h1 class=\"hero\">Hi</h1>\n</body>\n</html>\n",
+        )
+        .unwrap();
+        let site = Site::load(Path::new(dir.path())).unwrap();
+
+        let mut element = spike("s1", Some(Rating::Meh), Some(".hero"));
+        element.url = "http://localhost:3847/docs/index.html".to_string();
+        let mut page = spike("s2", Some(Rating::Meh), None);
+        page.url = element.url.clone();
+        let sarif = generate_sarif(&[element, page], Some(&site));
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+
+        let physical = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(physical["artifactLocation"]["uri"], "docs/index.html");
+        assert_eq!(physical["region"]["startLine"], 4);
+        assert_eq!(physical["region"]["startColumn"], 3);
+        assert_eq!(results[0]["level"], "warning");
+        // Page spikes point at the top of the file
+        assert_eq!(results[1]["locations"][0]["physicalLocation"]["region"]["startLine"], 1);
+    }
+}
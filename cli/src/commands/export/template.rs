@@ -0,0 +1,263 @@
+//! User-defined export templates, rendered with Tera.
+//!
+//! `--template <name>` looks for a file at that path, then in
+//! `.spikes/templates/` (with or without a `.tera` extension), then among
+//! the bundled templates. `cursor-context` and `claude-context` are bundled
+//! templates too. Templates whose name ends in `.html` or `.xml` are
+//! autoescaped.
+//!
+//! The context every template gets:
+//!
+//! | Name | Contents |
+//! |------|----------|
+//! | `project` | Project key of the exported spikes |
+//! | `generated` | Render time, e.g. `2024-01-15 10:00:00 UTC` |
+//! | `spikes` | Every exported spike, see below |
+//! | `blocking` | Unresolved meh/no spikes, duplicates left out |
+//! | `hotspots` | `{selector, count}` per element, most feedback first |
+//! | `contested` | Polarizing elements as in `hotspots --consensus --json`, plus `split` |
+//! | `elements` | `{selector, spikes}` per element, by selector |
+//! | `stats` | As in `spikes stats --json` |
+//! | `viewports` | `stats.viewports`, or empty when no spike has a viewport |
+//!
+//! Spikes have the fields of `export --format json`, plus `blocking`,
+//! `agreement` ("+2 reviewers agree") and `contested` ("1 love/like vs 2
+//! meh/no"). Filters `score` (2.25 → "2.25 (like)") and `percent` (0.5 →
+//! "50%") are available besides Tera's built-ins.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::commands::dedupe::{agree_counts, agree_label};
+use crate::commands::hotspots::compute_hotspots;
+use crate::commands::stats::{compute_stats, format_score};
+use crate::config::ViewportConfig;
+use crate::error::{Error, Result};
+use crate::selector::group_elements;
+use crate::spike::Spike;
+
+use super::{contested_elements, contested_for, is_blocking, split_label};
+
+/// Where projects keep their own templates
+pub const TEMPLATE_DIR: &str = ".spikes/templates";
+
+const BUNDLED: [(&str, &str); 2] = [
+    ("cursor-context", include_str!("../../../templates/export/cursor-context.tera")),
+    ("claude-context", include_str!("../../../templates/export/claude-context.tera")),
+];
+
+/// A template ready to render
+pub struct Template {
+    pub name: String,
+    pub source: String,
+}
+
+impl Template {
+    /// A bundled template by name
+    pub fn bundled(name: &str) -> Option<Template> {
+        BUNDLED.iter().find(|(n, _)| *n == name).map(|(n, source)| Template {
+            name: n.to_string(),
+            source: source.to_string(),
+        })
+    }
+
+    /// Find a template by path, then in `.spikes/templates/`, then bundled
+    pub fn find(name: &str) -> Result<Template> {
+        let dir = Path::new(TEMPLATE_DIR);
+        let candidates = [
+            PathBuf::from(name),
+            dir.join(name),
+            dir.join(format!("{}.tera", name)),
+        ];
+        if let Some(path) = candidates.iter().find(|p| p.is_file()) {
+            let file_name = path.file_name().map(|f| f.to_string_lossy().into_owned());
+            return Ok(Template {
+                // Tera decides on autoescaping by name, so drop the .tera suffix
+                name: file_name
+                    .as_deref()
+                    .map(|f| f.trim_end_matches(".tera").to_string())
+                    .unwrap_or_else(|| name.to_string()),
+                source: fs::read_to_string(path)?,
+            });
+        }
+        Template::bundled(name).ok_or_else(|| Error::TemplateNotFound(name.to_string()))
+    }
+
+    /// Render against a context built by [`context`]
+    pub fn render(&self, context: &Value) -> Result<String> {
+        let mut tera = tera::Tera::default();
+        tera.register_filter("score", score_filter);
+        tera.register_filter("percent", percent_filter);
+        tera.add_raw_template(&self.name, &self.source)
+            .map_err(|e| template_error(&self.name, e))?;
+        let context = tera::Context::from_value(context.clone()).map_err(|e| template_error(&self.name, e))?;
+        tera.render(&self.name, &context)
+            .map_err(|e| template_error(&self.name, e))
+    }
+}
+
+/// Templates in `.spikes/templates/` and bundled ones, as `(name, origin)`
+pub fn available() -> Vec<(String, String)> {
+    let mut names: Vec<(String, String)> = fs::read_dir(TEMPLATE_DIR)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .map(|e| (e.file_name().to_string_lossy().into_owned(), TEMPLATE_DIR.to_string()))
+        .collect();
+    names.sort();
+    names.extend(BUNDLED.iter().map(|(name, _)| (name.to_string(), "bundled".to_string())));
+    names
+}
+
+/// Tera errors nest the useful part (line, unknown variable) in their sources
+fn template_error(name: &str, error: tera::Error) -> Error {
+    let mut message = format!("{}: {}", name, error);
+    let mut source = std::error::Error::source(&error);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    Error::Template(message)
+}
+
+fn score_filter(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    Ok(Value::String(format_score(value.as_f64())))
+}
+
+fn percent_filter(value: &Value, _: &HashMap<String, Value>) -> tera::Result<Value> {
+    match value.as_f64() {
+        Some(ratio) => Ok(Value::String(format!("{:.0}%", ratio * 100.0))),
+        None => Err(tera::Error::msg(format!("percent expects a number, got {}", value))),
+    }
+}
+
+/// Build the documented template context for a set of spikes
+pub fn context(spikes: &[Spike], breakpoints: &ViewportConfig) -> Value {
+    let agree = agree_counts(spikes);
+    let contested = contested_elements(spikes);
+    let spike_value = |spike: &Spike| {
+        let mut value = serde_json::to_value(spike).unwrap_or(Value::Null);
+        value["blocking"] = json!(is_blocking(spike));
+        value["agreement"] = match agree.get(&spike.id).filter(|c| **c > 0) {
+            Some(&count) => json!(agree_label(count)),
+            None => Value::Null,
+        };
+        value["contested"] = match contested_for(&contested, &spike.id) {
+            Some(score) => json!(split_label(score)),
+            None => Value::Null,
+        };
+        value
+    };
+
+    let stats = compute_stats(spikes, breakpoints);
+    let viewports = if stats.viewports.iter().any(|v| v.range.is_some()) {
+        json!(stats.viewports)
+    } else {
+        json!([])
+    };
+    let mut elements = group_elements(spikes);
+    elements.sort_by(|a, b| a.selector.cmp(&b.selector));
+
+    json!({
+        "project": spikes.first().map(|s| s.project_key.as_str()).unwrap_or("unknown"),
+        "generated": chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+        "spikes": spikes.iter().map(spike_value).collect::<Vec<_>>(),
+        "blocking": spikes
+            .iter()
+            .filter(|s| is_blocking(s) && s.duplicate_of.is_none())
+            .map(spike_value)
+            .collect::<Vec<_>>(),
+        "hotspots": compute_hotspots(spikes)
+            .into_iter()
+            .map(|(selector, count)| json!({ "selector": selector, "count": count }))
+            .collect::<Vec<_>>(),
+        "contested": contested
+            .iter()
+            .map(|score| {
+                let mut value = json!(score);
+                value["split"] = json!(split_label(score));
+                value
+            })
+            .collect::<Vec<_>>(),
+        "elements": elements
+            .iter()
+            .map(|group| json!({
+                "selector": group.selector,
+                "spikes": group.spikes.iter().map(|s| spike_value(s)).collect::<Vec<_>>(),
+            }))
+            .collect::<Vec<_>>(),
+        "stats": stats,
+        "viewports": viewports,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Rating, Reviewer, SpikeType};
+
+    fn spike(id: &str, rating: Rating, comments: &str) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            project_key: "acme".to_string(),
+            reviewer: Reviewer {
+                id: id.to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: Some(".hero".to_string()),
+            comments: comments.to_string(),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            ..Spike::test(id, "index.html", Some(rating))
+        }
+    }
+
+    #[test]
+    fn test_render_user_template_against_context() {
+        let spikes = vec![spike("s1", Rating::No, "Too <loud>"), spike("s2", Rating::Love, "Great")];
+        let context = context(&spikes, &ViewportConfig::default());
+        let template = Template {
+            name: "report.md".to_string(),
+            source: "{{ project }}: {{ blocking | length }} blocking\n\
+                {% for h in hotspots %}{{ h.selector }}={{ h.count }}{% endfor %}\n\
+                {% for c in contested %}{{ c.split }} {{ c.agreement | percent }}{% endfor %}\n\
+                {{ stats.average | score }} {{ spikes.0.comments }}"
+                .to_string(),
+        };
+
+        assert_eq!(
+            template.render(&context).unwrap(),
+            "acme: 1 blocking\n.hero=2\n1 love/like vs 1 meh/no 0%\n1.50 (like) Too <loud>"
+        );
+        assert_eq!(context["spikes"][0]["contested"], "1 love/like vs 1 meh/no");
+        assert_eq!(context["viewports"], json!([]));
+    }
+
+    #[test]
+    fn test_html_templates_are_autoescaped() {
+        let context = context(&[spike("s1", Rating::No, "Too <loud>")], &ViewportConfig::default());
+        let template = Template {
+            name: "report.html".to_string(),
+            source: "{{ spikes.0.comments }}".to_string(),
+        };
+        assert_eq!(template.render(&context).unwrap(), "Too &lt;loud&gt;");
+    }
+
+    #[test]
+    fn test_template_errors_name_the_problem() {
+        let template = Template {
+            name: "broken.md".to_string(),
+            source: "{{ nope.field }}".to_string(),
+        };
+        let err = template.render(&json!({})).unwrap_err().to_string();
+        assert!(err.contains("broken.md"), "got: {}", err);
+        assert!(err.contains("nope.field"), "got: {}", err);
+
+        assert!(matches!(Template::find("no-such-template"), Err(Error::TemplateNotFound(_))));
+        assert!(Template::find("cursor-context").is_ok());
+    }
+}
@@ -0,0 +1,353 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::{Error, Result};
+use crate::identity::load_canonical_spikes;
+use crate::spike::{Rating, Spike, SpikeType};
+
+use super::clusters::NormalizedBox;
+use super::list::SpikeFilter;
+
+/// Default reference viewport width boxes are scaled to
+pub const DEFAULT_WIDTH: u32 = 1280;
+
+/// Opacity of one box; overlapping feedback stacks up into heat
+const FILL_OPACITY: f64 = 0.35;
+const STROKE_OPACITY: f64 = 0.8;
+const BACKGROUND: [u8; 3] = [0xf8, 0xf8, 0xf8];
+/// Height of the SVG title and legend band
+const HEADER_HEIGHT: u32 = 32;
+/// Keep pathological boxes from producing gigantic images
+const MAX_HEIGHT_RATIO: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HeatmapFormat {
+    Svg,
+    Png,
+}
+
+impl HeatmapFormat {
+    /// PNG for `.png` files, SVG for everything else
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("png") => HeatmapFormat::Png,
+            _ => HeatmapFormat::Svg,
+        }
+    }
+}
+
+pub struct HeatmapOptions {
+    pub page: String,
+    pub out: PathBuf,
+    /// Reference viewport width in pixels
+    pub width: u32,
+    /// Output format; inferred from `out` when not given
+    pub format: Option<HeatmapFormat>,
+}
+
+/// One spike's box, scaled to the reference width
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub color: [u8; 3],
+    /// Tooltip in the SVG: selector, rating and comment
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heatmap {
+    pub width: u32,
+    pub height: u32,
+    /// Largest boxes first, so smaller elements stay visible on top
+    pub boxes: Vec<HeatBox>,
+}
+
+/// Fill color of a rating
+pub fn rating_color(rating: Option<&Rating>) -> [u8; 3] {
+    match rating {
+        Some(Rating::Love) => [0x2e, 0xcc, 0x71],
+        Some(Rating::Like) => [0x34, 0x98, 0xdb],
+        Some(Rating::Meh) => [0xf1, 0xc4, 0x0f],
+        Some(Rating::No) => [0xe7, 0x4c, 0x3c],
+        None => [0x95, 0xa5, 0xa6],
+    }
+}
+
+/// CSS hex notation, e.g. "#e74c3c"
+pub fn hex(color: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// Lay out element spikes on a canvas `width` pixels wide.
+///
+/// Each box is normalized to its reviewer's viewport width and scaled to the
+/// reference width. Spikes without a bounding box or viewport are skipped.
+pub fn build_heatmap(spikes: &[Spike], width: u32) -> Heatmap {
+    let scale = width as f64;
+    let mut boxes: Vec<HeatBox> = spikes
+        .iter()
+        .filter(|s| s.spike_type == SpikeType::Element)
+        .filter_map(|s| {
+            let norm = NormalizedBox::from_spike(s)?;
+            let mut label = format!(
+                "{} — {}",
+                s.selector.as_deref().unwrap_or("element"),
+                s.rating_str()
+            );
+            if !s.comments.is_empty() {
+                label.push_str(&format!(": {}", s.comments));
+            }
+            Some(HeatBox {
+                x: norm.x * scale,
+                y: norm.y * scale,
+                width: norm.width * scale,
+                height: norm.height * scale,
+                color: rating_color(s.rating.as_ref()),
+                label,
+            })
+        })
+        .collect();
+    boxes.sort_by(|a, b| (b.width * b.height).total_cmp(&(a.width * a.height)));
+
+    let bottom = boxes.iter().map(|b| b.y + b.height).fold(0.0, f64::max);
+    let height = (bottom.ceil() as u32).clamp(1, width.saturating_mul(MAX_HEIGHT_RATIO));
+
+    Heatmap { width, height, boxes }
+}
+
+/// Escape text for XML and HTML content or double-quoted attributes
+pub fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render as SVG, with a title and rating legend above the page
+pub fn render_svg(heatmap: &Heatmap, title: &str) -> String {
+    let total_height = heatmap.height + HEADER_HEIGHT;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"system-ui, sans-serif\" font-size=\"13\">\n",
+        w = heatmap.width,
+        h = total_height
+    );
+    svg.push_str(&format!(
+        "  <rect width=\"{}\" height=\"{}\" fill=\"#222222\"/>\n",
+        heatmap.width, HEADER_HEIGHT
+    ));
+    svg.push_str(&format!(
+        "  <text x=\"10\" y=\"21\" fill=\"#ffffff\">{} — {} spike{}</text>\n",
+        escape_xml(title),
+        heatmap.boxes.len(),
+        if heatmap.boxes.len() == 1 { "" } else { "s" }
+    ));
+    let legend = [Some(Rating::Love), Some(Rating::Like), Some(Rating::Meh), Some(Rating::No)];
+    for (i, rating) in legend.iter().enumerate() {
+        let x = heatmap.width as i64 - 70 * (legend.len() - i) as i64;
+        svg.push_str(&format!(
+            "  <rect x=\"{}\" y=\"10\" width=\"12\" height=\"12\" fill=\"{}\"/><text x=\"{}\" y=\"21\" fill=\"#ffffff\">{}</text>\n",
+            x,
+            hex(rating_color(rating.as_ref())),
+            x + 17,
+            rating.as_ref().map(|r| r.to_string()).unwrap_or_default()
+        ));
+    }
+
+    svg.push_str(&format!("  <g transform=\"translate(0 {})\">\n", HEADER_HEIGHT));
+    svg.push_str(&format!(
+        "    <rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+        heatmap.width,
+        heatmap.height,
+        hex(BACKGROUND)
+    ));
+    for b in &heatmap.boxes {
+        let color = hex(b.color);
+        svg.push_str(&format!(
+            "    <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\" fill-opacity=\"{}\" stroke=\"{}\" stroke-opacity=\"{}\"><title>{}</title></rect>\n",
+            b.x,
+            b.y,
+            b.width,
+            b.height,
+            color,
+            FILL_OPACITY,
+            color,
+            STROKE_OPACITY,
+            escape_xml(&b.label)
+        ));
+    }
+    svg.push_str("  </g>\n</svg>\n");
+    svg
+}
+
+/// Blend `color` over the pixels in `[x0, x1) × [y0, y1)`
+fn blend(pixels: &mut [f64], width: u32, (x0, y0, x1, y1): (u32, u32, u32, u32), color: [u8; 3], alpha: f64) {
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let i = ((y * width + x) * 3) as usize;
+            for c in 0..3 {
+                pixels[i + c] = pixels[i + c] * (1.0 - alpha) + color[c] as f64 * alpha;
+            }
+        }
+    }
+}
+
+/// Rasterize to PNG bytes (the page area only, no title or legend)
+pub fn render_png(heatmap: &Heatmap) -> Result<Vec<u8>> {
+    let (width, height) = (heatmap.width, heatmap.height);
+    let mut pixels: Vec<f64> = (0..width as usize * height as usize)
+        .flat_map(|_| BACKGROUND.map(f64::from))
+        .collect();
+
+    let clamp_x = |v: f64| (v.round().max(0.0) as u32).min(width);
+    let clamp_y = |v: f64| (v.round().max(0.0) as u32).min(height);
+    for b in &heatmap.boxes {
+        let (x0, y0) = (clamp_x(b.x), clamp_y(b.y));
+        let (x1, y1) = (clamp_x(b.x + b.width), clamp_y(b.y + b.height));
+        if x0 >= x1 || y0 >= y1 {
+            continue;
+        }
+        blend(&mut pixels, width, (x0, y0, x1, y1), b.color, FILL_OPACITY);
+        // One pixel outline
+        for edge in [
+            (x0, y0, x1, y0 + 1),
+            (x0, y1 - 1, x1, y1),
+            (x0, y0 + 1, x0 + 1, y1 - 1),
+            (x1 - 1, y0 + 1, x1, y1 - 1),
+        ] {
+            if edge.0 < edge.2 && edge.1 < edge.3 {
+                blend(&mut pixels, width, edge, b.color, STROKE_OPACITY);
+            }
+        }
+    }
+
+    let data: Vec<u8> = pixels.iter().map(|v| v.round().clamp(0.0, 255.0) as u8).collect();
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let to_io = |e: png::EncodingError| Error::Io(std::io::Error::other(e.to_string()));
+        let mut writer = encoder.write_header().map_err(to_io)?;
+        writer.write_image_data(&data).map_err(to_io)?;
+    }
+    Ok(bytes)
+}
+
+pub fn run(options: HeatmapOptions) -> Result<()> {
+    let filter = SpikeFilter {
+        page: Some(options.page.clone()),
+        ..Default::default()
+    };
+    let spikes: Vec<Spike> = load_canonical_spikes()?
+        .into_iter()
+        .filter(|s| filter.matches(s))
+        .collect();
+    let heatmap = build_heatmap(&spikes, options.width);
+
+    // Fail rather than leave an old image at `--out` for scripts to pick up
+    if heatmap.boxes.is_empty() {
+        return Err(Error::EmptyHeatmap(options.page));
+    }
+
+    let format = options.format.unwrap_or_else(|| HeatmapFormat::from_path(&options.out));
+    // The SVG adds the title and legend band above the page
+    let height = match format {
+        HeatmapFormat::Svg => {
+            fs::write(&options.out, render_svg(&heatmap, &options.page))?;
+            heatmap.height + HEADER_HEIGHT
+        }
+        HeatmapFormat::Png => {
+            fs::write(&options.out, render_png(&heatmap)?)?;
+            heatmap.height
+        }
+    };
+
+    println!(
+        "Wrote {} ({}x{}, {} spike{}).",
+        options.out.display(),
+        heatmap.width,
+        height,
+        heatmap.boxes.len(),
+        if heatmap.boxes.len() == 1 { "" } else { "s" }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{BoundingBox, Reviewer, Viewport};
+
+    fn element(id: &str, rating: Rating, (x, y, w, h): (f64, f64, f64, f64), viewport_width: u32) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: Some(".hero".to_string()),
+            bounding_box: Some(BoundingBox { x, y, width: w, height: h }),
+            comments: "Too <loud>".to_string(),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            viewport: Some(Viewport {
+                width: viewport_width,
+                height: 800,
+            }),
+            ..Spike::test(id, "index.html", Some(rating))
+        }
+    }
+
+    #[test]
+    fn test_build_heatmap_normalizes_to_reference_width() {
+        let mut page_spike = element("s3", Rating::Like, (0.0, 0.0, 10.0, 10.0), 1000);
+        page_spike.spike_type = SpikeType::Page;
+        let spikes = vec![
+            // Same element seen on a phone and on a wide screen
+            element("s1", Rating::No, (0.0, 100.0, 195.0, 50.0), 390),
+            element("s2", Rating::Love, (0.0, 200.0, 960.0, 100.0), 1920),
+            page_spike,
+        ];
+        let heatmap = build_heatmap(&spikes, 1000);
+
+        assert_eq!(heatmap.width, 1000);
+        assert_eq!(heatmap.boxes.len(), 2);
+        // Larger box first
+        assert_eq!(heatmap.boxes[0].width, 500.0);
+        assert_eq!(heatmap.boxes[0].color, rating_color(Some(&Rating::No)));
+        assert_eq!(heatmap.boxes[1].y, 104.16666666666667);
+        assert_eq!(heatmap.height, 385);
+    }
+
+    #[test]
+    fn test_render_svg_escapes_labels() {
+        let heatmap = build_heatmap(&[element("s1", Rating::No, (10.0, 10.0, 100.0, 50.0), 1000)], 1000);
+        let svg = render_svg(&heatmap, "index.html");
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"1000\" height=\"92\""));
+        assert!(svg.contains("index.html — 1 spike</text>"));
+        assert!(svg.contains("fill=\"#e74c3c\" fill-opacity=\"0.35\""));
+        assert!(svg.contains("<title>.hero — no: Too &lt;loud&gt;</title>"));
+    }
+
+    #[test]
+    fn test_render_png_blends_boxes() {
+        let heatmap = build_heatmap(&[element("s1", Rating::No, (10.0, 10.0, 20.0, 20.0), 100)], 100);
+        let bytes = render_png(&heatmap).unwrap();
+
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data).unwrap();
+        assert_eq!((info.width, info.height), (100, 30));
+
+        let pixel = |x: usize, y: usize| &data[(y * 100 + x) * 3..(y * 100 + x) * 3 + 3];
+        assert_eq!(pixel(0, 0), &BACKGROUND);
+        // Inside the box: background blended toward red
+        let inside = pixel(20, 20);
+        assert!(inside[0] > inside[1] && inside[1] < BACKGROUND[1]);
+    }
+}
@@ -1,24 +1,57 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+use serde::Serialize;
 
 use crate::error::Result;
-use crate::output::{print_hotspots_table, print_json};
-use crate::spike::SpikeType;
-use crate::storage::load_spikes;
-
-pub fn run(json: bool) -> Result<()> {
-    let spikes = load_spikes()?;
-
-    let mut counts: HashMap<String, usize> = HashMap::new();
-    for spike in spikes {
-        if spike.spike_type == SpikeType::Element {
-            if let Some(selector) = spike.selector {
-                *counts.entry(selector).or_insert(0) += 1;
-            }
+use crate::identity::load_canonical_spikes;
+use crate::output::{print_consensus_table, print_hotspots_table, print_json};
+use crate::selector::{group_elements, ElementGroup};
+use crate::spike::{Rating, Spike};
+
+/// Standard deviation of two reviewers at opposite ends (no vs love)
+const MAX_SPREAD: f64 = 1.5;
+
+/// Share of reviewers the smaller camp (love/like vs meh/no) needs for an
+/// element to count as polarizing
+const POLARIZING_SHARE: f64 = 1.0 / 3.0;
+
+/// How much the reviewers of one element agree
+#[derive(Debug, Clone, Serialize)]
+pub struct Consensus {
+    pub selector: String,
+    /// Reviewers who rated the element (each counted once, latest rating)
+    pub reviewers: usize,
+    /// Mean rating score (no = 0 .. love = 3)
+    pub average: f64,
+    /// Standard deviation of the rating scores
+    pub spread: f64,
+    /// 1 when everyone gave the same rating, 0 at maximum spread
+    pub agreement: f64,
+    /// Reviewers who rated love or like
+    pub positive: usize,
+    /// Reviewers who rated meh or no
+    pub negative: usize,
+    /// Both camps hold a real share of the reviewers
+    pub polarizing: bool,
+    /// Spikes on the element, for cross-referencing
+    #[serde(skip)]
+    pub spike_ids: Vec<String>,
+}
+
+pub fn run(json: bool, consensus: bool) -> Result<()> {
+    let spikes = load_canonical_spikes()?;
+
+    if consensus {
+        let scores = compute_consensus(&spikes);
+        if json {
+            print_json(&scores);
+        } else {
+            print_consensus_table(&scores);
         }
+        return Ok(());
     }
 
-    let mut hotspots: Vec<(String, usize)> = counts.into_iter().collect();
-    hotspots.sort_by_key(|item| std::cmp::Reverse(item.1));
+    let hotspots = compute_hotspots(&spikes);
 
     if json {
         let output: Vec<serde_json::Value> = hotspots
@@ -37,3 +70,154 @@ pub fn run(json: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// Count element feedback per element, most feedback first.
+///
+/// Equivalent selectors count as one element, reported under their most
+/// common canonical spelling.
+pub fn compute_hotspots(spikes: &[Spike]) -> Vec<(String, usize)> {
+    hotspot_groups(spikes)
+        .into_iter()
+        .map(|g| (g.selector, g.spikes.len()))
+        .collect()
+}
+
+/// Element groups in hotspot order, for callers that need the spikes too
+pub fn hotspot_groups(spikes: &[Spike]) -> Vec<ElementGroup<'_>> {
+    let mut groups = group_elements(spikes);
+    groups.sort_by(|a, b| b.spikes.len().cmp(&a.spikes.len()).then_with(|| a.selector.cmp(&b.selector)));
+    groups
+}
+
+/// Score reviewer agreement on every element rated by two or more
+/// reviewers, least agreement first.
+///
+/// A reviewer who rated an element several times counts once, with their
+/// latest rating.
+pub fn compute_consensus(spikes: &[Spike]) -> Vec<Consensus> {
+    let mut scores: Vec<Consensus> = group_elements(spikes)
+        .into_iter()
+        .filter_map(|group| {
+            let mut latest: BTreeMap<&str, &Spike> = BTreeMap::new();
+            for spike in group.spikes.iter().filter(|s| s.rating.is_some()) {
+                let entry = latest.entry(spike.reviewer.id.as_str()).or_insert(spike);
+                if spike.timestamp > entry.timestamp {
+                    *entry = spike;
+                }
+            }
+            if latest.len() < 2 {
+                return None;
+            }
+
+            let ratings: Vec<&Rating> = latest.values().filter_map(|s| s.rating.as_ref()).collect();
+            let n = ratings.len() as f64;
+            let average = ratings.iter().map(|r| r.score()).sum::<f64>() / n;
+            let variance = ratings.iter().map(|r| (r.score() - average).powi(2)).sum::<f64>() / n;
+            let spread = variance.sqrt();
+            let negative = ratings.iter().filter(|r| r.is_negative()).count();
+            let positive = ratings.len() - negative;
+
+            Some(Consensus {
+                selector: group.selector,
+                reviewers: ratings.len(),
+                average,
+                spread,
+                agreement: (1.0 - spread / MAX_SPREAD).max(0.0),
+                positive,
+                negative,
+                polarizing: positive.min(negative) as f64 >= n * POLARIZING_SHARE,
+                spike_ids: group.spikes.iter().map(|s| s.id.clone()).collect(),
+            })
+        })
+        .collect();
+
+    scores.sort_by(|a, b| {
+        a.agreement
+            .total_cmp(&b.agreement)
+            .then_with(|| b.reviewers.cmp(&a.reviewers))
+            .then_with(|| a.selector.cmp(&b.selector))
+    });
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Reviewer, SpikeType};
+
+    fn rate(id: &str, selector: &str, reviewer: &str, rating: Rating, timestamp: &str) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            reviewer: Reviewer {
+                id: reviewer.to_string(),
+                name: reviewer.to_string(),
+                email: None,
+            },
+            selector: Some(selector.to_string()),
+            timestamp: timestamp.to_string(),
+            ..Spike::test(id, "index.html", Some(rating))
+        }
+    }
+
+    #[test]
+    fn test_consensus_scores_agreement_and_polarization() {
+        let t = "2024-01-15T10:00:00Z";
+        let spikes = vec![
+            // Split: two love it, two hate it
+            rate("s1", ".hero", "a", Rating::Love, t),
+            rate("s2", ".hero", "b", Rating::Love, t),
+            rate("s3", ".hero", "c", Rating::No, t),
+            rate("s4", ".hero", "d", Rating::No, t),
+            // Everyone agrees
+            rate("s5", ".cta", "a", Rating::No, t),
+            rate("s6", ".cta", "b", Rating::No, t),
+            rate("s7", ".cta", "c", Rating::No, t),
+            // A single reviewer has no consensus to score
+            rate("s8", ".footer", "a", Rating::Meh, t),
+        ];
+        let scores = compute_consensus(&spikes);
+
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0].selector, ".hero");
+        assert_eq!(scores[0].spread, 1.5);
+        assert_eq!(scores[0].agreement, 0.0);
+        assert!(scores[0].polarizing);
+        assert_eq!(scores[0].spike_ids, vec!["s1", "s2", "s3", "s4"]);
+
+        assert_eq!(scores[1].selector, ".cta");
+        assert_eq!(scores[1].agreement, 1.0);
+        assert_eq!((scores[1].positive, scores[1].negative), (0, 3));
+        assert!(!scores[1].polarizing);
+    }
+
+    #[test]
+    fn test_consensus_uses_latest_rating_per_reviewer() {
+        let spikes = vec![
+            rate("s1", ".hero", "a", Rating::No, "2024-01-15T10:00:00Z"),
+            rate("s2", ".hero", "a", Rating::Love, "2024-01-16T10:00:00Z"),
+            rate("s3", ".hero", "b", Rating::Love, "2024-01-15T10:00:00Z"),
+            // One dissenter among four is not polarizing
+            rate("s4", ".hero", "c", Rating::Love, "2024-01-15T10:00:00Z"),
+            rate("s5", ".hero", "d", Rating::Meh, "2024-01-15T10:00:00Z"),
+        ];
+        let scores = compute_consensus(&spikes);
+
+        assert_eq!(scores[0].reviewers, 4);
+        assert_eq!((scores[0].positive, scores[0].negative), (3, 1));
+        assert!(!scores[0].polarizing);
+    }
+
+    #[test]
+    fn test_consensus_counts_tagged_and_untagged_selectors_as_one_element() {
+        let t = "2024-01-15T10:00:00Z";
+        let spikes = vec![
+            rate("s1", "h1.hero", "a", Rating::Love, t),
+            rate("s2", ".hero", "b", Rating::No, t),
+        ];
+        let scores = compute_consensus(&spikes);
+
+        assert_eq!(scores.len(), 1);
+        assert!(scores[0].polarizing);
+        assert_eq!(scores[0].spike_ids, vec!["s1", "s2"]);
+    }
+}
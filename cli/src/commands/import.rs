@@ -0,0 +1,546 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::output::print_json;
+use crate::rounds::Rounds;
+use crate::spike::{Rating, Spike};
+use crate::storage::{load_spikes, save_spikes};
+
+use super::export::columns::{self, Column};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    Csv,
+    Json,
+    Jsonl,
+}
+
+impl ImportFormat {
+    /// JSON for `.json`, JSONL for `.jsonl`/`.ndjson`, CSV for everything else
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+            Some("json") => ImportFormat::Json,
+            Some("jsonl") | Some("ndjson") => ImportFormat::Jsonl,
+            _ => ImportFormat::Csv,
+        }
+    }
+}
+
+pub struct ImportOptions {
+    pub file: PathBuf,
+    /// Input format; inferred from the file extension when not given
+    pub format: Option<ImportFormat>,
+    /// `SOURCE=FIELD` pairs mapping other tools' columns or keys to spike fields
+    pub map: Vec<String>,
+    /// Project key for records without one (default: the configured key)
+    pub project: Option<String>,
+    pub dry_run: bool,
+    pub json: bool,
+}
+
+/// A spike-shaped JSON object, or why it couldn't be read
+type Record = std::result::Result<Map<String, Value>, String>;
+
+/// Reviewer name for records that don't name one
+const DEFAULT_REVIEWER: &str = "Imported";
+
+/// Parse `--map` pairs into `(source, column)`, checking the column exists
+fn parse_map(pairs: &[String]) -> Result<Vec<(String, Column)>> {
+    pairs
+        .iter()
+        .map(|pair| {
+            let (source, target) = pair
+                .split_once('=')
+                .ok_or_else(|| Error::InvalidImport(format!("--map expects SOURCE=FIELD, got '{}'", pair)))?;
+            let column = columns::find(target.trim()).ok_or_else(|| {
+                Error::InvalidImport(format!(
+                    "unknown field '{}' in --map. Fields: {}",
+                    target.trim(),
+                    columns::columns().iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
+                ))
+            })?;
+            Ok((source.trim().to_string(), column))
+        })
+        .collect()
+}
+
+fn set_path(record: &mut Map<String, Value>, path: &[String], value: Value) {
+    match path {
+        [] => {}
+        [key] => {
+            record.insert(key.to_string(), value);
+        }
+        [key, rest @ ..] => {
+            let child = record.entry(key.to_string()).or_insert_with(|| json!({}));
+            if !child.is_object() {
+                *child = json!({});
+            }
+            if let Value::Object(child) = child {
+                set_path(child, rest, value);
+            }
+        }
+    }
+}
+
+/// Turn a CSV row into a spike-shaped record. Empty cells are left out.
+fn csv_record(
+    headers: &[Option<Column>],
+    row: &csv::StringRecord,
+) -> Record {
+    let mut record = Map::new();
+    for (header, cell) in headers.iter().zip(row.iter()) {
+        let Some(column) = header else {
+            continue;
+        };
+        if cell.trim().is_empty() {
+            continue;
+        }
+        let value = column.kind.parse(json!(cell)).map_err(|e| format!("{}: {}", column.name, e))?;
+        set_path(&mut record, &column.path, value);
+    }
+    Ok(record)
+}
+
+/// Move mapped keys of a JSON record to the fields they stand for
+fn map_json_record(
+    mut record: Map<String, Value>,
+    map: &[(String, Column)],
+) -> Record {
+    for (source, column) in map {
+        let Some(value) = record.remove(source) else {
+            continue;
+        };
+        let value = column.kind.parse(value).map_err(|e| format!("{}: {}", column.name, e))?;
+        set_path(&mut record, &column.path, value);
+    }
+    Ok(record)
+}
+
+/// Page name from a URL's path: its last segment, or index.html for `/`
+fn page_from_url(url: &str) -> String {
+    let path = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let path = path.split(['?', '#']).next().unwrap_or("");
+    let path = path.split_once('/').map(|(_, p)| p).unwrap_or("");
+    match path.trim_end_matches('/').rsplit('/').next() {
+        Some(last) if !last.is_empty() => last.to_string(),
+        _ => "index.html".to_string(),
+    }
+}
+
+/// Keep RFC 3339 as is; read the usual spreadsheet date formats as UTC
+fn normalize_timestamp(text: &str) -> std::result::Result<String, String> {
+    if chrono::DateTime::parse_from_rfc3339(text).is_ok() {
+        return Ok(text.to_string());
+    }
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(time) = chrono::NaiveDateTime::parse_from_str(text, format) {
+            return Ok(time.and_utc().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        }
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        let time = date.and_hms_opt(0, 0, 0).expect("midnight is valid");
+        return Ok(time.and_utc().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    }
+    Err(format!("unrecognized timestamp '{}'", text))
+}
+
+/// IDs are typed as prefixes and shown truncated, so keep them to the
+/// characters the widget's own IDs use
+fn is_valid_id(id: &str) -> bool {
+    id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Fill in what a record leaves out and validate it into a spike
+fn to_spike(mut record: Map<String, Value>, project_key: &str, now: &str) -> std::result::Result<Spike, String> {
+    let text = |record: &Map<String, Value>, key: &str| {
+        record.get(key).and_then(Value::as_str).map(str::trim).filter(|s| !s.is_empty()).map(String::from)
+    };
+
+    match text(&record, "id") {
+        None => {
+            record.insert("id".into(), json!(nanoid::nanoid!(11)));
+        }
+        Some(id) if !is_valid_id(&id) => {
+            return Err(format!("invalid id '{}' (use ASCII letters, digits, '-', '_' or '.')", id));
+        }
+        Some(id) => {
+            record.insert("id".into(), json!(id));
+        }
+    }
+    if text(&record, "projectKey").is_none() {
+        record.insert("projectKey".into(), json!(project_key));
+    }
+    let url = text(&record, "url").unwrap_or_default();
+    if text(&record, "page").is_none() {
+        if url.is_empty() {
+            return Err("missing page (or a url to take it from)".to_string());
+        }
+        record.insert("page".into(), json!(page_from_url(&url)));
+    }
+    record.insert("url".into(), json!(url));
+    if text(&record, "type").is_none() {
+        let kind = if text(&record, "selector").is_some() { "element" } else { "page" };
+        record.insert("type".into(), json!(kind));
+    }
+
+    let reviewer = record.entry("reviewer").or_insert_with(|| json!({}));
+    if let Value::String(name) = reviewer {
+        *reviewer = json!({ "name": name.clone() });
+    }
+    let reviewer = reviewer.as_object_mut().ok_or("reviewer must be an object or a name")?;
+    if text(reviewer, "name").is_none() {
+        reviewer.insert("name".into(), json!(DEFAULT_REVIEWER));
+    }
+    if text(reviewer, "id").is_none() {
+        // Stable per name, so one person's imported spikes stay together
+        let name = text(reviewer, "name").unwrap_or_default();
+        let slug: String = name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect();
+        reviewer.insert("id".into(), json!(format!("import-{}", slug)));
+    }
+
+    match text(&record, "rating").map(|r| r.to_lowercase()) {
+        None => {
+            record.insert("rating".into(), Value::Null);
+        }
+        Some(r) if r == "-" || r == "unrated" => {
+            record.insert("rating".into(), Value::Null);
+        }
+        Some(r) => {
+            let rating: Rating = r.parse()?;
+            record.insert("rating".into(), json!(rating));
+        }
+    }
+    if !record.get("comments").is_some_and(Value::is_string) {
+        record.insert("comments".into(), json!(""));
+    }
+    let timestamp = match text(&record, "timestamp") {
+        Some(t) => normalize_timestamp(&t)?,
+        None => now.to_string(),
+    };
+    record.insert("timestamp".into(), json!(timestamp));
+    record.entry("viewport").or_insert(Value::Null);
+
+    serde_json::from_value(Value::Object(record)).map_err(|e| e.to_string())
+}
+
+/// Same feedback from the same person on the same target
+fn content_key(spike: &Spike) -> (String, Option<String>, String, String, String) {
+    (
+        spike.page.clone(),
+        spike.selector.clone(),
+        spike.reviewer.name.to_lowercase(),
+        spike.rating_str().to_string(),
+        spike.comments.trim().to_string(),
+    )
+}
+
+/// Read `(label, record)` pairs from a file; the label locates errors
+fn read_records(
+    content: &str,
+    format: ImportFormat,
+    map: &[(String, Column)],
+) -> Result<Vec<(String, Record)>> {
+    let object = |value: Value| match value {
+        Value::Object(record) => map_json_record(record, map),
+        other => Err(format!("expected an object, got {}", other)),
+    };
+
+    match format {
+        ImportFormat::Json => {
+            let value: Value = serde_json::from_str(content)?;
+            let items = match value {
+                Value::Array(items) => items,
+                // Accept `{"data": [...]}` as returned by the API
+                Value::Object(mut obj) if obj.get("data").is_some_and(Value::is_array) => {
+                    match obj.remove("data") {
+                        Some(Value::Array(items)) => items,
+                        _ => unreachable!(),
+                    }
+                }
+                other => vec![other],
+            };
+            Ok(items
+                .into_iter()
+                .enumerate()
+                .map(|(i, item)| (format!("record {}", i + 1), object(item)))
+                .collect())
+        }
+        ImportFormat::Jsonl => Ok(content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                let record = serde_json::from_str(line).map_err(|e| e.to_string()).and_then(object);
+                (format!("line {}", i + 1), record)
+            })
+            .collect()),
+        ImportFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(content.as_bytes());
+            let headers: Vec<Option<_>> = reader
+                .headers()?
+                .iter()
+                .map(|header| match map.iter().find(|(source, _)| source == header) {
+                    Some((_, column)) => Some(column.clone()),
+                    None => columns::find(header),
+                })
+                .collect();
+            for (header, known) in reader.headers()?.iter().zip(&headers) {
+                if known.is_none() {
+                    eprintln!("Ignoring column '{}' (map it with --map '{}=<field>')", header, header);
+                }
+            }
+
+            let mut records = Vec::new();
+            for row in reader.records() {
+                let row = row?;
+                let line = row.position().map(|p| p.line()).unwrap_or_default();
+                records.push((format!("line {}", line), csv_record(&headers, &row)));
+            }
+            Ok(records)
+        }
+    }
+}
+
+/// Validate records into spikes, rejecting the whole file on any error
+fn parse_spikes(
+    content: &str,
+    format: ImportFormat,
+    map: &[(String, Column)],
+    project_key: &str,
+) -> Result<Vec<Spike>> {
+    let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let mut spikes = Vec::new();
+    let mut errors = Vec::new();
+    for (label, record) in read_records(content, format, map)? {
+        match record.and_then(|r| to_spike(r, project_key, &now)) {
+            Ok(spike) => spikes.push(spike),
+            Err(e) => errors.push(format!("{}: {}", label, e)),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(Error::InvalidImport(format!(
+            "{} invalid record{}, nothing imported\n  {}",
+            errors.len(),
+            if errors.len() == 1 { "" } else { "s" },
+            errors.join("\n  ")
+        )));
+    }
+    Ok(spikes)
+}
+
+/// Split parsed spikes into new ones and ones already stored (by ID or
+/// content) or repeated earlier in the file
+fn dedupe(existing: &[Spike], spikes: Vec<Spike>) -> (Vec<Spike>, usize) {
+    let mut ids: HashSet<String> = existing.iter().map(|s| s.id.clone()).collect();
+    let mut keys: HashSet<_> = existing.iter().map(content_key).collect();
+    let mut skipped = 0;
+    let fresh = spikes
+        .into_iter()
+        .filter(|spike| {
+            let key = content_key(spike);
+            if ids.contains(&spike.id) || keys.contains(&key) {
+                skipped += 1;
+                return false;
+            }
+            ids.insert(spike.id.clone());
+            keys.insert(key);
+            true
+        })
+        .collect();
+    (fresh, skipped)
+}
+
+pub fn run(options: ImportOptions) -> Result<()> {
+    let mut existing = load_spikes()?;
+    let format = options.format.unwrap_or_else(|| ImportFormat::from_path(&options.file));
+    let map = parse_map(&options.map)?;
+    let project_key = match options.project {
+        Some(key) => key,
+        None => Config::load()?.effective_project_key(),
+    };
+
+    let content = fs::read_to_string(&options.file)?;
+    let parsed = parse_spikes(&content, format, &map, &project_key)?;
+    let total = parsed.len();
+    let (mut fresh, skipped) = dedupe(&existing, parsed);
+
+    let rounds = Rounds::load()?;
+    for spike in &mut fresh {
+        rounds.stamp(spike);
+    }
+    let ids: Vec<String> = fresh.iter().map(|s| s.id.clone()).collect();
+
+    if !options.dry_run && !fresh.is_empty() {
+        existing.extend(fresh);
+        save_spikes(&existing)?;
+    }
+
+    if options.json {
+        print_json(&json!({
+            "file": options.file.display().to_string(),
+            "records": total,
+            "imported": ids.len(),
+            "skipped": skipped,
+            "dry_run": options.dry_run,
+            "ids": ids,
+        }));
+        return Ok(());
+    }
+
+    println!(
+        "{} {} spike{} from {}{}",
+        if options.dry_run { "Would import" } else { "Imported" },
+        ids.len(),
+        if ids.len() == 1 { "" } else { "s" },
+        options.file.display(),
+        if skipped > 0 {
+            format!(" ({} already stored, skipped)", skipped)
+        } else {
+            String::new()
+        }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::SpikeType;
+
+    fn parse(content: &str, format: ImportFormat, map: &[&str]) -> Result<Vec<Spike>> {
+        let map: Vec<String> = map.iter().map(|m| m.to_string()).collect();
+        parse_spikes(content, format, &parse_map(&map)?, "acme")
+    }
+
+    #[test]
+    fn test_csv_import_reads_export_columns() {
+        let csv = "id,type,project_key,page,url,reviewer_id,reviewer_name,selector,element_text,rating,comments,timestamp,viewport_width,viewport_height\n\
+                   s1,element,demo,index.html,http://localhost/,r1,Alice,.hero,Hi,no,\"Too loud, really\",2024-01-15T10:00:00Z,390,844\n\
+                   s2,page,demo,about.html,,r2,Bob,,,-,,2024-01-16T10:00:00Z,,\n";
+        let spikes = parse(csv, ImportFormat::Csv, &[]).unwrap();
+
+        assert_eq!(spikes.len(), 2);
+        assert_eq!(spikes[0].id, "s1");
+        assert_eq!(spikes[0].spike_type, SpikeType::Element);
+        assert_eq!(spikes[0].project_key, "demo");
+        assert_eq!(spikes[0].rating, Some(Rating::No));
+        assert_eq!(spikes[0].comments, "Too loud, really");
+        assert_eq!(spikes[0].viewport.as_ref().map(|v| v.width), Some(390));
+        assert_eq!(spikes[1].rating, None);
+        assert!(spikes[1].viewport.is_none());
+    }
+
+    #[test]
+    fn test_flattened_csv_export_imports_losslessly() {
+        let spike: Spike = serde_json::from_value(json!({
+            "id": "s1", "type": "element", "projectKey": "demo", "page": "index.html",
+            "url": "http://localhost/index.html",
+            "reviewer": { "id": "r1", "name": "Alice", "email": "alice@example.com" },
+            "selector": ".hero", "xpath": "/html/body/div[1]", "elementText": "Welcome",
+            "boundingBox": { "x": 10.5, "y": 20.0, "width": 300.0, "height": 40.25 },
+            "rating": "no", "comments": "  Too loud,\n\"really\"  ", "timestamp": "2024-01-15T10:00:00.123Z",
+            "viewport": { "width": 390, "height": 844 },
+            "resolved": false, "resolvedAt": "2024-01-16T10:00:00Z", "duplicateOf": "s0", "stale": true,
+            "tags": ["copy, tone", "hero;v2", " padded "], "assignee": "bob", "round": "v1", "pageHash": "abc123",
+            "issue": { "tracker": "github", "id": "12", "project": "acme/site", "url": "https://github.com/acme/site/issues/12", "state": "closed" },
+        }))
+        .unwrap();
+
+        let all = columns::columns();
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(all.iter().map(|c| &c.name)).unwrap();
+        writer.write_record(columns::cells(&spike, &all)).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+
+        let imported = parse(&csv, ImportFormat::Csv, &[]).unwrap();
+        assert_eq!(serde_json::to_value(&imported[0]).unwrap(), serde_json::to_value(&spike).unwrap());
+    }
+
+    #[test]
+    fn test_csv_import_maps_columns_and_fills_defaults() {
+        let csv = "Feedback,Score,Page URL,Date,Extra\n\
+                   Make it pop,MEH,https://example.com/pricing/,2024-02-01 09:30:00,x\n";
+        let spikes = parse(
+            csv,
+            ImportFormat::Csv,
+            &["Feedback=comments", "Score=rating", "Page URL=url", "Date=timestamp"],
+        )
+        .unwrap();
+
+        let spike = &spikes[0];
+        assert_eq!(spike.id.len(), 11);
+        assert_eq!(spike.project_key, "acme");
+        assert_eq!(spike.page, "pricing");
+        assert_eq!(spike.spike_type, SpikeType::Page);
+        assert_eq!(spike.rating, Some(Rating::Meh));
+        assert_eq!(spike.timestamp, "2024-02-01T09:30:00Z");
+        assert_eq!(spike.reviewer.name, "Imported");
+        assert_eq!(spike.reviewer.id, "import-imported");
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_records() {
+        let csv = "id,page,rating,viewport_width\n,index.html,great,\n,,like,\n,index.html,no,wide\naéééé,index.html,no,\n";
+        let err = parse(csv, ImportFormat::Csv, &[]).unwrap_err().to_string();
+        assert!(err.contains("4 invalid records"), "got: {}", err);
+        assert!(err.contains("line 2: Invalid rating: great"), "got: {}", err);
+        assert!(err.contains("line 3: missing page"), "got: {}", err);
+        assert!(err.contains("line 4: viewport_width: expected a number"), "got: {}", err);
+        assert!(err.contains("line 5: invalid id 'aéééé'"), "got: {}", err);
+
+        assert!(parse("page\nx\n", ImportFormat::Csv, &["a=nope"]).is_err());
+    }
+
+    #[test]
+    fn test_json_and_jsonl_import() {
+        let json = r#"[{"id":"j1","type":"element","projectKey":"p","page":"index.html","url":"","reviewer":{"id":"r1","name":"Ann"},"selector":".cta","rating":"love","comments":"Yes","timestamp":"2024-01-15T10:00:00Z","viewport":null,"resolved":true},
+                       {"text":"Nope","reviewer":"Ben","page":"index.html"}]"#;
+        let spikes = parse(json, ImportFormat::Json, &["text=comments"]).unwrap();
+        assert_eq!(spikes[0].id, "j1");
+        assert_eq!(spikes[0].resolved, Some(true));
+        assert_eq!(spikes[1].comments, "Nope");
+        assert_eq!(spikes[1].reviewer.name, "Ben");
+
+        let jsonl = "{\"page\":\"a.html\",\"comments\":\"one\"}\n\n{\"page\":\"b.html\",\"rating\":\"bad\"}\n";
+        let err = parse(jsonl, ImportFormat::Jsonl, &[]).unwrap_err().to_string();
+        assert!(err.contains("line 3: Invalid rating: bad"), "got: {}", err);
+    }
+
+    #[test]
+    fn test_dedupe_against_storage_and_within_file() {
+        let existing = parse("id,page,reviewer_name,comments\ns1,index.html,Ann,Hi\n", ImportFormat::Csv, &[]).unwrap();
+        let incoming = parse(
+            "id,page,reviewer_name,comments\n\
+             s1,index.html,Ann,Changed\n\
+             ,index.html,ann,Hi\n\
+             ,index.html,Bob,New\n\
+             ,index.html,Bob,New\n",
+            ImportFormat::Csv,
+            &[],
+        )
+        .unwrap();
+
+        let (fresh, skipped) = dedupe(&existing, incoming);
+        assert_eq!(skipped, 3);
+        assert_eq!(fresh.len(), 1);
+        assert_eq!(fresh[0].comments, "New");
+    }
+
+    #[test]
+    fn test_format_from_path_and_page_from_url() {
+        assert_eq!(ImportFormat::from_path(Path::new("a.JSON")), ImportFormat::Json);
+        assert_eq!(ImportFormat::from_path(Path::new("a.ndjson")), ImportFormat::Jsonl);
+        assert_eq!(ImportFormat::from_path(Path::new("a.tsv.txt")), ImportFormat::Csv);
+        assert_eq!(page_from_url("http://localhost:3847/"), "index.html");
+        assert_eq!(page_from_url("https://x.com/docs/intro.html?a=1#top"), "intro.html");
+    }
+}
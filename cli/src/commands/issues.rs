@@ -0,0 +1,502 @@
+//! `spikes issues`: file spikes on an issue tracker and follow the issues.
+//!
+//! The issue's URL is stored on the spike, so a spike is only filed once.
+//! `spikes issues sync` (and `spikes sync`) fetch each linked issue's state:
+//! closing the issue resolves the spike, reopening it unresolves it again.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::config::{Config, IssuesConfig};
+use crate::error::{Error, Result};
+use crate::output::print_json;
+use crate::identity::load_canonical_spikes;
+use crate::spike::{IssueLink, IssueState, Spike, TrackerKind};
+use crate::storage::{find_spike_by_id, load_spikes, save_spikes};
+use crate::trackers::{self, NewIssue, Tracker};
+
+use super::bulk::select_ids;
+use super::export::is_blocking;
+use super::export::template::Template;
+use super::list::SpikeFilter;
+
+/// Looked up like an export template, so `.spikes/templates/issue-body.tera`
+/// replaces the bundled body
+pub const BODY_TEMPLATE: &str = "issue-body";
+
+const BUNDLED_BODY: &str = include_str!("../../templates/issues/body.tera");
+
+/// Characters of the comment kept in the issue title
+const TITLE_EXCERPT_CHARS: usize = 60;
+
+pub struct IssuesCreateOptions {
+    /// Spike IDs or prefixes; none means every open meh/no spike
+    pub ids: Vec<String>,
+    /// `key=value` filters selecting the spikes instead of IDs
+    pub filters: Vec<String>,
+    pub tracker: Option<TrackerKind>,
+    pub project: Option<String>,
+    pub url: Option<String>,
+    /// Labels added besides the spike's tags and the configured labels
+    pub labels: Vec<String>,
+    /// Body template name or path (default: `BODY_TEMPLATE`)
+    pub template: Option<String>,
+    pub dry_run: bool,
+    pub json: bool,
+}
+
+/// Issue title, e.g. `[spikes] no on .hero: Too loud`
+pub fn title(spike: &Spike) -> String {
+    let target = spike.selector.as_deref().unwrap_or(&spike.page);
+    let mut title = format!("[spikes] {} on {}", spike.rating_str(), target);
+    let comment = spike.comments.split_whitespace().collect::<Vec<_>>().join(" ");
+    if !comment.is_empty() {
+        let excerpt: String = comment.chars().take(TITLE_EXCERPT_CHARS).collect();
+        let ellipsis = if excerpt.len() < comment.len() { "…" } else { "" };
+        title.push_str(&format!(": {}{}", excerpt.trim_end(), ellipsis));
+    }
+    title
+}
+
+/// The spike's tags, then `extra`, without repeats
+pub fn labels(spike: &Spike, extra: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    spike
+        .tags
+        .iter()
+        .flatten()
+        .chain(extra)
+        .filter(|label| seen.insert(label.to_lowercase()))
+        .cloned()
+        .collect()
+}
+
+/// Context for the body template: `spike` as in `export --format json`,
+/// plus `project`, `rating` and the comment as a Markdown `quote`
+pub fn body_context(spike: &Spike) -> Value {
+    let quote = spike
+        .comments
+        .trim()
+        .lines()
+        .map(|line| format!("> {}", line).trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    json!({
+        "spike": spike,
+        "project": spike.project_key,
+        "rating": spike.rating.as_ref().map(|r| r.to_string()).unwrap_or_else(|| "unrated".to_string()),
+        "quote": quote,
+    })
+}
+
+fn body_template(name: Option<&str>) -> Result<Template> {
+    match Template::find(name.unwrap_or(BODY_TEMPLATE)) {
+        Err(Error::TemplateNotFound(_)) if name.is_none() => Ok(Template {
+            name: BODY_TEMPLATE.to_string(),
+            source: BUNDLED_BODY.to_string(),
+        }),
+        found => found,
+    }
+}
+
+/// Spikes to file, in file order: the given IDs, the `--where` matches, or
+/// every open meh/no spike that isn't a duplicate
+fn select(spikes: &[Spike], options: &IssuesCreateOptions) -> Result<Vec<String>> {
+    if !options.ids.is_empty() {
+        let mut ids = Vec::new();
+        for id in &options.ids {
+            let id = find_spike_by_id(spikes, id)?.id;
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        return Ok(ids);
+    }
+    if !options.filters.is_empty() {
+        return Ok(select_ids(spikes, &SpikeFilter::parse_where(&options.filters)?));
+    }
+    Ok(spikes
+        .iter()
+        .filter(|s| is_blocking(s) && s.duplicate_of.is_none())
+        .map(|s| s.id.clone())
+        .collect())
+}
+
+/// Options win over `[issues]` in config.toml
+fn destination(options: &IssuesCreateOptions, config: &IssuesConfig) -> Result<(TrackerKind, String)> {
+    let tracker = options.tracker.or(config.tracker).ok_or_else(|| {
+        Error::Tracker("No tracker configured. Pass --tracker or set tracker under [issues] in .spikes/config.toml".to_string())
+    })?;
+    let project = options.project.clone().or_else(|| config.project.clone()).ok_or_else(|| {
+        Error::Tracker(format!(
+            "No {} project configured. Pass --project or set project under [issues] in .spikes/config.toml",
+            tracker
+        ))
+    })?;
+    Ok((tracker, project))
+}
+
+#[derive(Debug, Serialize)]
+struct Filed {
+    id: String,
+    title: String,
+    labels: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issue: Option<IssueLink>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<String>,
+}
+
+pub fn create(options: IssuesCreateOptions) -> Result<()> {
+    let config = Config::load()?;
+    let (kind, project) = destination(&options, &config.issues)?;
+    let url = options.url.clone().or_else(|| config.issues.url.clone());
+    let template = body_template(options.template.as_deref())?;
+    let extra_labels: Vec<String> = config.issues.labels.iter().chain(&options.labels).cloned().collect();
+
+    let mut spikes = load_spikes()?;
+    let selected = select(&load_canonical_spikes()?, &options)?;
+    let (linked, ids): (Vec<String>, Vec<String>) = selected
+        .into_iter()
+        .partition(|id| spikes.iter().any(|s| s.id == *id && s.issue.is_some()));
+
+    // Nothing is sent on a dry run, so no token is needed either
+    let tracker = if options.dry_run || ids.is_empty() {
+        None
+    } else {
+        Some(trackers::connect(kind, &project, url.as_deref())?)
+    };
+
+    // Render every body before filing anything, so a template error can't
+    // leave a run half done
+    let mut issues = Vec::new();
+    for id in &ids {
+        let spike = spikes.iter().find(|s| s.id == *id).expect("selected spike exists");
+        let issue = NewIssue {
+            title: title(spike),
+            body: template.render(&body_context(spike))?,
+            labels: labels(spike, &extra_labels),
+        };
+        issues.push((id, issue));
+    }
+
+    let mut filed = Vec::new();
+    let mut failure = None;
+    for (id, issue) in issues {
+        let link = match &tracker {
+            Some(tracker) => match tracker.create(&issue) {
+                Ok(link) => Some(link),
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            },
+            None => None,
+        };
+        if link.is_some() {
+            if let Some(spike) = spikes.iter_mut().find(|s| s.id == *id) {
+                spike.issue = link.clone();
+            }
+        }
+        filed.push(Filed {
+            id: id.clone(),
+            title: issue.title,
+            labels: issue.labels,
+            issue: link,
+            body: options.dry_run.then_some(issue.body),
+        });
+    }
+
+    // Keep the links of issues filed before a failure, so a rerun skips them
+    if !options.dry_run && !filed.is_empty() {
+        save_spikes(&spikes)?;
+    }
+
+    let skipped: Vec<Value> = linked
+        .iter()
+        .filter_map(|id| spikes.iter().find(|s| s.id == *id))
+        .map(|s| json!({ "id": s.id, "url": s.issue.as_ref().map(|i| i.url.as_str()) }))
+        .collect();
+
+    if options.json {
+        print_json(&json!({
+            "tracker": kind,
+            "project": project,
+            "dryRun": options.dry_run,
+            "created": filed,
+            "skipped": skipped,
+        }));
+    } else {
+        for entry in &filed {
+            let short: String = entry.id.chars().take(8).collect();
+            match &entry.issue {
+                Some(link) => println!("Filed {} → {}", short, link.url),
+                None => println!("Would file {}: {}", short, entry.title),
+            }
+        }
+        for entry in &skipped {
+            println!("Skipped {}: already filed as {}", entry["id"].as_str().unwrap_or(""), entry["url"].as_str().unwrap_or(""));
+        }
+        if filed.is_empty() && skipped.is_empty() {
+            println!("No spikes to file.");
+        } else if !filed.is_empty() {
+            let verb = if options.dry_run { "Would file" } else { "Filed" };
+            println!(
+                "{} {} issue{} on {} ({}).",
+                verb,
+                filed.len(),
+                if filed.len() == 1 { "" } else { "s" },
+                kind,
+                project
+            );
+        }
+    }
+
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    /// Linked spikes whose issue was fetched
+    pub checked: usize,
+    /// Issues whose state changed since the last sync
+    pub updated: usize,
+    /// Spikes resolved because their issue was closed
+    pub resolved: Vec<String>,
+    /// Spikes unresolved because their issue was reopened
+    pub reopened: Vec<String>,
+    /// One message per tracker that couldn't be reached
+    pub errors: Vec<String>,
+}
+
+impl SyncReport {
+    pub fn changed(&self) -> bool {
+        self.updated > 0
+    }
+}
+
+/// Fetch the state of every linked issue and carry changes over to the
+/// spike. Only transitions count: resolving a spike by hand while its issue
+/// is still open is left alone.
+pub fn sync_states<F>(spikes: &mut [Spike], connect: F) -> SyncReport
+where
+    F: Fn(&IssueLink) -> Result<Box<dyn Tracker>>,
+{
+    let mut report = SyncReport::default();
+    let mut failed: HashSet<TrackerKind> = HashSet::new();
+
+    for spike in spikes.iter_mut() {
+        let Some(link) = spike.issue.as_mut() else { continue };
+        if failed.contains(&link.tracker) {
+            continue;
+        }
+        let state = match connect(link).and_then(|tracker| tracker.state(link)) {
+            Ok(state) => state,
+            Err(e) => {
+                // Usually a missing token, which would fail every other link too
+                failed.insert(link.tracker);
+                report.errors.push(e.to_string());
+                continue;
+            }
+        };
+        report.checked += 1;
+        if state == link.state {
+            continue;
+        }
+        link.state = state;
+        report.updated += 1;
+        match state {
+            IssueState::Closed if !spike.is_resolved() => {
+                spike.resolved = Some(true);
+                spike.resolved_at = Some(chrono::Local::now().to_rfc3339());
+                report.resolved.push(spike.id.clone());
+            }
+            IssueState::Open if spike.is_resolved() => {
+                spike.resolved = None;
+                spike.resolved_at = None;
+                report.reopened.push(spike.id.clone());
+            }
+            _ => {}
+        }
+    }
+    report
+}
+
+/// Sync linked issues in `.spikes/feedback.jsonl`. `None` when no spike has
+/// an issue.
+pub fn refresh() -> Result<Option<SyncReport>> {
+    let mut spikes = load_spikes()?;
+    if spikes.iter().all(|s| s.issue.is_none()) {
+        return Ok(None);
+    }
+    let config = Config::load()?.issues;
+    let report = sync_states(&mut spikes, |link| {
+        // The configured URL belongs to the configured tracker
+        let url = config.url.as_deref().filter(|_| config.tracker.is_none_or(|t| t == link.tracker));
+        trackers::connect(link.tracker, &link.project, url)
+    });
+    if report.changed() {
+        save_spikes(&spikes)?;
+    }
+    Ok(Some(report))
+}
+
+/// Print a sync report; shared with `spikes sync`
+pub fn print_report(report: &SyncReport) {
+    println!(
+        "Checked {} issue{}: {} changed, {} resolved, {} reopened.",
+        report.checked,
+        if report.checked == 1 { "" } else { "s" },
+        report.updated,
+        report.resolved.len(),
+        report.reopened.len()
+    );
+    for id in &report.resolved {
+        println!("  Resolved {}", id);
+    }
+    for id in &report.reopened {
+        println!("  Reopened {}", id);
+    }
+    for error in &report.errors {
+        eprintln!("Warning: {}", error);
+    }
+}
+
+pub fn sync(json: bool) -> Result<()> {
+    let Some(report) = refresh()? else {
+        if json {
+            print_json(&SyncReport::default());
+        } else {
+            println!("No spikes are linked to issues. File some with `spikes issues create`.");
+        }
+        return Ok(());
+    };
+
+    if json {
+        print_json(&report);
+    } else {
+        print_report(&report);
+    }
+    match report.errors.first() {
+        Some(error) => Err(Error::Tracker(error.clone())),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Rating, Reviewer, SpikeType};
+    use std::collections::HashMap;
+
+    fn spike(id: &str, comments: &str) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            project_key: "acme".to_string(),
+            url: "http://localhost:3847/index.html".to_string(),
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: Some(".hero".to_string()),
+            comments: comments.to_string(),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            tags: Some(vec!["copy".to_string(), "hero".to_string()]),
+            ..Spike::test(id, "index.html", Some(Rating::No))
+        }
+    }
+
+    fn link(id: &str, state: IssueState) -> IssueLink {
+        IssueLink {
+            tracker: TrackerKind::Github,
+            id: id.to_string(),
+            project: "acme/site".to_string(),
+            url: format!("https://github.com/acme/site/issues/{}", id),
+            state,
+        }
+    }
+
+    /// Answers `state` from a fixed table, standing in for a real tracker
+    struct Fake(HashMap<String, IssueState>);
+
+    impl Tracker for Fake {
+        fn create(&self, _: &NewIssue) -> Result<IssueLink> {
+            unreachable!("sync never creates issues")
+        }
+
+        fn state(&self, link: &IssueLink) -> Result<IssueState> {
+            Ok(self.0[&link.id])
+        }
+    }
+
+    #[test]
+    fn test_title_labels_and_body() {
+        let s = spike("s1", "The headline is far too loud and the contrast makes it hard to read on mobile\nsecond line");
+        assert_eq!(
+            title(&s),
+            "[spikes] no on .hero: The headline is far too loud and the contrast makes it hard…"
+        );
+        assert_eq!(title(&spike("s2", "")), "[spikes] no on .hero");
+        assert_eq!(
+            labels(&s, &["feedback".to_string(), "Copy".to_string()]),
+            vec!["copy", "hero", "feedback"]
+        );
+
+        let body = body_template(None).unwrap().render(&body_context(&s)).unwrap();
+        assert!(body.starts_with("> The headline is far too loud"), "got:\n{}", body);
+        assert!(body.contains("\n> second line\n"), "got:\n{}", body);
+        assert!(body.contains("- **Page:** [index.html](http://localhost:3847/index.html)"));
+        assert!(body.contains("- **Element:** `.hero`"));
+        assert!(body.contains("run `spikes show s1`"));
+    }
+
+    #[test]
+    fn test_sync_resolves_closed_and_reopens_reopened_issues() {
+        let mut closed = spike("s1", "a");
+        closed.issue = Some(link("1", IssueState::Open));
+        let mut reopened = spike("s2", "b");
+        reopened.issue = Some(link("2", IssueState::Closed));
+        reopened.resolved = Some(true);
+        let mut resolved_by_hand = spike("s3", "c");
+        resolved_by_hand.issue = Some(link("3", IssueState::Open));
+        resolved_by_hand.resolved = Some(true);
+        let mut spikes = vec![closed, reopened, resolved_by_hand, spike("s4", "unlinked")];
+
+        let states = HashMap::from([
+            ("1".to_string(), IssueState::Closed),
+            ("2".to_string(), IssueState::Open),
+            ("3".to_string(), IssueState::Open),
+        ]);
+        let report = sync_states(&mut spikes, |_| Ok(Box::new(Fake(states.clone()))));
+
+        assert_eq!((report.checked, report.updated), (3, 2));
+        assert_eq!(report.resolved, vec!["s1"]);
+        assert_eq!(report.reopened, vec!["s2"]);
+        assert!(spikes[0].is_resolved() && spikes[0].resolved_at.is_some());
+        assert_eq!(spikes[0].issue.as_ref().unwrap().state, IssueState::Closed);
+        assert!(!spikes[1].is_resolved());
+        assert!(spikes[2].is_resolved());
+    }
+
+    #[test]
+    fn test_sync_reports_each_unreachable_tracker_once() {
+        let mut spikes: Vec<Spike> = (1..=3)
+            .map(|i| {
+                let mut s = spike(&format!("s{}", i), "x");
+                s.issue = Some(link(&i.to_string(), IssueState::Open));
+                s
+            })
+            .collect();
+        let report = sync_states(&mut spikes, |_| Err(Error::Tracker("GITHUB_TOKEN is not set".to_string())));
+
+        assert_eq!(report.checked, 0);
+        assert_eq!(report.errors, vec!["Issue tracker error: GITHUB_TOKEN is not set"]);
+        assert!(!report.changed());
+    }
+}
@@ -1,58 +1,276 @@
-use crate::error::Result;
-use crate::output::{print_json, print_spikes_table};
-use crate::spike::{Rating, Spike};
-use crate::storage::load_spikes;
+use std::collections::HashSet;
+
+use crate::config::{Config, ViewportConfig};
+use crate::error::{Error, Result};
+use crate::identity::load_spikes_matching;
+use crate::output::{print_json, print_spikes_table_marked};
+use crate::pages::{spike_json_with_source, PageHashes, Site};
+use crate::selector::canonicalize;
+use crate::spike::{Rating, Spike, SpikeType, ViewportClass};
 
 pub struct ListOptions {
     pub json: bool,
+    pub filter: SpikeFilter,
+}
+
+/// Filters shared by `list`, `watch` and the bulk `--where` forms
+#[derive(Debug, Clone, Default)]
+pub struct SpikeFilter {
     pub page: Option<String>,
     pub reviewer: Option<String>,
     pub rating: Option<String>,
     pub unresolved: bool,
+    pub resolved: Option<bool>,
+    pub spike_type: Option<SpikeType>,
+    pub selector: Option<String>,
+    pub tag: Option<String>,
+    pub assignee: Option<String>,
+    pub stale: Option<bool>,
+    pub round: Option<String>,
+    pub viewport: Option<ViewportClass>,
+    /// Breakpoints used to classify viewports for `viewport`
+    pub breakpoints: ViewportConfig,
+    /// Text keys (page, reviewer, selector, assignee) that must match the
+    /// whole value rather than part of it, as `--where key=value` does
+    pub exact: Vec<&'static str>,
 }
 
-pub fn run(options: ListOptions) -> Result<()> {
-    let spikes = load_spikes()?;
+/// Keys whose `--where` value may also match part of the field, with `~=`
+const TEXT_KEYS: [&str; 4] = ["page", "reviewer", "selector", "assignee"];
 
-    let filtered: Vec<Spike> = spikes
-        .into_iter()
-        .filter(|s| {
-            if let Some(ref page) = options.page {
-                if !s.page.to_lowercase().contains(&page.to_lowercase()) {
-                    return false;
+/// Case-insensitive substring match, as used by `--page` and friends
+pub fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "1" => Ok(true),
+        "false" | "no" | "0" => Ok(false),
+        _ => Err(Error::InvalidFilter(format!("{} must be true or false, got '{}'", key, value))),
+    }
+}
+
+impl SpikeFilter {
+    /// Build a filter from `key=value` expressions, all of which must match.
+    ///
+    /// Keys: page, reviewer, rating, type, selector, tag, assignee, round,
+    /// viewport, resolved, stale. Text keys match the whole value (ignoring
+    /// case); `key~=value` matches part of it instead.
+    pub fn parse_where(exprs: &[String]) -> Result<SpikeFilter> {
+        let mut filter = SpikeFilter::default();
+        for expr in exprs {
+            let (key, value) = expr
+                .split_once('=')
+                .map(|(k, v)| (k.trim().to_lowercase(), v.trim().to_string()))
+                .ok_or_else(|| Error::InvalidFilter(format!("expected key=value, got '{}'", expr)))?;
+            let (key, partial) = match key.strip_suffix('~') {
+                Some(key) => (key.trim_end().to_string(), true),
+                None => (key, false),
+            };
+            if value.is_empty() {
+                return Err(Error::InvalidFilter(format!("no value for '{}'", key)));
+            }
+            match TEXT_KEYS.iter().find(|k| **k == key) {
+                Some(text_key) if !partial => filter.exact.push(text_key),
+                Some(_) => {}
+                None if partial => {
+                    return Err(Error::InvalidFilter(format!(
+                        "'~=' only applies to page, reviewer, selector and assignee, not '{}'",
+                        key
+                    )))
                 }
+                None => {}
             }
-            if let Some(ref reviewer) = options.reviewer {
-                if !s
-                    .reviewer
-                    .name
-                    .to_lowercase()
-                    .contains(&reviewer.to_lowercase())
-                {
-                    return false;
+            match key.as_str() {
+                "page" => filter.page = Some(value),
+                "reviewer" => filter.reviewer = Some(value),
+                "rating" => {
+                    value.parse::<Rating>().map_err(Error::InvalidFilter)?;
+                    filter.rating = Some(value);
+                }
+                "type" => {
+                    filter.spike_type = Some(match value.to_lowercase().as_str() {
+                        "page" => SpikeType::Page,
+                        "element" => SpikeType::Element,
+                        _ => {
+                            return Err(Error::InvalidFilter(format!(
+                                "type must be page or element, got '{}'",
+                                value
+                            )))
+                        }
+                    })
                 }
+                "selector" => filter.selector = Some(value),
+                "tag" => filter.tag = Some(value),
+                "assignee" => filter.assignee = Some(value),
+                "round" => filter.round = Some(value),
+                "viewport" => filter.viewport = Some(value.parse().map_err(Error::InvalidFilter)?),
+                "resolved" => filter.resolved = Some(parse_bool(&key, &value)?),
+                "stale" => filter.stale = Some(parse_bool(&key, &value)?),
+                _ => {
+                    return Err(Error::InvalidFilter(format!(
+                        "unknown key '{}' (expected page, reviewer, rating, type, selector, tag, assignee, round, viewport, resolved or stale)",
+                        key
+                    )))
+                }
+            }
+        }
+        filter.with_config_breakpoints()
+    }
+
+    /// Use the breakpoints from `.spikes/config.toml` when filtering by
+    /// viewport. The config is only read when it is needed.
+    pub fn with_config_breakpoints(mut self) -> Result<Self> {
+        if self.viewport.is_some() {
+            self.breakpoints = Config::load()?.viewport;
+        }
+        Ok(self)
+    }
+
+    /// Active filters in `--where` form, e.g. `["page=pricing", "resolved=false"]`
+    pub fn describe(&self) -> Vec<String> {
+        let mut parts = Vec::new();
+        let text = [
+            ("page", &self.page),
+            ("reviewer", &self.reviewer),
+            ("rating", &self.rating),
+            ("selector", &self.selector),
+            ("tag", &self.tag),
+            ("assignee", &self.assignee),
+            ("round", &self.round),
+        ];
+        for (key, value) in text {
+            if let Some(value) = value {
+                let op = if TEXT_KEYS.contains(&key) && !self.exact.contains(&key) { "~=" } else { "=" };
+                parts.push(format!("{}{}{}", key, op, value));
             }
-            if let Some(ref rating) = options.rating {
-                if let Ok(r) = rating.parse::<Rating>() {
-                    if s.rating.as_ref() != Some(&r) {
-                        return false;
-                    }
-                } else {
+        }
+        if let Some(ref spike_type) = self.spike_type {
+            parts.push(format!("type={}", if *spike_type == SpikeType::Element { "element" } else { "page" }));
+        }
+        if let Some(viewport) = self.viewport {
+            parts.push(format!("viewport={}", viewport));
+        }
+        if self.unresolved {
+            parts.push("resolved=false".to_string());
+        } else if let Some(resolved) = self.resolved {
+            parts.push(format!("resolved={}", resolved));
+        }
+        if let Some(stale) = self.stale {
+            parts.push(format!("stale={}", stale));
+        }
+        parts
+    }
+
+    /// Whether `value` matches the text filter for `key`: all of it for an
+    /// exact key, otherwise any part of it
+    fn text_matches(&self, key: &str, value: &str, wanted: &str) -> bool {
+        if self.exact.contains(&key) {
+            value.to_lowercase() == wanted.to_lowercase()
+        } else {
+            contains_ignore_case(value, wanted)
+        }
+    }
+
+    pub fn matches(&self, s: &Spike) -> bool {
+        if let Some(ref page) = self.page {
+            if !self.text_matches("page", &s.page, page) {
+                return false;
+            }
+        }
+        if let Some(ref reviewer) = self.reviewer {
+            let email_matches = s
+                .reviewer
+                .email
+                .as_deref()
+                .is_some_and(|email| email.eq_ignore_ascii_case(reviewer));
+            if !self.text_matches("reviewer", &s.reviewer.name, reviewer) && !email_matches {
+                return false;
+            }
+        }
+        if let Some(ref rating) = self.rating {
+            if let Ok(r) = rating.parse::<Rating>() {
+                if s.rating.as_ref() != Some(&r) {
                     return false;
                 }
+            } else {
+                return false;
+            }
+        }
+        // Filter for unresolved spikes only
+        if self.unresolved && s.is_resolved() {
+            return false;
+        }
+        if self.resolved.is_some_and(|resolved| resolved != s.is_resolved()) {
+            return false;
+        }
+        if self.spike_type.as_ref().is_some_and(|t| *t != s.spike_type) {
+            return false;
+        }
+        if let Some(ref selector) = self.selector {
+            // Compare canonical forms so equivalent spellings match
+            let matched = s
+                .selector
+                .as_deref()
+                .is_some_and(|own| self.text_matches("selector", &canonicalize(own), &canonicalize(selector)));
+            if !matched {
+                return false;
             }
-            // Filter for unresolved spikes only
-            if options.unresolved && s.is_resolved() {
+        }
+        if let Some(ref tag) = self.tag {
+            let tags = s.tags.as_deref().unwrap_or_default();
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
                 return false;
             }
-            true
-        })
-        .collect();
+        }
+        if let Some(ref assignee) = self.assignee {
+            if !s.assignee.as_deref().is_some_and(|a| self.text_matches("assignee", a, assignee)) {
+                return false;
+            }
+        }
+        if self.stale.is_some_and(|stale| stale != s.stale.unwrap_or(false)) {
+            return false;
+        }
+        if self.round.as_ref().is_some_and(|round| s.round.as_ref() != Some(round)) {
+            return false;
+        }
+        if let Some(class) = self.viewport {
+            // Spikes without a viewport belong to no class
+            if s.viewport.as_ref().and_then(|vp| self.breakpoints.classify(vp)) != Some(class) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub fn run(options: ListOptions) -> Result<()> {
+    let filter = options.filter.with_config_breakpoints()?;
+    let filtered = load_spikes_matching(|s| filter.matches(s))?;
+
+    let site_dir = Config::load()?.site_dir();
+    // Only parse the HTML when there are elements to locate
+    let site = filtered
+        .iter()
+        .any(|s| options.json && s.spike_type == SpikeType::Element)
+        .then(|| Site::load(&site_dir).ok())
+        .flatten();
+    let mut pages = PageHashes::new(&site_dir);
 
     if options.json {
-        print_json(&filtered);
+        let output = filtered
+            .iter()
+            .map(|s| spike_json_with_source(s, site.as_ref(), &mut pages))
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        print_json(&output);
     } else {
-        print_spikes_table(&filtered);
+        let outdated: HashSet<String> = filtered
+            .iter()
+            .filter(|s| pages.is_outdated(s) == Some(true))
+            .map(|s| s.id.clone())
+            .collect();
+        print_spikes_table_marked(&filtered, &outdated);
     }
 
     Ok(())
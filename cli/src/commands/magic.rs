@@ -82,7 +82,7 @@ pub fn run(port: u16) -> Result<()> {
     // Start server
     super::serve::run(ServeOptions {
         port,
-        directory: ".".to_string(),
+        directory: Some(".".to_string()),
         marked: false,
         cors_allow_origin: None,
     })
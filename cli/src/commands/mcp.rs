@@ -3,9 +3,8 @@
 //! Exposes spikes feedback as tools for AI agent integration.
 //! All logging goes to stderr; stdout is reserved for JSON-RPC.
 
-use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
 use rmcp::{
@@ -19,9 +18,19 @@ use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
 use crate::auth::{get_api_base, AuthConfig};
+use crate::config::{Config, ViewportConfig};
 use crate::error::{map_http_error, map_network_error, Error};
-use crate::spike::{Rating, Reviewer, Spike, SpikeType};
-use crate::storage::{load_spikes, remove_spike, save_spikes, update_spike};
+use crate::selector::{find_element, group_elements};
+use crate::spike::{Rating, Reviewer, Spike, SpikeType, ViewportClass};
+use crate::pages::{Location, Site};
+use crate::storage::{find_spike_by_id, load_spikes, remove_spike, save_spikes, update_spike};
+use crate::summary::Summary;
+
+use super::bulk::{select_ids, BulkAction};
+use super::clusters;
+use super::export::pack;
+use super::hotspots::hotspot_groups;
+use super::list::SpikeFilter;
 
 // ============================================================================
 // Data Source
@@ -88,12 +97,18 @@ pub struct GetSpikesArgs {
     /// Only return unresolved spikes
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unresolved_only: Option<bool>,
+
+    /// Filter by viewport class: mobile, tablet, or desktop (breakpoints
+    /// come from the project's config.toml)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub viewport: Option<String>,
 }
 
 /// Arguments for the get_element_feedback tool
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct GetElementFeedbackArgs {
-    /// CSS selector to look up (required)
+    /// CSS selector to look up (required). Equivalent spellings match the
+    /// same element; a partial selector, xpath or element text also works.
     pub selector: String,
 
     /// Optional page filter (e.g., 'index.html')
@@ -109,6 +124,41 @@ pub struct GetHotspotsArgs {
     pub limit: Option<u64>,
 }
 
+/// Arguments for the get_context_pack tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetContextPackArgs {
+    /// Approximate token budget for the pack (default: 4000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+}
+
+/// Arguments for the get_clusters tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetClustersArgs {
+    /// Optional page filter, substring and case-insensitive (e.g., 'index')
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+
+    /// Max gap between bounding boxes as a fraction of viewport width (default: 0.02)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+}
+
+/// Arguments for the get_spike_source_context tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GetSpikeSourceContextArgs {
+    /// Spike ID or prefix (minimum 4 characters)
+    pub spike_id: String,
+
+    /// Lines of HTML to include before and after the element (default: 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_lines: Option<u64>,
+
+    /// Directory containing the HTML files (default: [serve] dir in config.toml, or the current directory)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+}
+
 /// Arguments for the submit_spike tool
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SubmitSpikeArgs {
@@ -150,6 +200,38 @@ pub struct ResolveSpikeArgs {
     pub spike_id: String,
 }
 
+/// Arguments for the bulk_resolve tool
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct BulkResolveArgs {
+    /// Page filter, matched as a case-insensitive substring (e.g., 'old-pricing.html')
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+
+    /// Reviewer name filter, matched as a case-insensitive substring
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewer: Option<String>,
+
+    /// Rating filter: love, like, meh, or no
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<String>,
+
+    /// CSS selector filter; equivalent spellings match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+
+    /// Only spikes carrying this tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// Only spikes assigned to this person
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+
+    /// List the spikes that would be resolved without changing anything (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<bool>,
+}
+
 /// Arguments for the delete_spike tool
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct DeleteSpikeArgs {
@@ -199,8 +281,12 @@ struct CachedScope {
 /// - `get_spikes`: List feedback with optional filters
 /// - `get_element_feedback`: Get feedback for a specific element
 /// - `get_hotspots`: Find elements with the most feedback
+/// - `get_context_pack`: Prioritized feedback digest within a token budget
+/// - `get_clusters`: Group element feedback by overlapping bounding boxes
+/// - `get_spike_source_context`: Locate a spike's element in the HTML source
 /// - `submit_spike`: Create new feedback
 /// - `resolve_spike`: Mark feedback as resolved
+/// - `bulk_resolve`: Resolve all feedback matching a filter
 /// - `delete_spike`: Remove feedback
 /// - `create_share`: Upload directory and get shareable URL
 /// - `list_shares`: List all shares
@@ -230,12 +316,26 @@ impl SpikesService {
     /// and resolution status. Perfect for understanding what needs work.
     #[tool(
         name = "get_spikes",
-        description = "Dig into the feedback pile. Get all spikes (feedback items) with optional filters for page, rating, or unresolved status. Returns formatted text with spike details."
+        description = "Dig into the feedback pile. Get all spikes (feedback items) with optional filters for page, rating, unresolved status, or viewport class (mobile, tablet, desktop). Returns formatted text with spike details."
     )]
     async fn get_spikes(
         &self,
         Parameters(args): Parameters<GetSpikesArgs>,
     ) -> std::result::Result<CallToolResult, McpError> {
+        let viewport_filter = args
+            .viewport
+            .as_deref()
+            .map(str::parse::<ViewportClass>)
+            .transpose()
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        let breakpoints = if viewport_filter.is_some() {
+            Config::load()
+                .map(|c| c.viewport)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?
+        } else {
+            ViewportConfig::default()
+        };
+
         let spikes = match &self.data_source {
             DataSource::Local => {
                 match load_spikes() {
@@ -285,6 +385,12 @@ impl SpikesService {
                 if unresolved_only && s.is_resolved() {
                     return false;
                 }
+                // Viewport filter: spikes without a viewport match no class
+                if let Some(class) = viewport_filter {
+                    if s.viewport.as_ref().and_then(|vp| breakpoints.classify(vp)) != Some(class) {
+                        return false;
+                    }
+                }
                 true
             })
             .collect();
@@ -309,7 +415,7 @@ impl SpikesService {
     /// Use this to zoom in on a particular element's feedback history.
     #[tool(
         name = "get_element_feedback",
-        description = "Target lock: get feedback for a specific CSS selector. Zoom in on what reviewers said about a particular element. Requires selector parameter."
+        description = "Target lock: get feedback for a specific CSS selector. Zoom in on what reviewers said about a particular element. Equivalent selectors resolve to the same element; partial selectors, xpaths or element text fall back to a fuzzy match. Requires selector parameter."
     )]
     async fn get_element_feedback(
         &self,
@@ -339,22 +445,11 @@ impl SpikesService {
 
         let page_filter = args.page.as_deref();
 
-        let matching: Vec<&Spike> = spikes
-            .iter()
-            .filter(|s| {
-                if s.spike_type != SpikeType::Element {
-                    return false;
-                }
-                if s.selector.as_deref() != Some(args.selector.as_str()) {
-                    return false;
-                }
-                if let Some(page) = page_filter {
-                    if s.page != page {
-                        return false;
-                    }
-                }
-                true
-            })
+        // Exact canonical match first, then partial selector/xpath/text match
+        let groups = group_elements(spikes.iter().filter(|s| page_filter.is_none_or(|p| s.page == p)));
+        let matching: Vec<&Spike> = find_element(&groups, &args.selector)
+            .into_iter()
+            .flat_map(|g| g.spikes.iter().copied())
             .collect();
 
         if matching.is_empty() {
@@ -382,7 +477,7 @@ impl SpikesService {
     /// Identifies hotspots - elements that attracted the most attention.
     #[tool(
         name = "get_hotspots",
-        description = "Heat map mode: find elements with the most feedback. Identifies hotspots where reviewers clustered. Use this to prioritize what to fix first."
+        description = "Heat map mode: find elements with the most feedback. Identifies hotspots where reviewers clustered, with each one's rating mix, recurring keywords and representative comments. Use this to prioritize what to fix first."
     )]
     async fn get_hotspots(
         &self,
@@ -412,19 +507,7 @@ impl SpikesService {
 
         let limit = args.limit.unwrap_or(10) as usize;
 
-        // Count feedback per selector
-        let mut counts: HashMap<String, usize> = HashMap::new();
-        for spike in &spikes {
-            if spike.spike_type == SpikeType::Element {
-                if let Some(selector) = &spike.selector {
-                    *counts.entry(selector.clone()).or_insert(0) += 1;
-                }
-            }
-        }
-
-        // Sort by count descending
-        let mut hotspots: Vec<(String, usize)> = counts.into_iter().collect();
-        hotspots.sort_by_key(|item| std::cmp::Reverse(item.1));
+        let mut hotspots = hotspot_groups(&spikes);
         hotspots.truncate(limit);
 
         if hotspots.is_empty() {
@@ -434,15 +517,234 @@ impl SpikesService {
         }
 
         let mut output = format!("Top {} hotspot(s):\n\n", hotspots.len());
-        for (i, (selector, count)) in hotspots.iter().enumerate() {
+        for (i, group) in hotspots.iter().enumerate() {
+            let count = group.spikes.len();
             output.push_str(&format!(
                 "{}. {} ({} feedback item{})\n",
                 i + 1,
-                selector,
+                group.selector,
                 count,
-                if *count == 1 { "" } else { "s" }
+                if count == 1 { "" } else { "s" }
             ));
+            let summary = Summary::of(&group.spikes);
+            output.push_str(&format!("   Ratings: {}\n", summary.ratings_label()));
+            if !summary.keywords.is_empty() {
+                output.push_str(&format!("   Keywords: {}\n", summary.keywords.join(", ")));
+            }
+            for quote in &summary.quotes {
+                output.push_str(&format!("   \"{}\" — {}\n", quote.text, quote.reviewer));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    /// Prioritized feedback digest that fits a token budget.
+    ///
+    /// Same as `spikes export --format claude-context --max-tokens N`.
+    #[tool(
+        name = "get_context_pack",
+        description = "Pack the essentials: blocking issues, hotspots, contested elements and recent feedback, trimmed to fit a token budget (max_tokens, default 4000). Says what was left out. Start here on large projects instead of get_spikes."
+    )]
+    async fn get_context_pack(
+        &self,
+        Parameters(args): Parameters<GetContextPackArgs>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let spikes = match &self.data_source {
+            DataSource::Local => {
+                match load_spikes() {
+                    Ok(s) => s,
+                    Err(e) => {
+                        return Err(McpError::internal_error(
+                            format!("Could not load spikes: {}", e),
+                            None,
+                        ));
+                    }
+                }
+            }
+            DataSource::Remote { token, api_base } => {
+                match fetch_remote_spikes(token, api_base, None, None, false) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        return Err(McpError::internal_error(e.to_string(), None));
+                    }
+                }
+            }
+        };
+
+        let max_tokens = args.max_tokens.map(|n| n as usize).unwrap_or(pack::DEFAULT_MAX_TOKENS);
+        Ok(CallToolResult::success(vec![Content::text(
+            pack::generate_context_pack(&spikes, max_tokens),
+        )]))
+    }
+
+    /// Group element feedback by overlapping or nearby bounding boxes.
+    ///
+    /// Catches feedback that scattered across child elements of one component.
+    #[tool(
+        name = "get_clusters",
+        description = "Zoom out: group element feedback by overlapping or nearby bounding boxes per page. Catches reviewers who clicked different children of the same card. Returns combined rating and comments per cluster."
+    )]
+    async fn get_clusters(
+        &self,
+        Parameters(args): Parameters<GetClustersArgs>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let spikes = match &self.data_source {
+            DataSource::Local => {
+                match load_spikes() {
+                    Ok(s) => s,
+                    Err(e) => {
+                        return Err(McpError::internal_error(
+                            format!("Could not load spikes: {}", e),
+                            None,
+                        ));
+                    }
+                }
+            }
+            DataSource::Remote { token, api_base } => {
+                // Filter pages below, the same way as `spikes clusters --page`
+                match fetch_remote_spikes(token, api_base, None, None, false) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        return Err(McpError::internal_error(e.to_string(), None));
+                    }
+                }
+            }
+        };
+
+        let threshold = args.threshold.unwrap_or(clusters::DEFAULT_THRESHOLD);
+        let mut found = clusters::compute_clusters(&spikes, threshold);
+        if let Some(page) = args.page.as_deref() {
+            found.retain(|c| c.on_page(page));
+        }
+
+        if found.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No element feedback with bounding boxes found. Nothing to cluster.",
+            )]));
+        }
+
+        let mut output = format!("Found {} cluster(s):\n\n", found.len());
+        for (i, cluster) in found.iter().enumerate() {
+            output.push_str(&format!(
+                "{}. {} ({} feedback item{})\n",
+                i + 1,
+                cluster.page,
+                cluster.count,
+                if cluster.count == 1 { "" } else { "s" }
+            ));
+            output.push_str(&format!("  Selectors: {}\n", cluster.selectors.join(", ")));
+            output.push_str(&format!(
+                "  Rating: {} (love {}, like {}, meh {}, no {})\n",
+                cluster.rating.as_ref().map(|r| r.to_string()).unwrap_or_else(|| "-".to_string()),
+                cluster.ratings.love,
+                cluster.ratings.like,
+                cluster.ratings.meh,
+                cluster.ratings.no
+            ));
+            for comment in &cluster.comments {
+                output.push_str(&format!("  - {}\n", comment));
+            }
+            output.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
+    /// Locate a spike's element in the project's HTML source.
+    ///
+    /// Resolves the page to a file and returns file:line:col with a snippet.
+    #[tool(
+        name = "get_spike_source_context",
+        description = "Find it in the code: map a spike to the HTML file, line and column where its element lives. Returns the surrounding source snippet so you can edit without hunting. Falls back to xpath and element text if the selector moved."
+    )]
+    async fn get_spike_source_context(
+        &self,
+        Parameters(args): Parameters<GetSpikeSourceContextArgs>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let spikes = match &self.data_source {
+            DataSource::Local => {
+                match load_spikes() {
+                    Ok(s) => s,
+                    Err(e) => {
+                        return Err(McpError::internal_error(
+                            format!("Could not load spikes: {}", e),
+                            None,
+                        ));
+                    }
+                }
+            }
+            DataSource::Remote { token, api_base } => {
+                match fetch_remote_spikes(token, api_base, None, None, false) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        return Err(McpError::internal_error(e.to_string(), None));
+                    }
+                }
+            }
+        };
+
+        let spike = find_spike_by_id(&spikes, &args.spike_id)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+        if spike.spike_type != SpikeType::Element {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Spike [{}] is page feedback on {}. No element to locate.",
+                &spike.id.chars().take(8).collect::<String>(),
+                spike.page
+            ))]));
+        }
+
+        let dir = match args.dir {
+            Some(dir) => PathBuf::from(dir),
+            None => Config::load()
+                .map(|c| c.site_dir())
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+        };
+        let site = Site::load(&dir)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+        let Some(page) = site.resolve(&spike) else {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "No HTML file found for page '{}' ({}) under {}.",
+                spike.page, spike.url, dir.display()
+            ))]));
+        };
+
+        let (status, element) = match page.locate(&spike) {
+            Location::Live(element) => ("live".to_string(), element),
+            Location::Moved(element, by) => (format!("moved, found by {}", by.as_str()), element),
+            Location::Gone => {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Element '{}' is gone from {}. Consider resolving spike [{}].",
+                    spike.selector.as_deref().unwrap_or("-"),
+                    page.path,
+                    &spike.id.chars().take(8).collect::<String>()
+                ))]));
+            }
+        };
+        let Some(offset) = page.source_offset(element) else {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Element '{}' is in {} but has no start tag in the source (implied by the parser).",
+                spike.selector.as_deref().unwrap_or("-"),
+                page.path
+            ))]));
+        };
+
+        let (line, column) = page.line_col(offset);
+        let context_lines = args.context_lines.unwrap_or(5) as usize;
+        let mut output = format!(
+            "Spike [{}] {} at {}:{}:{} ({})\n",
+            &spike.id.chars().take(8).collect::<String>(),
+            spike.selector.as_deref().unwrap_or("-"),
+            page.path,
+            line,
+            column,
+            status
+        );
+        if !spike.comments.is_empty() {
+            output.push_str(&format!("Comment: \"{}\"\n", spike.comments));
         }
+        output.push('\n');
+        output.push_str(&page.snippet(line, context_lines));
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
@@ -492,6 +794,31 @@ impl SpikesService {
         }
     }
 
+    /// Resolve every unresolved spike matching a filter.
+    ///
+    /// At least one filter is required so an empty call cannot resolve everything.
+    #[tool(
+        name = "bulk_resolve",
+        description = "Close the chapter: resolve every unresolved spike matching page/reviewer/rating/selector/tag/assignee filters. Use dry_run to preview first."
+    )]
+    async fn bulk_resolve(
+        &self,
+        Parameters(args): Parameters<BulkResolveArgs>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let dry_run = args.dry_run.unwrap_or(false);
+        let filter = bulk_resolve_filter(args)?;
+
+        match &self.data_source {
+            DataSource::Local => bulk_resolve_local(&filter, dry_run).await,
+            DataSource::Remote { token, api_base } => {
+                if !dry_run {
+                    check_write_scope(token, api_base, &self.cached_scope)?;
+                }
+                bulk_resolve_remote(&filter, dry_run, token, api_base).await
+            }
+        }
+    }
+
     /// Delete a spike from the JSONL file.
     ///
     /// Removes the spike entirely from the feedback file.
@@ -1322,8 +1649,10 @@ async fn submit_spike_local(args: SubmitSpikeArgs) -> std::result::Result<CallTo
         reviewer: Reviewer {
             id: nanoid::nanoid!(8),
             name: args.reviewer_name.unwrap_or_else(|| "MCP Agent".to_string()),
+            email: None,
         },
         selector: args.selector,
+        xpath: None,
         element_text: args.element_text,
         bounding_box: None,
         rating,
@@ -1332,6 +1661,13 @@ async fn submit_spike_local(args: SubmitSpikeArgs) -> std::result::Result<CallTo
         viewport: None,
         resolved: None,
         resolved_at: None,
+        duplicate_of: None,
+        stale: None,
+        tags: None,
+        assignee: None,
+        round: None,
+        page_hash: None,
+        issue: None,
     };
 
     // Load existing spikes and append the new one
@@ -1519,6 +1855,145 @@ async fn resolve_spike_remote(
     ))]))
 }
 
+/// Build the filter for bulk_resolve, rejecting calls without any filter
+fn bulk_resolve_filter(args: BulkResolveArgs) -> std::result::Result<SpikeFilter, McpError> {
+    if let Some(ref rating) = args.rating {
+        rating
+            .parse::<Rating>()
+            .map_err(|e| McpError::invalid_params(e, None))?;
+    }
+
+    let filter = SpikeFilter {
+        page: args.page,
+        reviewer: args.reviewer,
+        rating: args.rating,
+        unresolved: true,
+        selector: args.selector,
+        tag: args.tag,
+        assignee: args.assignee,
+        ..Default::default()
+    };
+    if filter.page.is_none()
+        && filter.reviewer.is_none()
+        && filter.rating.is_none()
+        && filter.selector.is_none()
+        && filter.tag.is_none()
+        && filter.assignee.is_none()
+    {
+        return Err(McpError::invalid_params(
+            "bulk_resolve needs at least one filter (page, reviewer, rating, selector, tag or assignee)",
+            None,
+        ));
+    }
+    Ok(filter)
+}
+
+fn format_bulk_resolve(spikes: &[&Spike], dry_run: bool) -> String {
+    if spikes.is_empty() {
+        return "No unresolved spikes match the filter.".to_string();
+    }
+
+    let mut output = format!(
+        "{} {} spike{}:\n",
+        if dry_run { "Would resolve" } else { "Resolved" },
+        spikes.len(),
+        if spikes.len() == 1 { "" } else { "s" }
+    );
+    for spike in spikes {
+        output.push_str(&format!(
+            "  [{}] {} - {}\n",
+            &spike.id.chars().take(8).collect::<String>(),
+            spike.page,
+            spike.comments
+        ));
+    }
+    output
+}
+
+/// Local implementation of bulk_resolve: one load, one save
+async fn bulk_resolve_local(filter: &SpikeFilter, dry_run: bool) -> std::result::Result<CallToolResult, McpError> {
+    let mut spikes = load_spikes()
+        .map_err(|e| McpError::internal_error(format!("Could not load spikes: {}", e), None))?;
+    let ids = select_ids(&spikes, filter);
+
+    let text = {
+        let matched: Vec<&Spike> = spikes.iter().filter(|s| ids.contains(&s.id)).collect();
+        format_bulk_resolve(&matched, dry_run)
+    };
+    if !dry_run && !ids.is_empty() {
+        BulkAction::Resolve.apply(&mut spikes, &ids.into_iter().collect());
+        save_spikes(&spikes)
+            .map_err(|e| McpError::internal_error(format!("Could not save spikes: {}", e), None))?;
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(text)]))
+}
+
+/// Remote implementation of bulk_resolve: PATCH each matching spike
+async fn bulk_resolve_remote(
+    filter: &SpikeFilter,
+    dry_run: bool,
+    token: &str,
+    api_base: &str,
+) -> std::result::Result<CallToolResult, McpError> {
+    let spikes = fetch_remote_spikes(token, api_base, None, None, true)
+        .map_err(|e| map_error_to_mcp(&e))?;
+    let matched: Vec<&Spike> = spikes.iter().filter(|s| filter.matches(s)).collect();
+
+    if dry_run {
+        return Ok(CallToolResult::success(vec![Content::text(format_bulk_resolve(&matched, dry_run))]));
+    }
+
+    // Keep going past failures so the caller learns which spikes did change
+    let body = serde_json::json!({ "resolved": true });
+    let mut resolved = Vec::new();
+    let mut failed = Vec::new();
+    for spike in matched {
+        let url = format!("{}/spikes/{}", api_base.trim_end_matches('/'), urlencoding::encode(&spike.id));
+        match ureq::request("PATCH", &url)
+            .set("Authorization", &format!("Bearer {}", token))
+            .set("Content-Type", "application/json")
+            .send_json(&body)
+        {
+            Ok(_) => resolved.push(spike),
+            Err(ureq::Error::Status(status, response)) => {
+                let body_text = response.into_string().ok();
+                failed.push((spike, map_http_error(status, body_text.as_deref()).to_string()));
+            }
+            Err(e) => failed.push((spike, map_network_error(&e.to_string()).to_string())),
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(CallToolResult::success(vec![Content::text(format_bulk_resolve(&resolved, false))]))
+    } else {
+        Ok(CallToolResult::error(vec![Content::text(format_partial_resolve(&resolved, &failed))]))
+    }
+}
+
+/// Report of a remote bulk resolve where some PATCH requests failed
+fn format_partial_resolve(resolved: &[&Spike], failed: &[(&Spike, String)]) -> String {
+    let mut output = if resolved.is_empty() {
+        "Resolved no spikes.\n".to_string()
+    } else {
+        format_bulk_resolve(resolved, false)
+    };
+    output.push_str(&format!(
+        "Could not resolve {} spike{}:\n",
+        failed.len(),
+        if failed.len() == 1 { "" } else { "s" }
+    ));
+    for (spike, error) in failed {
+        output.push_str(&format!(
+            "  [{}] {} - {}\n",
+            &spike.id.chars().take(8).collect::<String>(),
+            spike.page,
+            error
+        ));
+    }
+    output
+}
+
 /// Local implementation of delete_spike
 async fn delete_spike_local(args: DeleteSpikeArgs) -> std::result::Result<CallToolResult, McpError> {
     let result = remove_spike(&args.spike_id);
@@ -1900,7 +2375,10 @@ pub fn install(json: bool) -> crate::error::Result<()> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
+    use crate::spike::Viewport;
     use serial_test::serial;
 
     // Test helper to create sample spikes
@@ -1915,8 +2393,10 @@ mod tests {
                 reviewer: crate::spike::Reviewer {
                     id: "r1".to_string(),
                     name: "Alice".to_string(),
+                    email: None,
                 },
                 selector: None,
+                xpath: None,
                 element_text: None,
                 bounding_box: None,
                 rating: Some(Rating::Love),
@@ -1925,6 +2405,13 @@ mod tests {
                 viewport: None,
                 resolved: None,
                 resolved_at: None,
+                duplicate_of: None,
+                stale: None,
+                tags: None,
+                assignee: None,
+                round: None,
+                page_hash: None,
+                issue: None,
             },
             Spike {
                 id: "spike002def".to_string(),
@@ -1935,8 +2422,10 @@ mod tests {
                 reviewer: crate::spike::Reviewer {
                     id: "r2".to_string(),
                     name: "Bob".to_string(),
+                    email: None,
                 },
                 selector: Some(".hero-title".to_string()),
+                xpath: None,
                 element_text: Some("Welcome".to_string()),
                 bounding_box: None,
                 rating: Some(Rating::No),
@@ -1945,6 +2434,13 @@ mod tests {
                 viewport: None,
                 resolved: Some(true),
                 resolved_at: Some("2024-01-16T09:00:00Z".to_string()),
+                duplicate_of: None,
+                stale: None,
+                tags: None,
+                assignee: None,
+                round: None,
+                page_hash: None,
+                issue: None,
             },
             Spike {
                 id: "spike003ghi".to_string(),
@@ -1955,8 +2451,10 @@ mod tests {
                 reviewer: crate::spike::Reviewer {
                     id: "r1".to_string(),
                     name: "Alice".to_string(),
+                    email: None,
                 },
                 selector: Some(".hero-title".to_string()),
+                xpath: None,
                 element_text: Some("About Us".to_string()),
                 bounding_box: None,
                 rating: Some(Rating::Meh),
@@ -1965,6 +2463,13 @@ mod tests {
                 viewport: None,
                 resolved: None,
                 resolved_at: None,
+                duplicate_of: None,
+                stale: None,
+                tags: None,
+                assignee: None,
+                round: None,
+                page_hash: None,
+                issue: None,
             },
             Spike {
                 id: "spike004jkl".to_string(),
@@ -1975,8 +2480,10 @@ mod tests {
                 reviewer: crate::spike::Reviewer {
                     id: "r3".to_string(),
                     name: "Charlie".to_string(),
+                    email: None,
                 },
                 selector: Some(".nav-button".to_string()),
+                xpath: None,
                 element_text: Some("Menu".to_string()),
                 bounding_box: None,
                 rating: Some(Rating::Like),
@@ -1985,6 +2492,13 @@ mod tests {
                 viewport: None,
                 resolved: None,
                 resolved_at: None,
+                duplicate_of: None,
+                stale: None,
+                tags: None,
+                assignee: None,
+                round: None,
+                page_hash: None,
+                issue: None,
             },
         ]
     }
@@ -2004,8 +2518,10 @@ mod tests {
             reviewer: crate::spike::Reviewer {
                 id: "r1".to_string(),
                 name: "Test User".to_string(),
+                email: None,
             },
             selector: None,
+            xpath: None,
             element_text: None,
             bounding_box: None,
             rating: Some(Rating::Love),
@@ -2014,6 +2530,13 @@ mod tests {
             viewport: None,
             resolved: None,
             resolved_at: None,
+            duplicate_of: None,
+            stale: None,
+            tags: None,
+            assignee: None,
+            round: None,
+            page_hash: None,
+            issue: None,
         };
 
         let formatted = format_spike(&spike);
@@ -2037,8 +2560,10 @@ mod tests {
             reviewer: crate::spike::Reviewer {
                 id: "r1".to_string(),
                 name: "Test".to_string(),
+                email: None,
             },
             selector: Some(".hero".to_string()),
+            xpath: None,
             element_text: Some("Welcome".to_string()),
             bounding_box: None,
             rating: Some(Rating::No),
@@ -2047,6 +2572,13 @@ mod tests {
             viewport: None,
             resolved: Some(true),
             resolved_at: Some("2024-01-02T00:00:00Z".to_string()),
+            duplicate_of: None,
+            stale: None,
+            tags: None,
+            assignee: None,
+            round: None,
+            page_hash: None,
+            issue: None,
         };
 
         let formatted = format_spike(&spike);
@@ -2162,6 +2694,25 @@ mod tests {
         assert_eq!(matching[0].id, "spike002def");
     }
 
+    #[test]
+    fn test_get_element_feedback_equivalent_and_partial_selectors() {
+        let mut spikes = create_test_spikes();
+        spikes[1].selector = Some("h1.hero-title".to_string());
+        spikes[1].xpath = Some("/html/body/h1".to_string());
+        spikes[2].selector = Some("body > h1".to_string());
+        spikes[2].xpath = Some("/html[1]/body[1]/h1[1]".to_string());
+        spikes[2].page = spikes[1].page.clone();
+        let groups = group_elements(&spikes);
+
+        let exact = find_element(&groups, "H1.hero-title");
+        assert_eq!(exact.len(), 1);
+        assert_eq!(exact[0].spikes.len(), 2, "Shared xpath should join both spellings");
+
+        let partial = find_element(&groups, "nav");
+        assert_eq!(partial.len(), 1);
+        assert_eq!(partial[0].selector, ".nav-button");
+    }
+
     #[test]
     fn test_get_hotspots_counting() {
         let spikes = create_test_spikes();
@@ -2245,6 +2796,7 @@ mod tests {
             page: Some("index.html".to_string()),
             rating: Some("love".to_string()),
             unresolved_only: Some(true),
+            viewport: None,
         };
         let json = serde_json::to_string(&args).unwrap();
         assert!(json.contains("index.html"));
@@ -3224,6 +3776,245 @@ mod tests {
         assert!(result2.is_err(), "Second check with cached 'read' scope should still fail");
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_spike_source_context_returns_location_and_snippet() {
+        use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let mut spike = create_test_spikes().remove(3);
+        spike.url = "http://localhost:3847/index.html".to_string();
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/spikes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(vec![spike]))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("index.html"),
+            "<html>\n<body>\n  <nav>\n    <button class=\"nav-button\">Menu</button>\n  </nav>\n</body>\n</html>\n",
+        )
+        .unwrap();
+
+        let service = SpikesService::new(DataSource::Remote {
+            token: "test-token".to_string(),
+            api_base: server.uri(),
+        });
+        let args = GetSpikeSourceContextArgs {
+            spike_id: "spike004".to_string(),
+            context_lines: Some(1),
+            dir: Some(dir.path().display().to_string()),
+        };
+
+        let result = service.get_spike_source_context(Parameters(args)).await.unwrap();
+        let text = format!("{:?}", result.content);
+        assert!(text.contains("index.html:4:5 (live)"), "got: {}", text);
+        assert!(text.contains("4 |     <button class"), "got: {}", text);
+        assert!(!text.contains("<body>"), "Snippet should be limited to context lines");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bulk_resolve_remote_patches_matching_unresolved_spikes() {
+        use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/spikes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(create_test_spikes()))
+            .mount(&server)
+            .await;
+        for id in ["spike001abc", "spike004jkl"] {
+            Mock::given(matchers::method("PATCH"))
+                .and(matchers::path(format!("/spikes/{}", id)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let service = SpikesService::new(DataSource::Remote {
+            token: "test-token".to_string(),
+            api_base: server.uri(),
+        });
+        let args = BulkResolveArgs {
+            page: Some("index.html".to_string()),
+            reviewer: None,
+            rating: None,
+            selector: None,
+            tag: None,
+            assignee: None,
+            dry_run: None,
+        };
+
+        let result = service.bulk_resolve(Parameters(args)).await.unwrap();
+        let text = format!("{:?}", result.content);
+        assert!(text.contains("Resolved 2 spikes"), "got: {}", text);
+        assert!(!text.contains("spike002"), "Already resolved spikes are skipped");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_bulk_resolve_remote_reports_partial_failure() {
+        use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/spikes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(create_test_spikes()))
+            .mount(&server)
+            .await;
+        Mock::given(matchers::method("PATCH"))
+            .and(matchers::path("/spikes/spike001abc"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(matchers::method("PATCH"))
+            .and(matchers::path("/spikes/spike004jkl"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let service = SpikesService::new(DataSource::Remote {
+            token: "test-token".to_string(),
+            api_base: server.uri(),
+        });
+        let args = BulkResolveArgs {
+            page: Some("index.html".to_string()),
+            reviewer: None,
+            rating: None,
+            selector: None,
+            tag: None,
+            assignee: None,
+            dry_run: None,
+        };
+
+        let result = service.bulk_resolve(Parameters(args)).await.unwrap();
+        let text = format!("{:?}", result.content);
+        assert_eq!(result.is_error, Some(true));
+        assert!(text.contains("Resolved 1 spike:"), "got: {}", text);
+        assert!(text.contains("[spike004]"), "got: {}", text);
+        assert!(text.contains("Could not resolve 1 spike:"), "got: {}", text);
+        assert!(text.contains("[spike001]"), "got: {}", text);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_hotspots_includes_summaries() {
+        use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+        let mut spikes = create_test_spikes();
+        let mut again = spikes[1].clone();
+        again.id = "spike004jkl".to_string();
+        again.comments = "The font is small on mobile".to_string();
+        spikes.push(again);
+        let server = MockServer::start().await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/spikes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&spikes))
+            .mount(&server)
+            .await;
+
+        let service = SpikesService::new(DataSource::Remote {
+            token: "test-token".to_string(),
+            api_base: server.uri(),
+        });
+        let result = service
+            .get_hotspots(Parameters(GetHotspotsArgs { limit: Some(1) }))
+            .await
+            .unwrap();
+        let text = format!("{:?}", result.content);
+
+        assert!(text.contains("1. .hero-title (3 feedback items)"), "got: {}", text);
+        assert!(text.contains("Ratings: 2 no, 1 meh"));
+        assert!(text.contains("Keywords: font, small"));
+        // "Font too small" says nothing the first quote doesn't
+        assert!(text.contains("The font is small on mobile"));
+        assert!(!text.contains("Font too small"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_context_pack_respects_budget() {
+        use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+        let spikes = create_test_spikes();
+        let server = MockServer::start().await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/spikes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&spikes))
+            .mount(&server)
+            .await;
+
+        let service = SpikesService::new(DataSource::Remote {
+            token: "test-token".to_string(),
+            api_base: server.uri(),
+        });
+        let result = service
+            .get_context_pack(Parameters(GetContextPackArgs { max_tokens: None }))
+            .await
+            .unwrap();
+        let text = format!("{:?}", result.content);
+        assert!(text.contains("SPIKES CONTEXT PACK"), "got: {}", text);
+        assert!(text.contains("CRITICAL ISSUES"));
+
+        let result = service
+            .get_context_pack(Parameters(GetContextPackArgs { max_tokens: Some(150) }))
+            .await
+            .unwrap();
+        let text = format!("{:?}", result.content);
+        assert!(text.contains("OMITTED"), "got: {}", text);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_spikes_filters_by_viewport() {
+        use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+        let mut spikes = create_test_spikes();
+        spikes[0].viewport = Some(Viewport { width: 390, height: 844 });
+        spikes[1].viewport = Some(Viewport { width: 1440, height: 900 });
+        let server = MockServer::start().await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::path("/spikes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&spikes))
+            .mount(&server)
+            .await;
+
+        let service = SpikesService::new(DataSource::Remote {
+            token: "test-token".to_string(),
+            api_base: server.uri(),
+        });
+        let args = |viewport: &str| GetSpikesArgs {
+            page: None,
+            rating: None,
+            unresolved_only: None,
+            viewport: Some(viewport.to_string()),
+        };
+
+        let result = service.get_spikes(Parameters(args("mobile"))).await.unwrap();
+        let text = format!("{:?}", result.content);
+        assert!(text.contains("spike001"), "got: {}", text);
+        assert!(!text.contains("spike002"));
+
+        let err = service.get_spikes(Parameters(args("watch"))).await.unwrap_err();
+        assert!(err.message.contains("Invalid viewport"));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_resolve_requires_a_filter() {
+        let service = SpikesService::new(DataSource::Local);
+        let args = BulkResolveArgs {
+            page: None,
+            reviewer: None,
+            rating: None,
+            selector: None,
+            tag: None,
+            assignee: None,
+            dry_run: Some(true),
+        };
+
+        let err = service.bulk_resolve(Parameters(args)).await.unwrap_err();
+        assert!(err.message.contains("at least one filter"));
+    }
+
     #[tokio::test]
     async fn test_submit_spike_remote_read_scoped_returns_scope_error() {
         // Test the full flow: SpikesService with a read-scoped API key in Remote mode
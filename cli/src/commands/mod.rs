@@ -1,12 +1,21 @@
+pub mod assign;
 pub mod auth_keys;
 pub mod billing;
+pub mod bulk;
+pub mod check;
+pub mod clusters;
 pub mod config_cmd;
+pub mod context;
+pub mod dedupe;
 pub mod delete;
 pub mod deploy;
 pub mod export;
+pub mod heatmap;
 pub mod hotspots;
+pub mod import;
 pub mod init;
 pub mod inject;
+pub mod issues;
 pub mod list;
 pub mod login;
 pub mod logout;
@@ -17,13 +26,18 @@ pub mod push;
 pub mod remote;
 pub mod resolve;
 pub mod reviewers;
+pub mod round;
 pub mod serve;
 pub mod share;
 pub mod shares;
 pub mod show;
+pub mod stats;
 pub mod sync;
+pub mod tag;
+pub mod trends;
 pub mod unshare;
 pub mod update;
 pub mod upgrade;
 pub mod usage;
+pub mod watch;
 pub mod whoami;
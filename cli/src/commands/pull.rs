@@ -5,6 +5,7 @@ use std::path::Path;
 
 use crate::auth::get_api_base;
 use crate::error::{map_http_error, map_network_error, Error, Result};
+use crate::rounds::Rounds;
 use crate::spike::{PaginatedResponse, Spike};
 
 pub struct PullOptions {
@@ -14,9 +15,9 @@ pub struct PullOptions {
     pub json: bool,
 }
 
-struct RemoteConfig {
-    endpoint: String,
-    token: String,
+pub struct RemoteConfig {
+    pub endpoint: String,
+    pub token: String,
 }
 
 pub fn run(options: PullOptions) -> Result<()> {
@@ -38,9 +39,15 @@ pub fn run(options: PullOptions) -> Result<()> {
     let existing_ids: HashSet<String> = local_spikes.iter().map(|s| s.id.clone()).collect();
 
     // Find new spikes
-    let new_spikes: Vec<&Spike> = remote_spikes
+    let rounds = Rounds::load()?;
+    let new_spikes: Vec<Spike> = remote_spikes
         .iter()
         .filter(|s| !existing_ids.contains(&s.id))
+        .cloned()
+        .map(|mut s| {
+            rounds.stamp(&mut s);
+            s
+        })
         .collect();
 
     let new_count = new_spikes.len();
@@ -83,7 +90,7 @@ pub fn run(options: PullOptions) -> Result<()> {
     Ok(())
 }
 
-fn get_remote_config(
+pub fn get_remote_config(
     endpoint_arg: Option<String>,
     token_arg: Option<String>,
 ) -> Result<RemoteConfig> {
@@ -150,7 +157,7 @@ fn get_remote_config(
     }
 }
 
-fn fetch_remote_spikes(config: &RemoteConfig) -> Result<Vec<Spike>> {
+pub fn fetch_remote_spikes(config: &RemoteConfig) -> Result<Vec<Spike>> {
     let url = format!("{}/spikes", config.endpoint.trim_end_matches('/'));
 
     // Use ureq for synchronous HTTP (simpler than async for CLI)
@@ -248,9 +255,15 @@ fn run_from_share(url: &str, json_output: bool) -> Result<()> {
 
     let existing_ids: HashSet<String> = local_spikes.iter().map(|s| s.id.clone()).collect();
 
-    let new_spikes: Vec<&Spike> = remote_spikes
+    let rounds = Rounds::load()?;
+    let new_spikes: Vec<Spike> = remote_spikes
         .iter()
         .filter(|s| !existing_ids.contains(&s.id))
+        .cloned()
+        .map(|mut s| {
+            rounds.stamp(&mut s);
+            s
+        })
         .collect();
 
     let new_count = new_spikes.len();
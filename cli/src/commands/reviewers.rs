@@ -1,33 +1,61 @@
-use std::collections::HashMap;
-
 use crate::error::Result;
+use crate::identity::{Aliases, Identities};
 use crate::output::{print_json, print_reviewers_table};
 use crate::storage::load_spikes;
 
 pub fn run(json: bool) -> Result<()> {
     let spikes = load_spikes()?;
+    let identities = Identities::build(&spikes, &Aliases::load()?);
+
+    if json {
+        print_json(&identities.all());
+    } else {
+        print_reviewers_table(identities.all());
+    }
+
+    Ok(())
+}
+
+/// Record that reviewer `from` is the same person as `into`
+pub fn merge(from: &str, into: &str, json: bool) -> Result<()> {
+    let spikes = load_spikes()?;
+    let mut aliases = Aliases::load()?;
+    let identities = Identities::build(&spikes, &aliases);
+
+    let from = identities.find(from)?;
+    let into = identities.find(into)?;
 
-    let mut counts: HashMap<String, usize> = HashMap::new();
-    for spike in spikes {
-        *counts.entry(spike.reviewer.name).or_insert(0) += 1;
+    if from.id == into.id {
+        if json {
+            print_json(&serde_json::json!({
+                "merged": false,
+                "into": into,
+                "message": "Already the same reviewer"
+            }));
+        } else {
+            println!("{} and {} are already the same reviewer.", from.name, into.name);
+        }
+        return Ok(());
     }
 
-    let mut reviewers: Vec<(String, usize)> = counts.into_iter().collect();
-    reviewers.sort_by_key(|item| std::cmp::Reverse(item.1));
+    aliases.merge(&from.ids, &into.id);
+    aliases.save()?;
 
+    let merged = Identities::build(&spikes, &aliases);
+    let identity = merged.get(&into.id).unwrap_or(into);
     if json {
-        let output: Vec<serde_json::Value> = reviewers
-            .iter()
-            .map(|(name, count)| {
-                serde_json::json!({
-                    "name": name,
-                    "count": count
-                })
-            })
-            .collect();
-        print_json(&output);
+        print_json(&serde_json::json!({
+            "merged": true,
+            "into": identity
+        }));
     } else {
-        print_reviewers_table(&reviewers);
+        println!(
+            "Merged {} into {} ({} spikes across {} reviewer IDs).",
+            from.name,
+            identity.name,
+            identity.count,
+            identity.ids.len()
+        );
     }
 
     Ok(())
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::identity::load_canonical_spikes;
+use crate::output::{print_json, print_round_deltas_table, print_rounds_table};
+use crate::rounds::Rounds;
+use crate::selector::group_elements;
+use crate::spike::Spike;
+
+use super::stats::average_score;
+
+/// Rating change for one page or element between two rounds
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundDelta {
+    pub page: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    pub before_count: usize,
+    pub after_count: usize,
+    /// Mean rating score (no = 0 .. love = 3) in the first round
+    pub before: Option<f64>,
+    pub after: Option<f64>,
+    /// `after - before`, when both rounds rated it
+    pub delta: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundComparison {
+    pub before: String,
+    pub after: String,
+    pub pages: Vec<RoundDelta>,
+    pub elements: Vec<RoundDelta>,
+}
+
+fn delta(page: &str, selector: Option<String>, spikes: &[&Spike], before: &str, after: &str) -> RoundDelta {
+    let in_round = |round: &str| -> Vec<&Spike> {
+        spikes
+            .iter()
+            .copied()
+            .filter(|s| s.round.as_deref() == Some(round))
+            .collect()
+    };
+    let (b, a) = (in_round(before), in_round(after));
+    let (before_score, after_score) = (average_score(b.iter().copied()), average_score(a.iter().copied()));
+    RoundDelta {
+        page: page.to_string(),
+        selector,
+        before_count: b.len(),
+        after_count: a.len(),
+        before: before_score,
+        after: after_score,
+        delta: before_score.zip(after_score).map(|(b, a)| a - b),
+    }
+}
+
+/// Compare ratings per page and per element between two rounds
+pub fn compare_rounds(spikes: &[Spike], before: &str, after: &str) -> RoundComparison {
+    let mut by_page: BTreeMap<&str, Vec<&Spike>> = BTreeMap::new();
+    for spike in spikes {
+        if matches!(spike.round.as_deref(), Some(r) if r == before || r == after) {
+            by_page.entry(spike.page.as_str()).or_default().push(spike);
+        }
+    }
+
+    let mut pages = Vec::new();
+    let mut elements = Vec::new();
+    for (page, page_spikes) in &by_page {
+        pages.push(delta(page, None, page_spikes, before, after));
+
+        let mut groups = group_elements(page_spikes.iter().copied());
+        groups.sort_by(|a, b| a.selector.cmp(&b.selector));
+        for group in groups {
+            elements.push(delta(page, Some(group.selector), &group.spikes, before, after));
+        }
+    }
+
+    RoundComparison {
+        before: before.to_string(),
+        after: after.to_string(),
+        pages,
+        elements,
+    }
+}
+
+pub fn start(name: &str, json: bool) -> Result<()> {
+    let mut rounds = Rounds::load()?;
+    let round = rounds.start(name, &chrono::Local::now().to_rfc3339())?.clone();
+    rounds.save()?;
+
+    if json {
+        print_json(&round);
+    } else {
+        println!("Started round '{}'. New spikes from serve and pull will be stamped with it.", round.name);
+    }
+    Ok(())
+}
+
+pub fn close(json: bool) -> Result<()> {
+    let mut rounds = Rounds::load()?;
+    let round = rounds.close(&chrono::Local::now().to_rfc3339())?.clone();
+    rounds.save()?;
+
+    if json {
+        print_json(&round);
+    } else {
+        let count = load_canonical_spikes()?
+            .iter()
+            .filter(|s| s.round.as_deref() == Some(round.name.as_str()))
+            .count();
+        println!(
+            "Closed round '{}' ({} spike{}).",
+            round.name,
+            count,
+            if count == 1 { "" } else { "s" }
+        );
+    }
+    Ok(())
+}
+
+pub fn list(json: bool) -> Result<()> {
+    let rounds = Rounds::load()?;
+    let spikes = load_canonical_spikes()?;
+    let counts: Vec<usize> = rounds
+        .rounds
+        .iter()
+        .map(|r| spikes.iter().filter(|s| s.round.as_deref() == Some(r.name.as_str())).count())
+        .collect();
+
+    if json {
+        let output: Vec<serde_json::Value> = rounds
+            .rounds
+            .iter()
+            .zip(&counts)
+            .map(|(round, count)| {
+                let mut value = serde_json::to_value(round).unwrap_or_default();
+                value["count"] = serde_json::json!(count);
+                value
+            })
+            .collect();
+        print_json(&output);
+    } else {
+        print_rounds_table(&rounds.rounds, &counts);
+    }
+    Ok(())
+}
+
+pub fn compare(before: &str, after: &str, json: bool) -> Result<()> {
+    let rounds = Rounds::load()?;
+    for name in [before, after] {
+        if rounds.get(name).is_none() {
+            return Err(Error::RoundNotFound(name.to_string()));
+        }
+    }
+
+    let spikes = load_canonical_spikes()?;
+    let comparison = compare_rounds(&spikes, before, after);
+
+    if json {
+        print_json(&comparison);
+        return Ok(());
+    }
+
+    if comparison.pages.is_empty() {
+        println!("No spikes in rounds '{}' or '{}'.", before, after);
+        return Ok(());
+    }
+
+    println!("Pages");
+    print_round_deltas_table(&comparison.pages, before, after);
+    if !comparison.elements.is_empty() {
+        println!();
+        println!("Elements");
+        print_round_deltas_table(&comparison.elements, before, after);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Rating, Reviewer, SpikeType};
+
+    fn spike(page: &str, selector: Option<&str>, rating: Rating, round: &str) -> Spike {
+        Spike {
+            spike_type: if selector.is_some() { SpikeType::Element } else { SpikeType::Page },
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: selector.map(|s| s.to_string()),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            round: Some(round.to_string()),
+            ..Spike::test(&format!("{}-{:?}-{}", page, selector, round), page, Some(rating))
+        }
+    }
+
+    #[test]
+    fn test_compare_rounds_pages_and_elements() {
+        let spikes = vec![
+            spike("pricing.html", Some("div.plan   > .cta"), Rating::No, "v1"),
+            spike("pricing.html", None, Rating::Meh, "v1"),
+            spike("pricing.html", Some("div.plan > .cta"), Rating::Love, "v2"),
+            spike("pricing.html", Some(".faq"), Rating::Like, "v2"),
+            spike("index.html", None, Rating::Like, "v1"),
+            spike("about.html", None, Rating::Love, "v0"),
+        ];
+        let comparison = compare_rounds(&spikes, "v1", "v2");
+
+        let pages: Vec<&str> = comparison.pages.iter().map(|p| p.page.as_str()).collect();
+        assert_eq!(pages, vec!["index.html", "pricing.html"], "Other rounds are ignored");
+
+        let index = &comparison.pages[0];
+        assert_eq!((index.before_count, index.after_count), (1, 0));
+        assert_eq!(index.delta, None);
+
+        let pricing = &comparison.pages[1];
+        assert_eq!(pricing.before, Some(0.5));
+        assert_eq!(pricing.after, Some(2.5));
+        assert_eq!(pricing.delta, Some(2.0));
+
+        assert_eq!(comparison.elements.len(), 2);
+        let cta = &comparison.elements[1];
+        assert_eq!(cta.selector.as_deref(), Some("div.plan > .cta"));
+        assert_eq!(cta.delta, Some(3.0), "Equivalent selectors are one element");
+        assert_eq!(comparison.elements[0].before_count, 0);
+    }
+}
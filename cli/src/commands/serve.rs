@@ -13,7 +13,10 @@ use axum::{
 use tokio::fs as async_fs;
 use tower_http::cors::CorsLayer;
 
+use crate::config::Config;
 use crate::error::Result;
+use crate::pages::page_hash;
+use crate::rounds::Rounds;
 use crate::spike::Spike;
 
 const DEFAULT_PORT: u16 = 3847;
@@ -23,7 +26,8 @@ const REVIEW_JS: &str = include_str!("../../assets/review.js");
 
 pub struct ServeOptions {
     pub port: u16,
-    pub directory: String,
+    /// Directory to serve; `None` serves `[serve] dir` and keeps `.spikes/` in the project
+    pub directory: Option<String>,
     pub marked: bool,
     pub cors_allow_origin: Option<String>,
 }
@@ -37,14 +41,21 @@ struct AppState {
 
 pub fn run(opts: ServeOptions) -> Result<()> {
     let port = if opts.port == 0 { DEFAULT_PORT } else { opts.port };
-    let serve_dir = PathBuf::from(&opts.directory).canonicalize().map_err(|e| {
+    let directory = match &opts.directory {
+        Some(directory) => PathBuf::from(directory),
+        None => Config::load()?.site_dir(),
+    };
+    let serve_dir = directory.canonicalize().map_err(|e| {
         crate::error::Error::Io(std::io::Error::new(
             std::io::ErrorKind::NotFound,
-            format!("Directory not found: {} ({})", opts.directory, e),
+            format!("Directory not found: {} ({})", directory.display(), e),
         ))
     })?;
 
-    let spikes_dir = serve_dir.join(".spikes");
+    let spikes_dir = match opts.directory {
+        Some(_) => serve_dir.join(".spikes"),
+        None => PathBuf::from(".spikes"),
+    };
     if !spikes_dir.exists() {
         fs::create_dir_all(&spikes_dir)?;
     }
@@ -179,10 +190,23 @@ async fn get_spikes(State(state): State<AppState>) -> Response<Body> {
 
 async fn save_spike(
     State(state): State<AppState>,
-    Json(spike): Json<Spike>,
+    Json(mut spike): Json<Spike>,
 ) -> Response<Body> {
     let feedback_file = state.spikes_dir.join("feedback.jsonl");
 
+    // A broken rounds.toml must not cost us the feedback itself
+    Rounds::load_from(&state.spikes_dir.join("rounds.toml"))
+        .unwrap_or_default()
+        .stamp(&mut spike);
+    // Remember which version of the page was reviewed, so later edits can
+    // flag the spike as outdated
+    let serve_dir = state.serve_dir.clone();
+    let captured = spike.clone();
+    spike.page_hash = tokio::task::spawn_blocking(move || page_hash(&serve_dir, &captured))
+        .await
+        .ok()
+        .flatten();
+
     let mut json = match serde_json::to_string(&spike) {
         Ok(j) => j,
         Err(e) => {
@@ -1,19 +1,60 @@
+use crate::config::Config;
 use crate::error::{Error, Result};
-use crate::output::{print_json, print_spike_detail};
+use crate::identity::load_canonical_spikes;
+use crate::output::{print_json, print_spike_detail, print_summary};
+use crate::pages::{is_outdated, spike_json_with_source, PageHashes, Site};
+use crate::spike::SpikeType;
 use crate::storage::load_spikes;
+use crate::summary::{thread_of, Summary};
 
-pub fn run(id: &str, json: bool) -> Result<()> {
-    let spikes = load_spikes()?;
+use super::dedupe::{agree_count, agree_label};
 
-    let spike = spikes
-        .into_iter()
+pub fn run(id: &str, summary: bool, json: bool) -> Result<()> {
+    // Show the spike as stored; threads and agreement use merged reviewers
+    let stored = load_spikes()?;
+    let spike = stored
+        .iter()
         .find(|s| s.id == id || s.id.starts_with(id))
         .ok_or_else(|| Error::SpikeNotFound(id.to_string()))?;
+    let spikes = load_canonical_spikes()?;
+
+    let agree = agree_count(&spikes, &spike.id);
+    let site_dir = Config::load()?.site_dir();
+    let site = (spike.spike_type == SpikeType::Element)
+        .then(|| Site::load(&site_dir).ok())
+        .flatten();
+    let summary = summary.then(|| {
+        let (target, thread) = thread_of(&spikes, spike);
+        (target, Summary::of(&thread))
+    });
 
     if json {
-        print_json(&spike);
+        let mut value = spike_json_with_source(spike, site.as_ref(), &mut PageHashes::new(&site_dir))?;
+        if agree > 0 {
+            value["agreeCount"] = serde_json::json!(agree);
+        }
+        if let Some((target, summary)) = &summary {
+            value["summary"] = serde_json::to_value(summary)?;
+            value["summary"]["target"] = serde_json::json!(target);
+        }
+        print_json(&value);
     } else {
-        print_spike_detail(&spike);
+        print_spike_detail(spike);
+        if let Some(location) = site.as_ref().and_then(|site| site.source_location(spike)) {
+            println!();
+            println!("Source:     {}", location);
+        }
+        if is_outdated(&site_dir, spike) == Some(true) {
+            println!("Outdated:   page changed since this feedback was given");
+        }
+        if agree > 0 {
+            println!();
+            println!("{}", agree_label(agree));
+        }
+        if let Some((target, summary)) = &summary {
+            println!();
+            print_summary(target, summary);
+        }
     }
 
     Ok(())
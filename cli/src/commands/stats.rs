@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::config::{Config, ViewportConfig};
+use crate::error::Result;
+use crate::identity::load_canonical_spikes;
+use crate::output::{print_json, print_page_stats_table, print_viewport_stats_table};
+use crate::spike::{Rating, Spike, ViewportClass};
+
+use super::list::SpikeFilter;
+
+pub struct StatsOptions {
+    pub json: bool,
+    pub filter: SpikeFilter,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RatingCounts {
+    pub love: usize,
+    pub like: usize,
+    pub meh: usize,
+    pub no: usize,
+    pub unrated: usize,
+}
+
+impl RatingCounts {
+    pub fn add(&mut self, rating: Option<&Rating>) {
+        match rating {
+            Some(Rating::Love) => self.love += 1,
+            Some(Rating::Like) => self.like += 1,
+            Some(Rating::Meh) => self.meh += 1,
+            Some(Rating::No) => self.no += 1,
+            None => self.unrated += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PageStats {
+    pub page: String,
+    pub total: usize,
+    pub unresolved: usize,
+    /// Mean rating score (no = 0 .. love = 3) of rated spikes
+    pub average: Option<f64>,
+    /// Spikes rated meh or no
+    pub negative: usize,
+}
+
+/// Feedback from one viewport class
+#[derive(Debug, Clone, Serialize)]
+pub struct ViewportStats {
+    /// mobile, tablet, desktop, or unknown for spikes without a viewport width
+    pub viewport: String,
+    /// Width range of the class, e.g. "≤767px"
+    pub range: Option<String>,
+    pub total: usize,
+    pub average: Option<f64>,
+    /// Spikes rated meh or no
+    pub negative: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Stats {
+    pub total: usize,
+    pub unresolved: usize,
+    pub resolved: usize,
+    pub reviewers: usize,
+    pub ratings: RatingCounts,
+    pub average: Option<f64>,
+    pub pages: Vec<PageStats>,
+    pub viewports: Vec<ViewportStats>,
+}
+
+/// Mean rating score of the rated spikes, if any are rated
+pub fn average_score<'a>(spikes: impl IntoIterator<Item = &'a Spike>) -> Option<f64> {
+    let scores: Vec<f64> = spikes
+        .into_iter()
+        .filter_map(|s| s.rating.as_ref().map(Rating::score))
+        .collect();
+    (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64)
+}
+
+/// Spikes per viewport class, mobile to desktop, then spikes without a
+/// viewport width. Classes without spikes are left out.
+pub fn viewport_breakdown(spikes: &[Spike], breakpoints: &ViewportConfig) -> Vec<ViewportStats> {
+    let classes = ViewportClass::ALL.into_iter().map(Some).chain([None]);
+    classes
+        .filter_map(|class| {
+            let matching: Vec<&Spike> = spikes
+                .iter()
+                .filter(|s| s.viewport.as_ref().and_then(|vp| breakpoints.classify(vp)) == class)
+                .collect();
+            (!matching.is_empty()).then(|| ViewportStats {
+                viewport: class.map_or("unknown".to_string(), |c| c.to_string()),
+                range: class.map(|c| breakpoints.range(c)),
+                total: matching.len(),
+                average: average_score(matching.iter().copied()),
+                negative: matching
+                    .iter()
+                    .filter(|s| s.rating.as_ref().is_some_and(Rating::is_negative))
+                    .count(),
+            })
+        })
+        .collect()
+}
+
+pub fn compute_stats(spikes: &[Spike], breakpoints: &ViewportConfig) -> Stats {
+    let mut ratings = RatingCounts::default();
+    let mut by_page: BTreeMap<&str, Vec<&Spike>> = BTreeMap::new();
+    for spike in spikes {
+        ratings.add(spike.rating.as_ref());
+        by_page.entry(spike.page.as_str()).or_default().push(spike);
+    }
+
+    let pages = by_page
+        .into_iter()
+        .map(|(page, spikes)| PageStats {
+            page: page.to_string(),
+            total: spikes.len(),
+            unresolved: spikes.iter().filter(|s| !s.is_resolved()).count(),
+            average: average_score(spikes.iter().copied()),
+            negative: spikes
+                .iter()
+                .filter(|s| s.rating.as_ref().is_some_and(Rating::is_negative))
+                .count(),
+        })
+        .collect();
+
+    let resolved = spikes.iter().filter(|s| s.is_resolved()).count();
+    Stats {
+        total: spikes.len(),
+        unresolved: spikes.len() - resolved,
+        resolved,
+        reviewers: spikes.iter().map(|s| s.reviewer.id.as_str()).collect::<HashSet<_>>().len(),
+        ratings,
+        average: average_score(spikes),
+        pages,
+        viewports: viewport_breakdown(spikes, breakpoints),
+    }
+}
+
+/// Format a mean score with the rating it rounds to, e.g. "1.50 (meh)"
+pub fn format_score(score: Option<f64>) -> String {
+    match score {
+        Some(score) => format!("{:.2} ({})", score, Rating::from_score(score)),
+        None => "-".to_string(),
+    }
+}
+
+pub fn run(options: StatsOptions) -> Result<()> {
+    let spikes: Vec<Spike> = load_canonical_spikes()?
+        .into_iter()
+        .filter(|s| options.filter.matches(s))
+        .collect();
+    let stats = compute_stats(&spikes, &Config::load()?.viewport);
+
+    if options.json {
+        print_json(&stats);
+        return Ok(());
+    }
+
+    if stats.total == 0 {
+        println!("No spikes found.");
+        return Ok(());
+    }
+
+    let r = &stats.ratings;
+    println!("Spikes:     {} ({} unresolved)", stats.total, stats.unresolved);
+    println!("Reviewers:  {}", stats.reviewers);
+    println!(
+        "Ratings:    love {} · like {} · meh {} · no {} · unrated {}",
+        r.love, r.like, r.meh, r.no, r.unrated
+    );
+    println!("Average:    {}", format_score(stats.average));
+    println!();
+    print_page_stats_table(&stats.pages);
+    // Only worth a table once the widget has recorded viewports
+    if stats.viewports.iter().any(|v| v.range.is_some()) {
+        println!();
+        print_viewport_stats_table(&stats.viewports);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Reviewer, Viewport};
+
+    fn spike(page: &str, reviewer: &str, rating: Option<Rating>, resolved: bool) -> Spike {
+        Spike {
+            reviewer: Reviewer {
+                id: reviewer.to_string(),
+                name: reviewer.to_string(),
+                email: None,
+            },
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            resolved: resolved.then_some(true),
+            ..Spike::test(&format!("{}-{}", page, reviewer), page, rating)
+        }
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let spikes = vec![
+            spike("index.html", "a", Some(Rating::Love), false),
+            spike("index.html", "b", Some(Rating::No), true),
+            spike("about.html", "a", Some(Rating::Meh), false),
+            spike("about.html", "c", None, false),
+        ];
+        let stats = compute_stats(&spikes, &ViewportConfig::default());
+
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.resolved, 1);
+        assert_eq!(stats.reviewers, 3);
+        assert_eq!(stats.ratings, RatingCounts { love: 1, like: 0, meh: 1, no: 1, unrated: 1 });
+        assert_eq!(stats.average, Some(4.0 / 3.0));
+
+        assert_eq!(stats.pages[0].page, "about.html");
+        assert_eq!(stats.pages[0].average, Some(1.0));
+        assert_eq!(stats.pages[0].negative, 1);
+        assert_eq!(stats.pages[1].unresolved, 1);
+        assert_eq!(stats.pages[1].average, Some(1.5));
+    }
+
+    #[test]
+    fn test_viewport_breakdown() {
+        let mut spikes = vec![
+            spike("index.html", "a", Some(Rating::No), false),
+            spike("index.html", "b", Some(Rating::Meh), false),
+            spike("index.html", "c", Some(Rating::Love), false),
+            spike("index.html", "d", Some(Rating::Like), false),
+            spike("index.html", "e", Some(Rating::Like), false),
+        ];
+        spikes[0].viewport = Some(Viewport { width: 390, height: 844 });
+        spikes[1].viewport = Some(Viewport { width: 767, height: 1024 });
+        spikes[2].viewport = Some(Viewport { width: 1440, height: 900 });
+        spikes[4].viewport = Some(Viewport { width: 0, height: 0 });
+
+        let breakdown = viewport_breakdown(&spikes, &ViewportConfig::default());
+        let summary: Vec<(&str, usize, usize)> = breakdown
+            .iter()
+            .map(|v| (v.viewport.as_str(), v.total, v.negative))
+            .collect();
+        assert_eq!(summary, vec![("mobile", 2, 2), ("desktop", 1, 0), ("unknown", 2, 0)]);
+        assert_eq!(breakdown[0].range.as_deref(), Some("≤767px"));
+        assert_eq!(breakdown[0].average, Some(0.5));
+        assert_eq!(breakdown[2].range, None);
+    }
+
+    #[test]
+    fn test_format_score() {
+        assert_eq!(format_score(Some(2.25)), "2.25 (like)");
+        assert_eq!(format_score(None), "-");
+    }
+}
@@ -4,7 +4,22 @@ use crate::error::Result;
 use super::pull::PullOptions;
 use super::push::PushOptions;
 
-/// Sync with remote: pull then push
+/// Refresh the state of issues filed with `spikes issues create`. Tracker
+/// failures are warnings here, so they don't fail the remote sync.
+fn sync_issues(json: bool) -> Result<()> {
+    let Some(report) = super::issues::refresh()? else {
+        return Ok(());
+    };
+    if json {
+        println!("{}", serde_json::json!({ "issues": report }));
+    } else {
+        print!("  ");
+        super::issues::print_report(&report);
+    }
+    Ok(())
+}
+
+/// Sync with remote: pull then push, then refresh linked issues
 pub fn run(json: bool) -> Result<()> {
     let config = Config::load()?;
 
@@ -28,7 +43,7 @@ pub fn run(json: bool) -> Result<()> {
             eprintln!("    spikes deploy cloudflare");
             eprintln!();
         }
-        return Ok(());
+        return sync_issues(json);
     }
 
     if !json {
@@ -66,6 +81,8 @@ pub fn run(json: bool) -> Result<()> {
         return Err(e);
     }
 
+    sync_issues(json)?;
+
     if !json {
         println!();
         println!("  / Sync complete");
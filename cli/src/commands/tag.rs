@@ -0,0 +1,24 @@
+use crate::error::Result;
+
+use super::bulk::{self, BulkAction};
+
+pub struct TagOptions {
+    pub id: Option<String>,
+    /// `key=value` filters selecting the spikes to tag instead of an ID
+    pub filters: Vec<String>,
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+    pub force: bool,
+    pub json: bool,
+}
+
+pub fn run(options: TagOptions) -> Result<()> {
+    let action = BulkAction::Tag {
+        add: options.add,
+        remove: options.remove,
+    };
+    match options.id {
+        Some(id) => bulk::run_one(&id, action, options.json),
+        None => bulk::run_where(&options.filters, action, options.force, options.json),
+    }
+}
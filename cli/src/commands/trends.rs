@@ -0,0 +1,247 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::identity::load_canonical_spikes;
+use crate::output::{print_json, print_trends_table};
+use crate::spike::{Rating, Spike};
+
+use super::list::SpikeFilter;
+
+const CHART_WIDTH: usize = 40;
+
+/// Size of the time buckets spikes are grouped into
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bucket {
+    Day,
+    /// ISO weeks, starting on Monday
+    Week,
+}
+
+impl Bucket {
+    /// First day of the bucket containing `date`
+    fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Bucket::Day => date,
+            Bucket::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        }
+    }
+
+    fn step(self) -> Duration {
+        match self {
+            Bucket::Day => Duration::days(1),
+            Bucket::Week => Duration::weeks(1),
+        }
+    }
+
+    /// "2024-01-15" for days, "2024-W03" for weeks
+    fn label(self, start: NaiveDate) -> String {
+        match self {
+            Bucket::Day => start.format("%Y-%m-%d").to_string(),
+            Bucket::Week => {
+                let week = start.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+        }
+    }
+}
+
+pub struct TrendsOptions {
+    pub filter: SpikeFilter,
+    pub bucket: Bucket,
+    pub chart: bool,
+    pub json: bool,
+}
+
+/// Feedback received in one time bucket
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrendPoint {
+    pub bucket: String,
+    /// First day of the bucket
+    pub start: String,
+    pub total: usize,
+    /// Spikes with a rating
+    pub rated: usize,
+    /// Spikes rated meh or no
+    pub negative: usize,
+    /// `negative / rated`, if anything was rated
+    pub negative_ratio: Option<f64>,
+}
+
+/// Count spikes per bucket, oldest first.
+///
+/// Buckets without feedback between the first and last spike are included
+/// so gaps show up in the chart. Spikes with unparseable timestamps are
+/// skipped.
+pub fn compute_trends(spikes: &[Spike], bucket: Bucket) -> Vec<TrendPoint> {
+    let dated: Vec<(NaiveDate, &Spike)> = spikes
+        .iter()
+        .filter_map(|s| {
+            let time = DateTime::parse_from_rfc3339(&s.timestamp).ok()?;
+            Some((bucket.start(time.date_naive()), s))
+        })
+        .collect();
+
+    let (Some(first), Some(last)) = (
+        dated.iter().map(|(d, _)| *d).min(),
+        dated.iter().map(|(d, _)| *d).max(),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut points = Vec::new();
+    let mut start = first;
+    while start <= last {
+        let in_bucket: Vec<&Spike> = dated.iter().filter(|(d, _)| *d == start).map(|(_, s)| *s).collect();
+        let rated = in_bucket.iter().filter(|s| s.rating.is_some()).count();
+        let negative = in_bucket
+            .iter()
+            .filter(|s| s.rating.as_ref().is_some_and(Rating::is_negative))
+            .count();
+        points.push(TrendPoint {
+            bucket: bucket.label(start),
+            start: start.format("%Y-%m-%d").to_string(),
+            total: in_bucket.len(),
+            rated,
+            negative,
+            negative_ratio: (rated > 0).then(|| negative as f64 / rated as f64),
+        });
+        start += bucket.step();
+    }
+    points
+}
+
+/// Format a ratio as a whole percentage, e.g. "40%"
+pub fn format_ratio(ratio: Option<f64>) -> String {
+    match ratio {
+        Some(ratio) => format!("{:.0}%", ratio * 100.0),
+        None => "-".to_string(),
+    }
+}
+
+/// One line comparing the first and last buckets with rated feedback
+fn summary(points: &[TrendPoint]) -> Option<String> {
+    let mut rated = points.iter().filter_map(|p| p.negative_ratio);
+    let first = rated.next()?;
+    let last = rated.next_back()?;
+    let direction = if last < first {
+        "improving"
+    } else if last > first {
+        "worsening"
+    } else {
+        "unchanged"
+    };
+    Some(format!(
+        "Negative ratio: {} → {} ({})",
+        format_ratio(Some(first)),
+        format_ratio(Some(last)),
+        direction
+    ))
+}
+
+fn print_chart(points: &[TrendPoint]) {
+    for point in points {
+        match point.negative_ratio {
+            Some(ratio) => {
+                let filled = (ratio * CHART_WIDTH as f64).round() as usize;
+                println!(
+                    "{:<10}  {}{}  {:>4}  ({}/{} negative)",
+                    point.bucket,
+                    "█".repeat(filled),
+                    "░".repeat(CHART_WIDTH - filled),
+                    format_ratio(Some(ratio)),
+                    point.negative,
+                    point.rated
+                );
+            }
+            None if point.total > 0 => println!("{:<10}  ({} unrated)", point.bucket, point.total),
+            None => println!("{:<10}  (no feedback)", point.bucket),
+        }
+    }
+}
+
+pub fn run(options: TrendsOptions) -> Result<()> {
+    let spikes: Vec<Spike> = load_canonical_spikes()?
+        .into_iter()
+        .filter(|s| options.filter.matches(s))
+        .collect();
+    let points = compute_trends(&spikes, options.bucket);
+
+    if options.json {
+        print_json(&points);
+        return Ok(());
+    }
+
+    if points.is_empty() {
+        println!("No spikes found.");
+        return Ok(());
+    }
+
+    if options.chart {
+        print_chart(&points);
+    } else {
+        print_trends_table(&points);
+    }
+    if let Some(summary) = summary(&points) {
+        println!();
+        println!("{}", summary);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::Reviewer;
+
+    fn spike(timestamp: &str, rating: Option<Rating>) -> Spike {
+        Spike {
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            timestamp: timestamp.to_string(),
+            ..Spike::test(timestamp, "pricing.html", rating)
+        }
+    }
+
+    #[test]
+    fn test_daily_trends_fill_gaps() {
+        let spikes = vec![
+            spike("2024-01-15T10:00:00Z", Some(Rating::No)),
+            spike("2024-01-15T18:00:00Z", Some(Rating::Like)),
+            spike("2024-01-17T09:00:00+02:00", Some(Rating::Love)),
+            spike("2024-01-17T09:30:00Z", None),
+            spike("not a timestamp", Some(Rating::No)),
+        ];
+        let points = compute_trends(&spikes, Bucket::Day);
+
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].bucket, "2024-01-15");
+        assert_eq!((points[0].total, points[0].negative), (2, 1));
+        assert_eq!(points[0].negative_ratio, Some(0.5));
+        assert_eq!(points[1].total, 0);
+        assert_eq!(points[1].negative_ratio, None);
+        assert_eq!((points[2].total, points[2].rated), (2, 1));
+        assert_eq!(points[2].negative_ratio, Some(0.0));
+        assert_eq!(summary(&points).unwrap(), "Negative ratio: 50% → 0% (improving)");
+    }
+
+    #[test]
+    fn test_weekly_trends_start_on_monday() {
+        let spikes = vec![
+            // Sunday, then the Monday after
+            spike("2024-01-14T10:00:00Z", Some(Rating::Meh)),
+            spike("2024-01-15T10:00:00Z", Some(Rating::No)),
+            spike("2024-01-21T10:00:00Z", Some(Rating::Love)),
+        ];
+        let points = compute_trends(&spikes, Bucket::Week);
+
+        assert_eq!(points.len(), 2);
+        assert_eq!((points[0].bucket.as_str(), points[0].start.as_str()), ("2024-W02", "2024-01-08"));
+        assert_eq!((points[1].bucket.as_str(), points[1].start.as_str()), ("2024-W03", "2024-01-15"));
+        assert_eq!(points[1].negative_ratio, Some(0.5));
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::error::{Error, Result};
+use crate::identity::load_canonical_spikes;
+use crate::spike::Spike;
+
+use super::list::SpikeFilter;
+use super::pull::{fetch_remote_spikes, get_remote_config};
+
+const FEEDBACK_FILE: &str = ".spikes/feedback.jsonl";
+
+pub struct WatchOptions {
+    pub json: bool,
+    pub filter: SpikeFilter,
+    pub remote: bool,
+    pub interval: Option<u64>,
+    pub exec: Option<String>,
+}
+
+pub fn run(options: WatchOptions) -> Result<()> {
+    if options.remote {
+        watch_remote(&options)
+    } else {
+        watch_local(&options)
+    }
+}
+
+/// Tail the local feedback file, reloading whenever it changes
+fn watch_local(options: &WatchOptions) -> Result<()> {
+    let path = Path::new(FEEDBACK_FILE);
+    let mut seen: HashSet<String> = load_canonical_spikes()?.into_iter().map(|s| s.id).collect();
+    let mut last_state = file_state(path);
+    let interval = Duration::from_secs(options.interval.unwrap_or(1));
+
+    if !options.json {
+        eprintln!("Watching {} for new spikes (Ctrl+C to stop)...", FEEDBACK_FILE);
+    }
+
+    loop {
+        thread::sleep(interval);
+        let state = file_state(path);
+        if state == last_state {
+            continue;
+        }
+
+        let spikes = match load_canonical_spikes() {
+            Ok(spikes) => spikes,
+            // A spike may be half-written; pick it up on the next tick
+            Err(Error::Json(_)) => continue,
+            Err(e) => return Err(e),
+        };
+        last_state = state;
+        emit_new(spikes, &mut seen, options)?;
+    }
+}
+
+/// Poll the remote for spikes not seen yet
+fn watch_remote(options: &WatchOptions) -> Result<()> {
+    let config = get_remote_config(None, None)?;
+    let mut seen: HashSet<String> = fetch_remote_spikes(&config)?.into_iter().map(|s| s.id).collect();
+    let interval = Duration::from_secs(options.interval.unwrap_or(10));
+
+    if !options.json {
+        eprintln!("Watching {} for new spikes (Ctrl+C to stop)...", config.endpoint);
+    }
+
+    loop {
+        thread::sleep(interval);
+        match fetch_remote_spikes(&config) {
+            Ok(spikes) => emit_new(spikes, &mut seen, options)?,
+            // Keep watching through transient network trouble
+            Err(e @ (Error::ConnectionFailed | Error::ServerFailure | Error::RequestFailed(_))) => {
+                eprintln!("Warning: {}", e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn file_state(path: &Path) -> Option<(u64, SystemTime)> {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.len(), meta.modified().ok()?))
+}
+
+/// Print (and run the exec hook for) spikes not seen before that match the filter
+fn emit_new(spikes: Vec<Spike>, seen: &mut HashSet<String>, options: &WatchOptions) -> Result<()> {
+    let stdout = io::stdout();
+    for spike in spikes {
+        if !seen.insert(spike.id.clone()) || !options.filter.matches(&spike) {
+            continue;
+        }
+
+        let json = serde_json::to_string(&spike)?;
+        {
+            let mut handle = stdout.lock();
+            if options.json {
+                writeln!(handle, "{}", json)?;
+            } else {
+                writeln!(handle, "{}", format_line(&spike))?;
+            }
+            handle.flush()?;
+        }
+
+        if let Some(ref cmd) = options.exec {
+            if let Err(e) = run_hook(cmd, &spike, &json) {
+                eprintln!("Warning: --exec failed for {}: {}", spike.id, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn format_line(spike: &Spike) -> String {
+    let time = chrono::DateTime::parse_from_rfc3339(&spike.timestamp)
+        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+        .unwrap_or_else(|_| spike.timestamp.clone());
+    let mut line = format!(
+        "{}  {}  {:<4}  {}",
+        time,
+        spike.id.chars().take(8).collect::<String>(),
+        spike.rating_str(),
+        spike.page
+    );
+    if let Some(ref selector) = spike.selector {
+        line.push_str(&format!("  {}", selector));
+    }
+    if !spike.comments.is_empty() {
+        line.push_str(&format!("  \"{}\"", spike.comments));
+    }
+    line.push_str(&format!("  — {}", spike.reviewer.name));
+    line
+}
+
+/// Run the exec hook through the shell with the spike as JSON on stdin and
+/// its main fields as SPIKE_* environment variables
+fn run_hook(cmd: &str, spike: &Spike, json: &str) -> Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.args(["/C", cmd]);
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.args(["-c", cmd]);
+        c
+    };
+
+    let mut child = command
+        .env("SPIKE_ID", &spike.id)
+        .env("SPIKE_TYPE", spike.type_str())
+        .env("SPIKE_PAGE", &spike.page)
+        .env("SPIKE_URL", &spike.url)
+        .env("SPIKE_RATING", spike.rating_str())
+        .env("SPIKE_SELECTOR", spike.selector.as_deref().unwrap_or(""))
+        .env("SPIKE_REVIEWER", &spike.reviewer.name)
+        .env("SPIKE_COMMENTS", &spike.comments)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The hook may not read stdin; a closed pipe is not an error
+        let _ = writeln!(stdin, "{}", json);
+    }
+
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(Error::Io(io::Error::other(format!("command exited with {}", status))));
+    }
+    Ok(())
+}
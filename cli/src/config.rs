@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::error::{Error, Result};
+use crate::spike::{TrackerKind, Viewport, ViewportClass};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
@@ -12,6 +13,12 @@ pub struct Config {
     pub widget: WidgetConfig,
     #[serde(default)]
     pub remote: RemoteConfig,
+    #[serde(default)]
+    pub viewport: ViewportConfig,
+    #[serde(default)]
+    pub issues: IssuesConfig,
+    #[serde(default)]
+    pub serve: ServeConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -59,6 +66,81 @@ fn default_color() -> String {
     "#e74c3c".to_string()
 }
 
+/// Breakpoints for classifying spikes by viewport width
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ViewportConfig {
+    /// Widest viewport (px) that counts as mobile
+    #[serde(default = "default_mobile_max")]
+    pub mobile_max: u32,
+    /// Widest viewport (px) that counts as tablet; anything wider is desktop
+    #[serde(default = "default_tablet_max")]
+    pub tablet_max: u32,
+}
+
+impl Default for ViewportConfig {
+    fn default() -> Self {
+        Self {
+            mobile_max: default_mobile_max(),
+            tablet_max: default_tablet_max(),
+        }
+    }
+}
+
+fn default_mobile_max() -> u32 {
+    767
+}
+
+fn default_tablet_max() -> u32 {
+    1023
+}
+
+impl ViewportConfig {
+    /// Class of a viewport; `None` for a width of 0, which means the width
+    /// was not captured
+    pub fn classify(&self, viewport: &Viewport) -> Option<ViewportClass> {
+        if viewport.width == 0 {
+            None
+        } else if viewport.width <= self.mobile_max {
+            Some(ViewportClass::Mobile)
+        } else if viewport.width <= self.tablet_max {
+            Some(ViewportClass::Tablet)
+        } else {
+            Some(ViewportClass::Desktop)
+        }
+    }
+
+    /// Width range of a class, e.g. "≤767px" or "768–1023px"
+    pub fn range(&self, class: ViewportClass) -> String {
+        match class {
+            ViewportClass::Mobile => format!("≤{}px", self.mobile_max),
+            ViewportClass::Tablet => format!("{}–{}px", self.mobile_max + 1, self.tablet_max),
+            ViewportClass::Desktop => format!("≥{}px", self.tablet_max + 1),
+        }
+    }
+}
+
+/// Defaults for `spikes issues create`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct IssuesConfig {
+    /// Tracker to file issues on
+    pub tracker: Option<TrackerKind>,
+    /// Repository, team key or project key, depending on the tracker
+    pub project: Option<String>,
+    /// API base URL (Jira site URL, or a self-hosted GitHub/GitLab)
+    pub url: Option<String>,
+    /// Labels added to every issue besides the spike's tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+/// Where the site under review lives
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ServeConfig {
+    /// Directory of the site's HTML files, relative to the project (default: the project itself)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RemoteConfig {
     /// Remote endpoint URL
@@ -90,6 +172,17 @@ impl Config {
             ))
         })?;
 
+        let viewport = config.viewport;
+        if viewport.mobile_max >= viewport.tablet_max {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Invalid config.toml: viewport mobile_max ({}) must be less than tablet_max ({})",
+                    viewport.mobile_max, viewport.tablet_max
+                ),
+            )));
+        }
+
         Ok(config)
     }
 
@@ -134,6 +227,11 @@ impl Config {
         }
     }
 
+    /// Directory of the site's HTML files: `[serve] dir`, or the project directory
+    pub fn site_dir(&self) -> PathBuf {
+        PathBuf::from(self.serve.dir.as_deref().unwrap_or("."))
+    }
+
     /// Get effective project key (from config or current directory name)
     pub fn effective_project_key(&self) -> String {
         self.project.key.clone().unwrap_or_else(|| {
@@ -234,6 +332,11 @@ collect_email = false    # Ask reviewers for email (builds prospect list)
 # endpoint = \"https://your-worker.workers.dev\"
 # token = \"your-token-here\"
 # hosted = false  # Use spikes.sh managed backend instead of self-hosted
+
+[viewport]
+# Breakpoints for the mobile/tablet/desktop breakdown and --viewport filters
+mobile_max = 767   # Widest mobile viewport (px)
+tablet_max = 1023  # Widest tablet viewport (px); wider is desktop
 ";
 
 #[cfg(test)]
@@ -255,6 +358,22 @@ mod tests {
         assert!(!config.remote.hosted);
     }
 
+    #[test]
+    fn test_viewport_breakpoints() {
+        let config: Config = toml::from_str("[viewport]\nmobile_max = 599\n").unwrap();
+        assert_eq!(config.viewport.mobile_max, 599);
+        assert_eq!(config.viewport.tablet_max, 1023);
+
+        let classify = |width| config.viewport.classify(&Viewport { width, height: 800 });
+        assert_eq!(classify(0), None, "A width of 0 was not captured");
+        assert_eq!(classify(1), Some(ViewportClass::Mobile));
+        assert_eq!(classify(599), Some(ViewportClass::Mobile));
+        assert_eq!(classify(600), Some(ViewportClass::Tablet));
+        assert_eq!(classify(1023), Some(ViewportClass::Tablet));
+        assert_eq!(classify(1024), Some(ViewportClass::Desktop));
+        assert_eq!(config.viewport.range(ViewportClass::Tablet), "600–1023px");
+    }
+
     #[test]
     fn test_load_missing_config() {
         let temp_dir = TempDir::new().unwrap();
@@ -300,6 +419,18 @@ mod tests {
         assert!(config.remote.hosted);
     }
 
+    #[test]
+    fn test_load_rejects_inverted_breakpoints() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+
+        for breakpoints in ["mobile_max = 1200", "mobile_max = 800\ntablet_max = 800"] {
+            std::fs::write(&config_path, format!("[viewport]\n{}\n", breakpoints)).unwrap();
+            let err = Config::load_from(&config_path).unwrap_err().to_string();
+            assert!(err.contains("must be less than tablet_max"), "got: {}", err);
+        }
+    }
+
     #[test]
     fn test_load_invalid_toml() {
         let temp_dir = TempDir::new().unwrap();
@@ -73,6 +73,48 @@ pub enum Error {
     #[error("CSV error: {0}")]
     Csv(#[from] csv::Error),
 
+    #[error("Reviewer not found: {0}")]
+    ReviewerNotFound(String),
+
+    #[error("Round not found: {0}")]
+    RoundNotFound(String),
+
+    #[error("Round '{0}' is still open. Run `spikes round close` first.")]
+    RoundOpen(String),
+
+    #[error("No round is open. Start one with `spikes round start <name>`.")]
+    NoOpenRound,
+
+    #[error("Round '{0}' already exists")]
+    RoundExists(String),
+
+    #[error("Invalid filter: {0}")]
+    InvalidFilter(String),
+
+    #[error("Template not found: {0}. Pass a path or put it in .spikes/templates/ (see `spikes export --list-templates`).")]
+    TemplateNotFound(String),
+
+    #[error("Template error: {0}")]
+    Template(String),
+
+    #[error("Unknown field: {0}")]
+    UnknownField(String),
+
+    #[error("Import failed: {0}")]
+    InvalidImport(String),
+
+    #[error("{0} has a spikes:begin marker without a matching spikes:end, or the other way round. Fix the markers and run again.")]
+    ContextMarkers(String),
+
+    #[error("Agent context is out of date: {0}. Run `spikes context write`.")]
+    StaleContext(String),
+
+    #[error("No element spikes with a bounding box and viewport on '{0}'. Nothing written.")]
+    EmptyHeatmap(String),
+
+    #[error("Issue tracker error: {0}")]
+    Tracker(String),
+
     // HTTP/API errors with actionable messages
     #[error("Authentication failed. Run `spikes login` to refresh your token.")]
     AuthFailed,
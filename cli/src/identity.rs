@@ -0,0 +1,441 @@
+//! Reviewer identity: one person may leave spikes under several widget IDs
+//! (one per browser). Reviewers sharing an email are the same person, and
+//! `spikes reviewers merge` records further links in `.spikes/reviewers.toml`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::spike::{Reviewer, Spike};
+use crate::storage::load_spikes;
+
+const ALIASES_FILE: &str = ".spikes/reviewers.toml";
+
+/// Reviewer ID aliases, stored in `.spikes/reviewers.toml`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Aliases {
+    /// Reviewer ID -> the reviewer ID it is counted as
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl Aliases {
+    /// Load aliases from .spikes/reviewers.toml, or return an empty table
+    pub fn load() -> Result<Self> {
+        Self::load_from(Path::new(ALIASES_FILE))
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Invalid reviewers.toml: {}", e),
+            ))
+        })
+    }
+
+    /// Save aliases to .spikes/reviewers.toml
+    pub fn save(&self) -> Result<()> {
+        self.save_to(Path::new(ALIASES_FILE))
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Failed to serialize reviewers.toml: {}", e),
+            ))
+        })?;
+
+        fs::write(
+            path,
+            format!("# Reviewer aliases, written by `spikes reviewers merge`\n{}", content),
+        )?;
+        Ok(())
+    }
+
+    /// Follow alias links to the final reviewer ID
+    pub fn resolve<'a>(&'a self, id: &'a str) -> &'a str {
+        let mut current = id;
+        // Bounded so a hand-edited cycle cannot loop forever
+        for _ in 0..=self.aliases.len() {
+            match self.aliases.get(current) {
+                Some(next) if next != current => current = next,
+                _ => break,
+            }
+        }
+        current
+    }
+
+    /// Record that every ID in `from` is `into`, keeping chains flat
+    pub fn merge(&mut self, from: &[String], into: &str) {
+        for target in self.aliases.values_mut() {
+            if from.contains(target) {
+                *target = into.to_string();
+            }
+        }
+        for id in from {
+            if id != into {
+                self.aliases.insert(id.clone(), into.to_string());
+            }
+        }
+    }
+}
+
+/// One person, possibly seen under several reviewer IDs and names
+#[derive(Debug, Clone, Serialize)]
+pub struct Identity {
+    /// Canonical reviewer ID
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Every reviewer ID belonging to this person
+    pub ids: Vec<String>,
+    /// Every name this person has used
+    pub names: Vec<String>,
+    /// Number of spikes
+    pub count: usize,
+}
+
+impl Identity {
+    pub fn reviewer(&self) -> Reviewer {
+        Reviewer {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            email: self.email.clone(),
+        }
+    }
+}
+
+/// Canonical identities for every reviewer in a set of spikes
+#[derive(Debug, Clone)]
+pub struct Identities {
+    identities: Vec<Identity>,
+    by_id: HashMap<String, usize>,
+}
+
+#[derive(Default)]
+struct SeenReviewer<'a> {
+    count: usize,
+    latest: Option<(&'a str, &'a str)>,
+    email: Option<String>,
+    names: Vec<&'a str>,
+}
+
+fn normalize_email(email: &str) -> Option<String> {
+    let email = email.trim().to_lowercase();
+    email.contains('@').then_some(email)
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let (ra, rb) = (find_root(parent, a), find_root(parent, b));
+    if ra != rb {
+        parent[ra.max(rb)] = ra.min(rb);
+    }
+}
+
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+impl Identities {
+    pub fn build(spikes: &[Spike], aliases: &Aliases) -> Self {
+        // Reviewer IDs in first-seen order
+        let mut order: Vec<&str> = Vec::new();
+        let mut seen: HashMap<&str, SeenReviewer> = HashMap::new();
+        for spike in spikes {
+            let reviewer = &spike.reviewer;
+            let entry = seen.entry(reviewer.id.as_str()).or_insert_with(|| {
+                order.push(reviewer.id.as_str());
+                SeenReviewer::default()
+            });
+            entry.count += 1;
+            if entry.latest.is_none_or(|(ts, _)| spike.timestamp.as_str() >= ts) {
+                entry.latest = Some((spike.timestamp.as_str(), reviewer.name.as_str()));
+            }
+            if let Some(email) = reviewer.email.as_deref().and_then(normalize_email) {
+                entry.email = Some(email);
+            }
+            if !entry.names.contains(&reviewer.name.as_str()) {
+                entry.names.push(reviewer.name.as_str());
+            }
+        }
+        // Merge targets with no spikes of their own still anchor their group
+        for target in aliases.aliases.values() {
+            if !seen.contains_key(target.as_str()) {
+                order.push(target.as_str());
+                seen.insert(target.as_str(), SeenReviewer::default());
+            }
+        }
+
+        let index: HashMap<&str, usize> = order.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut parent: Vec<usize> = (0..order.len()).collect();
+
+        let mut by_email: HashMap<&str, usize> = HashMap::new();
+        for (i, id) in order.iter().enumerate() {
+            if let Some(email) = seen[id].email.as_deref() {
+                match by_email.get(email) {
+                    Some(&first) => union(&mut parent, first, i),
+                    None => {
+                        by_email.insert(email, i);
+                    }
+                }
+            }
+            let target = aliases.resolve(id);
+            if let Some(&t) = index.get(target) {
+                union(&mut parent, i, t);
+            }
+        }
+
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+        for i in 0..order.len() {
+            let root = find_root(&mut parent, i);
+            let g = *group_of_root.entry(root).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[g].push(i);
+        }
+
+        let mut identities: Vec<Identity> = groups
+            .iter()
+            .map(|members| {
+                let ids: Vec<&str> = members.iter().map(|&i| order[i]).collect();
+                // An explicit merge target wins; otherwise the busiest ID
+                let rep = ids
+                    .iter()
+                    .copied()
+                    .find(|id| aliases.resolve(id) == *id && aliases.aliases.values().any(|t| t == id))
+                    .unwrap_or_else(|| {
+                        ids.iter()
+                            .copied()
+                            .rev()
+                            .max_by_key(|id| seen[id].count)
+                            .unwrap_or(ids[0])
+                    });
+                let latest = seen[rep].latest.or_else(|| {
+                    ids.iter()
+                        .filter_map(|id| seen[id].latest)
+                        .max_by(|a, b| a.0.cmp(b.0))
+                });
+                let mut names: Vec<String> = Vec::new();
+                for id in &ids {
+                    for name in &seen[id].names {
+                        if !names.iter().any(|n| n == name) {
+                            names.push(name.to_string());
+                        }
+                    }
+                }
+                Identity {
+                    id: rep.to_string(),
+                    name: latest.map(|(_, name)| name.to_string()).unwrap_or_else(|| rep.to_string()),
+                    email: seen[rep]
+                        .email
+                        .clone()
+                        .or_else(|| ids.iter().find_map(|id| seen[id].email.clone())),
+                    ids: ids.iter().map(|id| id.to_string()).collect(),
+                    names,
+                    count: ids.iter().map(|id| seen[id].count).sum(),
+                }
+            })
+            .filter(|identity| identity.count > 0)
+            .collect();
+        identities.sort_by_key(|identity| std::cmp::Reverse(identity.count));
+
+        let by_id = identities
+            .iter()
+            .enumerate()
+            .flat_map(|(i, identity)| identity.ids.iter().map(move |id| (id.clone(), i)))
+            .collect();
+
+        Identities { identities, by_id }
+    }
+
+    /// All identities, busiest first
+    pub fn all(&self) -> &[Identity] {
+        &self.identities
+    }
+
+    /// The identity a reviewer ID belongs to
+    pub fn get(&self, reviewer_id: &str) -> Option<&Identity> {
+        self.by_id.get(reviewer_id).map(|&i| &self.identities[i])
+    }
+
+    /// Replace every spike's reviewer with its canonical identity
+    pub fn canonicalize(&self, spikes: &mut [Spike]) {
+        for spike in spikes {
+            if let Some(identity) = self.get(&spike.reviewer.id) {
+                spike.reviewer = identity.reviewer();
+            }
+        }
+    }
+
+    /// Find an identity by reviewer ID (or a prefix of 4+ characters), email or name
+    pub fn find(&self, query: &str) -> Result<&Identity> {
+        let matches: Vec<&Identity> = self
+            .identities
+            .iter()
+            .filter(|identity| {
+                identity
+                    .ids
+                    .iter()
+                    .any(|id| id == query || (query.len() >= 4 && id.starts_with(query)))
+                    || identity.email.as_deref().is_some_and(|e| e.eq_ignore_ascii_case(query))
+                    || identity.names.iter().any(|n| n.eq_ignore_ascii_case(query))
+            })
+            .collect();
+
+        match matches.len() {
+            0 => Err(Error::ReviewerNotFound(query.to_string())),
+            1 => Ok(matches[0]),
+            _ => {
+                let ids: Vec<String> = matches.iter().map(|m| format!("{} ({})", m.name, m.id)).collect();
+                Err(Error::ReviewerNotFound(format!(
+                    "'{}' matches several reviewers: {}. Use a reviewer ID instead",
+                    query,
+                    ids.join(", ")
+                )))
+            }
+        }
+    }
+}
+
+/// Load spikes with every reviewer replaced by their canonical identity
+pub fn load_canonical_spikes() -> Result<Vec<Spike>> {
+    let mut spikes = load_spikes()?;
+    let identities = Identities::build(&spikes, &Aliases::load()?);
+    identities.canonicalize(&mut spikes);
+    Ok(spikes)
+}
+
+/// Load the spikes as stored whose canonical form passes `keep`.
+///
+/// Filters see merged reviewers, but the output keeps each spike's reviewer
+/// as recorded.
+pub fn load_spikes_matching(keep: impl Fn(&Spike) -> bool) -> Result<Vec<Spike>> {
+    let spikes = load_spikes()?;
+    let mut canonical = spikes.clone();
+    Identities::build(&spikes, &Aliases::load()?).canonicalize(&mut canonical);
+    Ok(spikes
+        .into_iter()
+        .zip(canonical)
+        .filter(|(_, c)| keep(c))
+        .map(|(s, _)| s)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn spike(id: &str, reviewer_id: &str, name: &str, email: Option<&str>, timestamp: &str) -> Spike {
+        Spike {
+            url: "http://localhost/".to_string(),
+            reviewer: Reviewer {
+                id: reviewer_id.to_string(),
+                name: name.to_string(),
+                email: email.map(|e| e.to_string()),
+            },
+            timestamp: timestamp.to_string(),
+            ..Spike::test(id, "index.html", None)
+        }
+    }
+
+    #[test]
+    fn test_same_email_is_one_identity() {
+        let spikes = vec![
+            spike("s1", "laptop", "Sam", Some("sam@example.com"), "2024-01-01T00:00:00Z"),
+            spike("s2", "phone", "sam b", Some("Sam@Example.com "), "2024-01-02T00:00:00Z"),
+            spike("s3", "phone", "sam b", None, "2024-01-03T00:00:00Z"),
+            spike("s4", "other", "Alex", None, "2024-01-01T00:00:00Z"),
+        ];
+        let identities = Identities::build(&spikes, &Aliases::default());
+
+        assert_eq!(identities.all().len(), 2);
+        let sam = identities.get("laptop").unwrap();
+        assert_eq!(sam.id, "phone", "The busiest ID is canonical");
+        assert_eq!(sam.name, "sam b");
+        assert_eq!(sam.email.as_deref(), Some("sam@example.com"));
+        assert_eq!(sam.count, 3);
+        assert_eq!(sam.names, vec!["Sam", "sam b"]);
+    }
+
+    #[test]
+    fn test_aliases_merge_into_target() {
+        let spikes = vec![
+            spike("s1", "a", "Sam (laptop)", None, "2024-01-05T00:00:00Z"),
+            spike("s2", "a", "Sam (laptop)", None, "2024-01-05T00:00:00Z"),
+            spike("s3", "b", "Sam", None, "2024-01-01T00:00:00Z"),
+            spike("s4", "c", "Sammy", None, "2024-01-01T00:00:00Z"),
+        ];
+        let mut aliases = Aliases::default();
+        aliases.merge(&["c".to_string()], "a");
+        aliases.merge(&["a".to_string(), "c".to_string()], "b");
+        assert_eq!(aliases.aliases.get("c").map(String::as_str), Some("b"), "Chains are flattened");
+
+        let identities = Identities::build(&spikes, &aliases);
+        assert_eq!(identities.all().len(), 1);
+        let sam = &identities.all()[0];
+        assert_eq!(sam.id, "b");
+        assert_eq!(sam.name, "Sam");
+        assert_eq!(sam.count, 4);
+
+        let mut canonical = spikes.clone();
+        identities.canonicalize(&mut canonical);
+        assert!(canonical.iter().all(|s| s.reviewer.id == "b" && s.reviewer.name == "Sam"));
+    }
+
+    #[test]
+    fn test_resolve_survives_cycles() {
+        let mut aliases = Aliases::default();
+        aliases.aliases.insert("a".to_string(), "b".to_string());
+        aliases.aliases.insert("b".to_string(), "a".to_string());
+        let resolved = aliases.resolve("a");
+        assert!(resolved == "a" || resolved == "b");
+    }
+
+    #[test]
+    fn test_find_by_id_name_or_email() {
+        let spikes = vec![
+            spike("s1", "reviewer-1", "Sam", Some("sam@example.com"), "2024-01-01T00:00:00Z"),
+            spike("s2", "reviewer-2", "Alex", None, "2024-01-01T00:00:00Z"),
+            spike("s3", "reviewer-3", "alex", None, "2024-01-01T00:00:00Z"),
+        ];
+        let identities = Identities::build(&spikes, &Aliases::default());
+
+        assert_eq!(identities.find("SAM@example.com").unwrap().id, "reviewer-1");
+        assert_eq!(identities.find("sam").unwrap().id, "reviewer-1");
+        assert_eq!(identities.find("reviewer-2").unwrap().name, "Alex");
+        assert!(matches!(identities.find("alex"), Err(Error::ReviewerNotFound(msg)) if msg.contains("several")));
+        assert!(matches!(identities.find("nobody"), Err(Error::ReviewerNotFound(_))));
+    }
+
+    #[test]
+    fn test_aliases_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("reviewers.toml");
+        assert!(Aliases::load_from(&path).unwrap().aliases.is_empty());
+
+        let mut aliases = Aliases::default();
+        aliases.merge(&["phone".to_string()], "laptop");
+        aliases.save_to(&path).unwrap();
+
+        let loaded = Aliases::load_from(&path).unwrap();
+        assert_eq!(loaded.resolve("phone"), "laptop");
+    }
+}
@@ -2,16 +2,30 @@ mod auth;
 mod commands;
 mod config;
 mod error;
+mod identity;
 mod output;
+mod pages;
+mod rounds;
+mod selector;
 mod spike;
 mod storage;
+mod summary;
+mod trackers;
 
 use clap::{Parser, Subcommand};
+use commands::assign::AssignOptions;
+use commands::check::CheckOptions;
+use commands::clusters::ClustersOptions;
+use commands::context::ContextWriteOptions;
+use commands::dedupe::DedupeOptions;
 use commands::delete::DeleteOptions;
 use commands::deploy::DeployOptions;
-use commands::export::ExportFormat;
+use commands::export::{ExportFormat, ExportOptions};
+use commands::heatmap::{HeatmapFormat, HeatmapOptions};
+use commands::import::{ImportFormat, ImportOptions};
 use commands::inject::InjectOptions;
-use commands::list::ListOptions;
+use commands::issues::IssuesCreateOptions;
+use commands::list::{ListOptions, SpikeFilter};
 use commands::login::LoginOptions;
 use commands::pull::PullOptions;
 use commands::push::PushOptions;
@@ -19,8 +33,13 @@ use commands::resolve::ResolveOptions;
 use commands::serve::ServeOptions;
 use commands::share::ShareOptions;
 use commands::shares::SharesOptions;
+use commands::stats::StatsOptions;
+use commands::tag::TagOptions;
+use commands::trends::{Bucket, TrendsOptions};
 use commands::unshare::UnshareOptions;
 use commands::usage::UsageOptions;
+use commands::watch::WatchOptions;
+use spike::{TrackerKind, ViewportClass};
 
 #[derive(Parser)]
 #[command(name = "spikes")]
@@ -69,6 +88,49 @@ enum Commands {
         /// Show only unresolved spikes
         #[arg(long)]
         unresolved: bool,
+
+        /// Filter by review round
+        #[arg(long)]
+        round: Option<String>,
+
+        /// Filter by viewport class (mobile, tablet, desktop)
+        #[arg(long)]
+        viewport: Option<ViewportClass>,
+    },
+
+    /// Print new spikes as they arrive
+    Watch {
+        /// Output each new spike as a JSON line
+        #[arg(long)]
+        json: bool,
+
+        /// Filter by page name
+        #[arg(long)]
+        page: Option<String>,
+
+        /// Filter by reviewer name
+        #[arg(long)]
+        reviewer: Option<String>,
+
+        /// Filter by rating (love, like, meh, no)
+        #[arg(long)]
+        rating: Option<String>,
+
+        /// Show only unresolved spikes
+        #[arg(long)]
+        unresolved: bool,
+
+        /// Poll the configured remote instead of the local feedback file
+        #[arg(long)]
+        remote: bool,
+
+        /// Seconds between checks (default: 1 local, 10 remote)
+        #[arg(long)]
+        interval: Option<u64>,
+
+        /// Shell command to run per new spike (spike JSON on stdin, SPIKE_* env vars)
+        #[arg(long)]
+        exec: Option<String>,
     },
 
     /// Show a single spike by ID
@@ -76,6 +138,10 @@ enum Commands {
         /// Spike ID (or prefix)
         id: String,
 
+        /// Also summarize the element's (or page's) other feedback
+        #[arg(long)]
+        summary: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -83,9 +149,128 @@ enum Commands {
 
     /// Export all spikes
     Export {
-        /// Output format: json, csv, or jsonl
+        /// Output format: json, csv, jsonl, cursor-context, claude-context, clusters, brief, html, sarif, junit, or markdown
         #[arg(long, short, default_value = "json")]
         format: String,
+
+        /// Only export spikes from pages matching this name
+        #[arg(long)]
+        page: Option<String>,
+
+        /// Only export spikes from this reviewer
+        #[arg(long)]
+        reviewer: Option<String>,
+
+        /// Only export spikes with this rating (love, like, meh, no)
+        #[arg(long)]
+        rating: Option<String>,
+
+        /// Only export unresolved spikes
+        #[arg(long)]
+        unresolved: bool,
+
+        /// Render a Tera template instead: a path, or a name from .spikes/templates/ or the bundled ones
+        #[arg(long, conflicts_with = "format")]
+        template: Option<String>,
+
+        /// List templates in .spikes/templates/ and the bundled ones
+        #[arg(long)]
+        list_templates: bool,
+
+        /// Fit claude-context into about this many tokens, most important feedback first
+        #[arg(long, conflicts_with = "template")]
+        max_tokens: Option<usize>,
+
+        /// CSV columns to export, in order, e.g. id,rating,comments (a nested field
+        /// such as reviewer or bounding_box gives all of its columns; default: all)
+        #[arg(long, value_delimiter = ',', conflicts_with = "template")]
+        fields: Vec<String>,
+
+        /// Only export spikes from this review round
+        #[arg(long)]
+        round: Option<String>,
+
+        /// Only export spikes from this viewport class (mobile, tablet, desktop)
+        #[arg(long)]
+        viewport: Option<ViewportClass>,
+    },
+
+    /// Import spikes from CSV, JSON or JSONL, e.g. a spreadsheet or another feedback tool
+    Import {
+        /// File to import
+        file: std::path::PathBuf,
+
+        /// Input format (default: from the file extension)
+        #[arg(long, short, value_enum)]
+        format: Option<ImportFileFormat>,
+
+        /// Map a column or key to a spike field, e.g. --map "Feedback=comments" (repeatable)
+        #[arg(long, value_name = "SOURCE=FIELD")]
+        map: Vec<String>,
+
+        /// Project key for records without one (default: the configured key)
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Validate and count without saving
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Summarize ratings and resolution per page
+    Stats {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Filter by page name
+        #[arg(long)]
+        page: Option<String>,
+
+        /// Only count spikes from this review round
+        #[arg(long)]
+        round: Option<String>,
+    },
+
+    /// Show feedback counts and negative ratio over time
+    Trends {
+        /// Filter by page name
+        #[arg(long)]
+        page: Option<String>,
+
+        /// Group spikes by day or by week
+        #[arg(long, value_enum, default_value = "day")]
+        bucket: TrendBucket,
+
+        /// Draw an ASCII chart of the negative ratio instead of a table
+        #[arg(long, conflicts_with = "json")]
+        chart: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Manage review rounds (v1, v2, ...)
+    Round {
+        #[command(subcommand)]
+        action: RoundAction,
+    },
+
+    /// Keep open feedback in agent rule files (AGENTS.md, CLAUDE.md, Cursor rules)
+    Context {
+        #[command(subcommand)]
+        action: ContextAction,
+    },
+
+    /// File spikes as GitHub, GitLab, Linear or Jira issues and sync their state
+    Issues {
+        #[command(subcommand)]
+        action: IssuesAction,
     },
 
     /// Show elements with most feedback
@@ -93,13 +278,92 @@ enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Score how much reviewers agree on each element instead
+        #[arg(long)]
+        consensus: bool,
+    },
+
+    /// Render element feedback on a page as an SVG or PNG heatmap
+    Heatmap {
+        /// Page to render (matched like `list --page`)
+        #[arg(long)]
+        page: String,
+
+        /// Output file; .png writes a PNG, anything else an SVG
+        #[arg(long, short)]
+        out: std::path::PathBuf,
+
+        /// Reference viewport width (px) boxes are scaled to
+        #[arg(long, default_value_t = commands::heatmap::DEFAULT_WIDTH)]
+        width: u32,
+
+        /// Output format (default: from the --out extension)
+        #[arg(long, value_enum)]
+        format: Option<HeatmapImageFormat>,
     },
 
-    /// List all reviewers who left feedback
+    /// Check spike selectors against the current HTML files
+    Check {
+        /// Directory containing HTML files (default: [serve] dir in config.toml, or the current directory)
+        #[arg(long, short)]
+        dir: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Flag spikes whose element is gone as stale (clears the flag on the rest)
+        #[arg(long)]
+        flag: bool,
+
+        /// Resolve spikes whose element is gone
+        #[arg(long)]
+        resolve: bool,
+    },
+
+    /// Group element spikes by overlapping or nearby bounding boxes
+    Clusters {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Filter by page name
+        #[arg(long)]
+        page: Option<String>,
+
+        /// Max gap between boxes, as a fraction of viewport width (default: 0.02)
+        #[arg(long, default_value_t = commands::clusters::DEFAULT_THRESHOLD)]
+        threshold: f64,
+    },
+
+    /// Find near-duplicate feedback and link it to a canonical spike
+    Dedupe {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Minimum comment similarity from 0.0 to 1.0 (default: 0.6)
+        #[arg(long, default_value_t = commands::dedupe::DEFAULT_THRESHOLD)]
+        threshold: f64,
+
+        /// Link duplicates to their canonical spike instead of only previewing
+        #[arg(long)]
+        apply: bool,
+
+        /// Undo links: unlink this duplicate, or every duplicate of this canonical spike
+        #[arg(long, value_name = "ID", conflicts_with = "apply")]
+        unlink: Option<String>,
+    },
+
+    /// List reviewers who left feedback, one row per person
     Reviewers {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        #[command(subcommand)]
+        action: Option<ReviewersAction>,
     },
 
     /// Add widget script tag to HTML files
@@ -130,9 +394,9 @@ enum Commands {
         #[arg(long, short, default_value = "3847")]
         port: u16,
 
-        /// Directory to serve (default: current directory)
-        #[arg(long, short, default_value = ".")]
-        dir: String,
+        /// Directory to serve (default: [serve] dir in config.toml, or the current directory)
+        #[arg(long, short)]
+        dir: Option<String>,
 
         /// Enable review mode with spike markers on pages
         #[arg(long, short)]
@@ -281,10 +545,17 @@ enum Commands {
         json: bool,
     },
 
-    /// Delete a spike from local storage
+    /// Delete a spike, or every spike matching --where, from local storage
     Delete {
         /// Spike ID or prefix (minimum 4 characters)
-        id: String,
+        #[arg(required_unless_present = "filters", conflicts_with = "filters")]
+        id: Option<String>,
+
+        /// Select spikes by filter instead of ID, e.g. page=pricing.html, or page~=pricing
+        /// to match part of the value (repeatable; keys: page, reviewer, rating, type,
+        /// selector, tag, assignee, round, viewport, resolved, stale)
+        #[arg(long = "where", value_name = "KEY=VALUE")]
+        filters: Vec<String>,
 
         /// Skip confirmation prompt
         #[arg(long, short)]
@@ -295,15 +566,84 @@ enum Commands {
         json: bool,
     },
 
-    /// Mark a spike as resolved
+    /// Mark a spike, or every spike matching --where, as resolved
     Resolve {
         /// Spike ID or prefix (minimum 4 characters)
-        id: String,
+        #[arg(required_unless_present = "filters", conflicts_with = "filters")]
+        id: Option<String>,
+
+        /// Select spikes by filter instead of ID, e.g. page=pricing.html, or page~=pricing
+        /// to match part of the value (repeatable; keys: page, reviewer, rating, type,
+        /// selector, tag, assignee, round, viewport, resolved, stale)
+        #[arg(long = "where", value_name = "KEY=VALUE")]
+        filters: Vec<String>,
 
         /// Mark as unresolved instead
         #[arg(long)]
         unresolve: bool,
 
+        /// Skip confirmation prompt when using --where
+        #[arg(long, short)]
+        force: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Add or remove tags on a spike, or every spike matching --where
+    Tag {
+        /// Spike ID or prefix (minimum 4 characters)
+        #[arg(required_unless_present = "filters", conflicts_with = "filters")]
+        id: Option<String>,
+
+        /// Select spikes by filter instead of ID, e.g. page=pricing.html, or page~=pricing
+        /// to match part of the value (repeatable; keys: page, reviewer, rating, type,
+        /// selector, tag, assignee, round, viewport, resolved, stale)
+        #[arg(long = "where", value_name = "KEY=VALUE")]
+        filters: Vec<String>,
+
+        /// Tag to add (repeatable)
+        #[arg(long, required_unless_present = "remove")]
+        add: Vec<String>,
+
+        /// Tag to remove (repeatable)
+        #[arg(long)]
+        remove: Vec<String>,
+
+        /// Skip confirmation prompt when using --where
+        #[arg(long, short)]
+        force: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Assign a spike, or every spike matching --where, to someone
+    Assign {
+        /// Spike ID or prefix (minimum 4 characters)
+        #[arg(required_unless_present = "filters", conflicts_with = "filters")]
+        id: Option<String>,
+
+        /// Select spikes by filter instead of ID, e.g. page=pricing.html, or page~=pricing
+        /// to match part of the value (repeatable; keys: page, reviewer, rating, type,
+        /// selector, tag, assignee, round, viewport, resolved, stale)
+        #[arg(long = "where", value_name = "KEY=VALUE")]
+        filters: Vec<String>,
+
+        /// Assignee name
+        #[arg(long, required_unless_present = "clear", conflicts_with = "clear")]
+        to: Option<String>,
+
+        /// Remove the assignee instead
+        #[arg(long)]
+        clear: bool,
+
+        /// Skip confirmation prompt when using --where
+        #[arg(long, short)]
+        force: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -405,6 +745,106 @@ enum McpTransport {
     Http,
 }
 
+/// Image format for `spikes heatmap`
+#[derive(Clone, Debug, clap::ValueEnum)]
+enum HeatmapImageFormat {
+    Svg,
+    Png,
+}
+
+/// Input format for `spikes import`
+#[derive(Clone, Debug, clap::ValueEnum)]
+enum ImportFileFormat {
+    Csv,
+    Json,
+    Jsonl,
+}
+
+/// Tracker for `spikes issues create`
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum IssueTracker {
+    Github,
+    Gitlab,
+    Linear,
+    Jira,
+}
+
+/// Time bucket for `spikes trends`
+#[derive(Clone, Debug, clap::ValueEnum)]
+enum TrendBucket {
+    Day,
+    Week,
+}
+
+#[derive(Subcommand)]
+enum ContextAction {
+    /// Update the managed section in each file with the current blocking feedback
+    Write {
+        /// File to update instead of AGENTS.md, CLAUDE.md and .cursor/rules/spikes.mdc (repeatable)
+        #[arg(long)]
+        file: Vec<std::path::PathBuf>,
+
+        /// Fail if any file is missing or out of date, without writing (for CI)
+        #[arg(long)]
+        check: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum IssuesAction {
+    /// File an issue per spike and store its URL on the spike; spikes already filed are skipped
+    Create {
+        /// Spike IDs or prefixes (default: every unresolved meh/no spike)
+        #[arg(conflicts_with = "filters")]
+        ids: Vec<String>,
+
+        /// Select spikes by filter instead of ID, e.g. page=pricing.html, or page~=pricing
+        /// to match part of the value (repeatable; keys: page, reviewer, rating, type,
+        /// selector, tag, assignee, round, viewport, resolved, stale)
+        #[arg(long = "where", value_name = "KEY=VALUE")]
+        filters: Vec<String>,
+
+        /// Tracker to file on (default: tracker under [issues] in config.toml)
+        #[arg(long, value_enum)]
+        tracker: Option<IssueTracker>,
+
+        /// owner/repo (GitHub), group/project (GitLab), team key (Linear) or project key (Jira)
+        #[arg(long)]
+        project: Option<String>,
+
+        /// API base URL: your Jira site, or a self-hosted GitHub/GitLab
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Extra label for every issue, besides the spike's tags (repeatable)
+        #[arg(long = "label")]
+        labels: Vec<String>,
+
+        /// Body template name or path (default: .spikes/templates/issue-body.tera, else bundled)
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Show the issues that would be filed without filing them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Fetch the state of linked issues; closed issues resolve their spikes
+    Sync {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum AuthAction {
     /// Create a new API key for agent authentication
@@ -436,6 +876,62 @@ enum AuthAction {
     },
 }
 
+#[derive(Subcommand)]
+enum RoundAction {
+    /// Open a round; new spikes from serve and pull are stamped with it
+    Start {
+        /// Round name, e.g. v1
+        name: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Close the open round
+    Close {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List rounds with their spike counts
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show rating changes per page and per element between two rounds
+    Compare {
+        /// Earlier round
+        before: String,
+
+        /// Later round
+        after: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReviewersAction {
+    /// Count reviewer <FROM> as <INTO> from now on
+    Merge {
+        /// Reviewer to merge away: ID, name or email
+        from: String,
+
+        /// Reviewer to keep: ID, name or email
+        into: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum RemoteAction {
     /// Add or update remote endpoint
@@ -476,15 +972,56 @@ fn main() {
             reviewer,
             rating,
             unresolved,
+            round,
+            viewport,
         }) => commands::list::run(ListOptions {
+            json,
+            filter: SpikeFilter {
+                page,
+                reviewer,
+                rating,
+                unresolved,
+                round,
+                viewport,
+                ..Default::default()
+            },
+        }),
+        Some(Commands::Watch {
             json,
             page,
             reviewer,
             rating,
             unresolved,
+            remote,
+            interval,
+            exec,
+        }) => commands::watch::run(WatchOptions {
+            json,
+            filter: SpikeFilter {
+                page,
+                reviewer,
+                rating,
+                unresolved,
+                ..Default::default()
+            },
+            remote,
+            interval,
+            exec,
         }),
-        Some(Commands::Show { id, json }) => commands::show::run(&id, json),
-        Some(Commands::Export { format }) => {
+        Some(Commands::Show { id, summary, json }) => commands::show::run(&id, summary, json),
+        Some(Commands::Export {
+            format,
+            page,
+            reviewer,
+            rating,
+            unresolved,
+            template,
+            list_templates,
+            max_tokens,
+            fields,
+            round,
+            viewport,
+        }) => {
             let fmt = match format.parse::<ExportFormat>() {
                 Ok(f) => f,
                 Err(e) => {
@@ -492,10 +1029,171 @@ fn main() {
                     std::process::exit(1);
                 }
             };
-            commands::export::run(fmt)
+            if max_tokens.is_some() && fmt != ExportFormat::ClaudeContext {
+                eprintln!("Error: --max-tokens only applies to --format claude-context");
+                std::process::exit(1);
+            }
+            if !fields.is_empty() && fmt != ExportFormat::Csv {
+                eprintln!("Error: --fields only applies to --format csv");
+                std::process::exit(1);
+            }
+            commands::export::run(ExportOptions {
+                format: fmt,
+                filter: SpikeFilter {
+                    page,
+                    reviewer,
+                    rating,
+                    unresolved,
+                    round,
+                    viewport,
+                    ..Default::default()
+                },
+                template,
+                list_templates,
+                max_tokens,
+                fields,
+            })
         }
-        Some(Commands::Hotspots { json }) => commands::hotspots::run(json),
-        Some(Commands::Reviewers { json }) => commands::reviewers::run(json),
+        Some(Commands::Hotspots { json, consensus }) => commands::hotspots::run(json, consensus),
+        Some(Commands::Heatmap {
+            page,
+            out,
+            width,
+            format,
+        }) => commands::heatmap::run(HeatmapOptions {
+            page,
+            out,
+            width,
+            format: format.map(|f| match f {
+                HeatmapImageFormat::Svg => HeatmapFormat::Svg,
+                HeatmapImageFormat::Png => HeatmapFormat::Png,
+            }),
+        }),
+        Some(Commands::Import {
+            file,
+            format,
+            map,
+            project,
+            dry_run,
+            json,
+        }) => commands::import::run(ImportOptions {
+            file,
+            format: format.map(|f| match f {
+                ImportFileFormat::Csv => ImportFormat::Csv,
+                ImportFileFormat::Json => ImportFormat::Json,
+                ImportFileFormat::Jsonl => ImportFormat::Jsonl,
+            }),
+            map,
+            project,
+            dry_run,
+            json,
+        }),
+        Some(Commands::Check {
+            dir,
+            json,
+            flag,
+            resolve,
+        }) => commands::check::run(CheckOptions {
+            dir,
+            json,
+            flag,
+            resolve,
+        }),
+        Some(Commands::Clusters {
+            json,
+            page,
+            threshold,
+        }) => commands::clusters::run(ClustersOptions {
+            json,
+            page,
+            threshold,
+        }),
+        Some(Commands::Dedupe {
+            json,
+            unlink: Some(id),
+            ..
+        }) => commands::dedupe::unlink(&id, json),
+        Some(Commands::Dedupe {
+            json,
+            threshold,
+            apply,
+            unlink: None,
+        }) => commands::dedupe::run(DedupeOptions {
+            json,
+            threshold,
+            apply,
+        }),
+        Some(Commands::Stats { json, page, round }) => commands::stats::run(StatsOptions {
+            json,
+            filter: SpikeFilter {
+                page,
+                round,
+                ..Default::default()
+            },
+        }),
+        Some(Commands::Trends {
+            page,
+            bucket,
+            chart,
+            json,
+        }) => commands::trends::run(TrendsOptions {
+            filter: SpikeFilter {
+                page,
+                ..Default::default()
+            },
+            bucket: match bucket {
+                TrendBucket::Day => Bucket::Day,
+                TrendBucket::Week => Bucket::Week,
+            },
+            chart,
+            json,
+        }),
+        Some(Commands::Round { action }) => match action {
+            RoundAction::Start { name, json } => commands::round::start(&name, json),
+            RoundAction::Close { json } => commands::round::close(json),
+            RoundAction::List { json } => commands::round::list(json),
+            RoundAction::Compare { before, after, json } => commands::round::compare(&before, &after, json),
+        },
+        Some(Commands::Context { action }) => match action {
+            ContextAction::Write { file, check, json } => commands::context::write(ContextWriteOptions {
+                files: file,
+                check,
+                json,
+            }),
+        },
+        Some(Commands::Issues { action }) => match action {
+            IssuesAction::Create {
+                ids,
+                filters,
+                tracker,
+                project,
+                url,
+                labels,
+                template,
+                dry_run,
+                json,
+            } => commands::issues::create(IssuesCreateOptions {
+                ids,
+                filters,
+                tracker: tracker.map(|t| match t {
+                    IssueTracker::Github => TrackerKind::Github,
+                    IssueTracker::Gitlab => TrackerKind::Gitlab,
+                    IssueTracker::Linear => TrackerKind::Linear,
+                    IssueTracker::Jira => TrackerKind::Jira,
+                }),
+                project,
+                url,
+                labels,
+                template,
+                dry_run,
+                json,
+            }),
+            IssuesAction::Sync { json } => commands::issues::sync(json),
+        },
+        Some(Commands::Reviewers { json, action }) => match action {
+            None => commands::reviewers::run(json),
+            Some(ReviewersAction::Merge { from, into, json }) => commands::reviewers::merge(&from, &into, json),
+        },
         Some(Commands::Inject {
             directory,
             remove,
@@ -564,11 +1262,17 @@ fn main() {
         Some(Commands::Unshare { slug, force, json }) => {
             commands::unshare::run(UnshareOptions { slug, force, json })
         }
-        Some(Commands::Delete { id, force, json }) => {
-            commands::delete::run(DeleteOptions { id, force, json })
+        Some(Commands::Delete { id, filters, force, json }) => {
+            commands::delete::run(DeleteOptions { id, filters, force, json })
+        }
+        Some(Commands::Resolve { id, filters, unresolve, force, json }) => {
+            commands::resolve::run(ResolveOptions { id, filters, unresolve, force, json })
+        }
+        Some(Commands::Tag { id, filters, add, remove, force, json }) => {
+            commands::tag::run(TagOptions { id, filters, add, remove, force, json })
         }
-        Some(Commands::Resolve { id, unresolve, json }) => {
-            commands::resolve::run(ResolveOptions { id, unresolve, json })
+        Some(Commands::Assign { id, filters, to, clear: _, force, json }) => {
+            commands::assign::run(AssignOptions { id, filters, to, force, json })
         }
         Some(Commands::Billing { json }) => commands::billing::run(json),
         Some(Commands::Upgrade { json }) => commands::upgrade::run(json),
@@ -1,8 +1,24 @@
+use std::collections::HashSet;
+
 use comfy_table::{presets::UTF8_FULL_CONDENSED, Cell, Color, ContentArrangement, Table};
 
+use crate::commands::check::{CheckResult, CheckStatus};
+use crate::commands::clusters::Cluster;
+use crate::commands::hotspots::Consensus;
+use crate::commands::round::RoundDelta;
+use crate::commands::stats::{format_score, PageStats, ViewportStats};
+use crate::commands::trends::{format_ratio, TrendPoint};
+use crate::identity::Identity;
+use crate::rounds::Round;
 use crate::spike::{Rating, Spike};
+use crate::summary::Summary;
 
 pub fn print_spikes_table(spikes: &[Spike]) {
+    print_spikes_table_marked(spikes, &HashSet::new());
+}
+
+/// Spikes table with the pages of `outdated` spikes marked as changed
+pub fn print_spikes_table_marked(spikes: &[Spike], outdated: &HashSet<String>) {
     if spikes.is_empty() {
         println!("No spikes found.");
         return;
@@ -29,16 +45,22 @@ pub fn print_spikes_table(spikes: &[Spike]) {
             Cell::new("")
         };
 
-        let comments = if spike.comments.len() > 40 {
-            format!("{}...", &spike.comments[..37])
+        let page_cell = if outdated.contains(&spike.id) {
+            Cell::new(format!("{} (outdated)", spike.page)).fg(Color::Yellow)
+        } else {
+            Cell::new(&spike.page)
+        };
+
+        let comments = if spike.comments.chars().count() > 40 {
+            format!("{}...", spike.comments.chars().take(37).collect::<String>())
         } else {
             spike.comments.clone()
         };
 
         table.add_row(vec![
-            Cell::new(&spike.id[..8.min(spike.id.len())]),
+            Cell::new(spike.id.chars().take(8).collect::<String>()),
             Cell::new(spike.type_str()),
-            Cell::new(&spike.page),
+            page_cell,
             Cell::new(&spike.reviewer.name),
             rating_cell,
             resolved_cell,
@@ -56,6 +78,9 @@ pub fn print_spike_detail(spike: &Spike) {
     println!("Page:       {}", spike.page);
     println!("URL:        {}", spike.url);
     println!("Reviewer:   {} ({})", spike.reviewer.name, spike.reviewer.id);
+    if let Some(ref email) = spike.reviewer.email {
+        println!("Email:      {}", email);
+    }
     println!("Rating:     {}", spike.rating_str());
     println!("Timestamp:  {}", spike.timestamp);
     if spike.is_resolved() {
@@ -63,6 +88,21 @@ pub fn print_spike_detail(spike: &Spike) {
     } else {
         println!("Resolved:   No");
     }
+    if let Some(ref canonical) = spike.duplicate_of {
+        println!("Duplicate:  of {}", canonical);
+    }
+    if let Some(ref round) = spike.round {
+        println!("Round:      {}", round);
+    }
+    if let Some(ref assignee) = spike.assignee {
+        println!("Assignee:   {}", assignee);
+    }
+    if let Some(ref tags) = spike.tags {
+        println!("Tags:       {}", tags.join(", "));
+    }
+    if let Some(ref issue) = spike.issue {
+        println!("Issue:      {} ({}, {})", issue.url, issue.tracker, issue.state);
+    }
     if let Some(ref vp) = spike.viewport {
         println!("Viewport:   {}x{}", vp.width, vp.height);
     }
@@ -70,6 +110,12 @@ pub fn print_spike_detail(spike: &Spike) {
     if let Some(selector) = &spike.selector {
         println!("Selector:   {}", selector);
     }
+    if spike.stale == Some(true) {
+        println!("Stale:      element not found in current HTML");
+    }
+    if let Some(xpath) = &spike.xpath {
+        println!("XPath:      {}", xpath);
+    }
     if let Some(text) = &spike.element_text {
         println!("Element:    {}", text);
     }
@@ -85,6 +131,29 @@ pub fn print_spike_detail(spike: &Spike) {
     println!("  {}", spike.comments);
 }
 
+pub fn print_summary(target: &str, summary: &Summary) {
+    println!(
+        "Summary of {} ({} spike{})",
+        target,
+        summary.spikes,
+        if summary.spikes == 1 { "" } else { "s" }
+    );
+    println!("  Ratings:  {}", summary.ratings_label());
+    if !summary.keywords.is_empty() {
+        println!("  Keywords: {}", summary.keywords.join(", "));
+    }
+    for (i, quote) in summary.quotes.iter().enumerate() {
+        let rating = quote.rating.as_ref().map(|r| r.to_string()).unwrap_or_else(|| "unrated".to_string());
+        println!(
+            "  {}\"{}\" — {} ({})",
+            if i == 0 { "Quotes:   " } else { "          " },
+            quote.text,
+            quote.reviewer,
+            rating
+        );
+    }
+}
+
 pub fn print_json<T: serde::Serialize>(data: &T) {
     println!(
         "{}",
@@ -114,7 +183,39 @@ pub fn print_hotspots_table(hotspots: &[(String, usize)]) {
     println!("{table}");
 }
 
-pub fn print_reviewers_table(reviewers: &[(String, usize)]) {
+pub fn print_consensus_table(scores: &[Consensus]) {
+    if scores.is_empty() {
+        println!("No elements rated by two or more reviewers.");
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Selector", "Reviewers", "Average", "Spread", "Agreement", "Split"]);
+
+    for score in scores {
+        let split = format!("{} love/like · {} meh/no", score.positive, score.negative);
+        let split_cell = if score.polarizing {
+            Cell::new(format!("{} (polarizing)", split)).fg(Color::Yellow)
+        } else {
+            Cell::new(split)
+        };
+        table.add_row(vec![
+            Cell::new(&score.selector),
+            Cell::new(score.reviewers),
+            Cell::new(format_score(Some(score.average))),
+            Cell::new(format!("{:.2}", score.spread)),
+            Cell::new(format!("{:.0}%", score.agreement * 100.0)),
+            split_cell,
+        ]);
+    }
+
+    println!("{table}");
+}
+
+pub fn print_reviewers_table(reviewers: &[Identity]) {
     if reviewers.is_empty() {
         println!("No reviewers found.");
         return;
@@ -124,12 +225,221 @@ pub fn print_reviewers_table(reviewers: &[(String, usize)]) {
     table
         .load_preset(UTF8_FULL_CONDENSED)
         .set_content_arrangement(ContentArrangement::Dynamic)
-        .set_header(vec!["Reviewer", "Spikes"]);
+        .set_header(vec!["Reviewer", "Email", "Spikes", "Also seen as"]);
 
-    for (name, count) in reviewers {
+    for reviewer in reviewers {
+        let others: Vec<&str> = reviewer
+            .names
+            .iter()
+            .map(String::as_str)
+            .filter(|n| *n != reviewer.name)
+            .collect();
         table.add_row(vec![
-            Cell::new(name),
-            Cell::new(format!("{} spikes", count)),
+            Cell::new(&reviewer.name),
+            Cell::new(reviewer.email.as_deref().unwrap_or("")),
+            Cell::new(format!("{} spikes", reviewer.count)),
+            Cell::new(others.join(", ")),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+pub fn print_clusters_table(clusters: &[Cluster]) {
+    if clusters.is_empty() {
+        println!("No element spikes with bounding boxes found.");
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Page", "Selectors", "Spikes", "Rating", "Comments"]);
+
+    for cluster in clusters {
+        let rating_cell = match &cluster.rating {
+            Some(Rating::Love) => Cell::new("love").fg(Color::Green),
+            Some(Rating::Like) => Cell::new("like").fg(Color::Blue),
+            Some(Rating::Meh) => Cell::new("meh").fg(Color::Yellow),
+            Some(Rating::No) => Cell::new("no").fg(Color::Red),
+            None => Cell::new("-"),
+        };
+
+        let comments = cluster.comments.join(" | ");
+        let comments = if comments.chars().count() > 60 {
+            format!("{}...", comments.chars().take(57).collect::<String>())
+        } else {
+            comments
+        };
+
+        table.add_row(vec![
+            Cell::new(&cluster.page),
+            Cell::new(cluster.selectors.join("\n")),
+            Cell::new(cluster.count),
+            rating_cell,
+            Cell::new(comments),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+pub fn print_page_stats_table(pages: &[PageStats]) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Page", "Spikes", "Unresolved", "Average", "Meh/No"]);
+
+    for page in pages {
+        table.add_row(vec![
+            Cell::new(&page.page),
+            Cell::new(page.total),
+            Cell::new(page.unresolved),
+            Cell::new(format_score(page.average)),
+            Cell::new(page.negative),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+pub fn print_viewport_stats_table(viewports: &[ViewportStats]) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Viewport", "Width", "Spikes", "Average", "Meh/No"]);
+
+    for viewport in viewports {
+        table.add_row(vec![
+            Cell::new(&viewport.viewport),
+            Cell::new(viewport.range.as_deref().unwrap_or("-")),
+            Cell::new(viewport.total),
+            Cell::new(format_score(viewport.average)),
+            Cell::new(viewport.negative),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+pub fn print_trends_table(points: &[TrendPoint]) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Bucket", "Spikes", "Rated", "Meh/No", "Negative"]);
+
+    for point in points {
+        table.add_row(vec![
+            Cell::new(&point.bucket),
+            Cell::new(point.total),
+            Cell::new(point.rated),
+            Cell::new(point.negative),
+            Cell::new(format_ratio(point.negative_ratio)),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+pub fn print_rounds_table(rounds: &[Round], counts: &[usize]) {
+    if rounds.is_empty() {
+        println!("No rounds yet. Start one with `spikes round start <name>`.");
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["Round", "Started", "Closed", "Spikes"]);
+
+    for (round, count) in rounds.iter().zip(counts) {
+        let closed_cell = match round.closed_at {
+            Some(ref closed) => Cell::new(closed),
+            None => Cell::new("open").fg(Color::Green),
+        };
+        table.add_row(vec![
+            Cell::new(&round.name),
+            Cell::new(&round.started_at),
+            closed_cell,
+            Cell::new(count),
+        ]);
+    }
+
+    println!("{table}");
+}
+
+pub fn print_round_deltas_table(deltas: &[RoundDelta], before: &str, after: &str) {
+    let with_selector = deltas.iter().any(|d| d.selector.is_some());
+    let mut header = vec!["Page".to_string()];
+    if with_selector {
+        header.push("Element".to_string());
+    }
+    header.extend([before.to_string(), after.to_string(), "Change".to_string()]);
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(header);
+
+    let score_cell = |score: Option<f64>, count: usize| match score {
+        Some(score) => Cell::new(format!("{:.2} ({})", score, count)),
+        None => Cell::new(format!("- ({})", count)),
+    };
+
+    for delta in deltas {
+        let change_cell = match delta.delta {
+            Some(d) if d > 0.0 => Cell::new(format!("+{:.2}", d)).fg(Color::Green),
+            Some(d) if d < 0.0 => Cell::new(format!("{:.2}", d)).fg(Color::Red),
+            Some(_) => Cell::new("0.00"),
+            None => Cell::new("-"),
+        };
+        let mut row = vec![Cell::new(&delta.page)];
+        if with_selector {
+            row.push(Cell::new(delta.selector.as_deref().unwrap_or("")));
+        }
+        row.extend([
+            score_cell(delta.before, delta.before_count),
+            score_cell(delta.after, delta.after_count),
+            change_cell,
+        ]);
+        table.add_row(row);
+    }
+
+    println!("{table}");
+}
+
+pub fn print_check_table(results: &[CheckResult]) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL_CONDENSED)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec!["ID", "File", "Selector", "Status", "Now at"]);
+
+    for result in results {
+        let status_cell = match result.status {
+            CheckStatus::Live => Cell::new("live").fg(Color::Green),
+            CheckStatus::Moved => Cell::new("moved").fg(Color::Yellow),
+            CheckStatus::Gone => Cell::new("gone").fg(Color::Red),
+            CheckStatus::Unknown => Cell::new("unknown"),
+        };
+
+        let now_at = match (&result.new_selector, result.found_by) {
+            (Some(selector), Some(by)) => format!("{} (by {})", selector, by.as_str()),
+            _ => "-".to_string(),
+        };
+
+        table.add_row(vec![
+            Cell::new(result.id.chars().take(8).collect::<String>()),
+            Cell::new(result.file.as_deref().unwrap_or(&result.page)),
+            Cell::new(result.selector.as_deref().unwrap_or("-")),
+            status_cell,
+            Cell::new(now_at),
         ]);
     }
 
@@ -0,0 +1,700 @@
+//! Resolving spikes against the project's current HTML files.
+//!
+//! A spike names its page by `url` (and `page`, which the widget fills with
+//! the document title when there is one). This module maps spikes back to
+//! the HTML file they were captured on and finds the element they point at,
+//! falling back to the xpath and element text when the selector no longer
+//! matches.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::error::{Error, Result};
+use crate::spike::Spike;
+
+/// HTML files under `dir`: the same set `spikes inject` walks
+pub fn html_files(dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            let ext = p.extension().and_then(|e| e.to_str()).unwrap_or("");
+            ext == "html" || ext == "htm"
+        })
+        .collect()
+}
+
+/// A parsed HTML file of the site
+pub struct SitePage {
+    /// Path relative to the site root, with `/` separators
+    pub path: String,
+    pub source: String,
+    pub document: Html,
+    title: Option<String>,
+}
+
+/// All HTML files under a directory, parsed
+pub struct Site {
+    pub pages: Vec<SitePage>,
+}
+
+/// How an element was found when its selector no longer matches
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FoundBy {
+    Xpath,
+    Text,
+}
+
+/// Where a spike's element is in the current HTML
+pub enum Location<'a> {
+    /// The selector still matches
+    Live(ElementRef<'a>),
+    /// The selector no longer matches, but the element was re-found
+    Moved(ElementRef<'a>, FoundBy),
+    /// The element could not be found
+    Gone,
+}
+
+impl<'a> Location<'a> {
+    /// The located element, if any
+    pub fn element(&self) -> Option<ElementRef<'a>> {
+        match self {
+            Location::Live(element) | Location::Moved(element, _) => Some(*element),
+            Location::Gone => None,
+        }
+    }
+}
+
+/// Position of an element's start tag in its HTML file (1-based)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+impl FoundBy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FoundBy::Xpath => "xpath",
+            FoundBy::Text => "text",
+        }
+    }
+}
+
+impl Site {
+    pub fn load(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            return Err(Error::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Directory not found: {}", dir.display()),
+            )));
+        }
+
+        let mut pages = Vec::new();
+        for path in html_files(dir) {
+            let Ok(source) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let rel = path.strip_prefix(dir).unwrap_or(&path);
+            let rel = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let document = Html::parse_document(&source);
+            let title = Selector::parse("title")
+                .ok()
+                .and_then(|s| document.select(&s).next().map(|t| normalize_text(&t.text().collect::<String>())))
+                .filter(|t| !t.is_empty());
+            pages.push(SitePage {
+                path: rel,
+                source,
+                document,
+                title,
+            });
+        }
+        pages.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Self { pages })
+    }
+
+    /// Find the file a spike was captured on.
+    ///
+    /// Tries the URL path, then the `page` field as a path, then the `page`
+    /// field as a document title.
+    pub fn resolve(&self, spike: &Spike) -> Option<&SitePage> {
+        url_path(&spike.url)
+            .and_then(|p| self.find_path(&p))
+            .or_else(|| self.find_path(&percent_decode(&spike.page)))
+            .or_else(|| {
+                let page = normalize_text(&spike.page);
+                self.pages.iter().find(|p| p.title.as_deref() == Some(page.as_str()))
+            })
+    }
+
+    /// Source location of a spike's element, if its page and element are found
+    pub fn source_location(&self, spike: &Spike) -> Option<SourceLocation> {
+        self.resolve(spike)?.source_location(spike)
+    }
+
+    fn find_path(&self, path: &str) -> Option<&SitePage> {
+        let candidates = candidates(path);
+        for candidate in &candidates {
+            if let Some(page) = self.pages.iter().find(|p| &p.path == candidate) {
+                return Some(page);
+            }
+        }
+        // Served under a prefix (e.g. a share URL): longest file path suffix
+        self.pages
+            .iter()
+            .filter(|p| candidates[0].ends_with(&format!("/{}", p.path)))
+            .max_by_key(|p| p.path.len())
+    }
+}
+
+impl SitePage {
+    /// Locate a spike's element: by selector, then xpath, then element text
+    pub fn locate(&self, spike: &Spike) -> Location<'_> {
+        if let Some(selector) = spike.selector.as_deref().and_then(|s| Selector::parse(s).ok()) {
+            if let Some(element) = self.document.select(&selector).next() {
+                return Location::Live(element);
+            }
+        }
+
+        let text = spike
+            .element_text
+            .as_deref()
+            .map(normalize_text)
+            .filter(|t| !t.is_empty() && t != "<no text>");
+
+        if let Some(element) = spike.xpath.as_deref().and_then(|x| self.find_xpath(x)) {
+            if text.as_deref().is_none_or(|t| text_matches(element, t)) {
+                return Location::Moved(element, FoundBy::Xpath);
+            }
+        }
+
+        if let Some(text) = text {
+            // Deepest element whose text matches, so ancestors don't win
+            let found = self
+                .document
+                .root_element()
+                .descendent_elements()
+                .filter(|e| text_matches(*e, &text))
+                .find(|e| !e.child_elements().any(|c| text_matches(c, &text)));
+            if let Some(element) = found {
+                return Location::Moved(element, FoundBy::Text);
+            }
+        }
+
+        Location::Gone
+    }
+
+    /// Byte offset of an element's start tag in the source.
+    ///
+    /// Matches the element to the n-th `<tag` in the source, where n is its
+    /// index among same-named elements in the parsed tree. Returns None when
+    /// the parser implied the element (e.g. a missing `<tbody>`).
+    pub fn source_offset(&self, element: ElementRef) -> Option<usize> {
+        let name = element.value().name();
+        let index = self
+            .document
+            .root_element()
+            .descendent_elements()
+            .filter(|e| e.value().name() == name)
+            .position(|e| e.id() == element.id())?;
+        start_tags(&self.source, name).into_iter().nth(index)
+    }
+
+    /// 1-based line and column (in characters) of a byte offset
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let before = &self.source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        (line, before[line_start..].chars().count() + 1)
+    }
+
+    /// Source location of a spike's element, found by [`SitePage::locate`]
+    pub fn source_location(&self, spike: &Spike) -> Option<SourceLocation> {
+        let offset = self.source_offset(self.locate(spike).element()?)?;
+        let (line, column) = self.line_col(offset);
+        Some(SourceLocation {
+            file: self.path.clone(),
+            line,
+            column,
+        })
+    }
+
+    /// Lines `line - around ..= line + around` of the source, numbered
+    pub fn snippet(&self, line: usize, around: usize) -> String {
+        let first = line.saturating_sub(around).max(1);
+        let width = (line + around).to_string().len();
+        self.source
+            .lines()
+            .enumerate()
+            .skip(first - 1)
+            .take(line + around + 1 - first)
+            .map(|(i, text)| {
+                let marker = if i + 1 == line { '>' } else { ' ' };
+                format!("{} {:>width$} | {}\n", marker, i + 1, text, width = width)
+            })
+            .collect()
+    }
+
+    /// Evaluate an absolute xpath of `tag[n]` steps, as the widget records it
+    pub fn find_xpath(&self, xpath: &str) -> Option<ElementRef<'_>> {
+        let mut steps = xpath.trim().trim_start_matches('/').split('/').map(parse_step);
+        let (tag, index) = steps.next()??;
+        let mut current = self.document.root_element();
+        if current.value().name() != tag || index != 1 {
+            return None;
+        }
+        for step in steps {
+            let (tag, index) = step?;
+            current = current
+                .child_elements()
+                .filter(|c| c.value().name() == tag)
+                .nth(index.checked_sub(1)?)?;
+        }
+        Some(current)
+    }
+}
+
+/// Stable hash of a page's contents (64-bit FNV-1a, hex).
+///
+/// Only used to notice that a page changed, so it need not be
+/// cryptographic, but it must not change between releases.
+pub fn content_hash(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in bytes {
+        hash ^= u64::from(b);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Files a URL path may be served from, relative to the site root
+fn candidates(path: &str) -> Vec<String> {
+    let path = path.trim_start_matches('/');
+    if path.is_empty() || path.ends_with('/') {
+        vec![format!("{}index.html", path)]
+    } else {
+        vec![
+            path.to_string(),
+            format!("{}.html", path),
+            format!("{}/index.html", path),
+        ]
+    }
+}
+
+/// The HTML file under `dir` a spike was captured on, from its URL path or
+/// `page`, without reading the rest of the site. Like [`Site::resolve`] but
+/// without the title fallback.
+pub fn page_file(dir: &Path, spike: &Spike) -> Option<PathBuf> {
+    let is_page = |rel: &str| {
+        let file = dir.join(rel);
+        let html = matches!(file.extension().and_then(|e| e.to_str()), Some("html" | "htm"));
+        // Spike URLs come from the browser: stay inside `dir`
+        (html && !rel.split('/').any(|part| part == "..") && file.is_file()).then_some(file)
+    };
+    url_path(&spike.url)
+        .into_iter()
+        .chain([percent_decode(&spike.page)])
+        .find_map(|path| {
+            let candidates = candidates(&path);
+            candidates.iter().find_map(|c| is_page(c)).or_else(|| {
+                // Served under a prefix (e.g. a share URL): longest suffix
+                let parts: Vec<&str> = candidates[0].split('/').collect();
+                (1..parts.len()).find_map(|skip| is_page(&parts[skip..].join("/")))
+            })
+        })
+}
+
+/// Hash of the page a spike was captured on, if it is a file under `dir`
+pub fn page_hash(dir: &Path, spike: &Spike) -> Option<String> {
+    fs::read(page_file(dir, spike)?).ok().map(|bytes| content_hash(&bytes))
+}
+
+/// Whether a spike's page under `dir` has changed since the spike was recorded.
+///
+/// `None` when the spike carries no page hash or its page is not found.
+pub fn is_outdated(dir: &Path, spike: &Spike) -> Option<bool> {
+    PageHashes::new(dir).is_outdated(spike)
+}
+
+/// Current hashes of the page files under a directory, each file read and
+/// hashed at most once
+pub struct PageHashes {
+    dir: PathBuf,
+    hashes: HashMap<PathBuf, Option<String>>,
+}
+
+impl PageHashes {
+    pub fn new(dir: &Path) -> Self {
+        PageHashes {
+            dir: dir.to_path_buf(),
+            hashes: HashMap::new(),
+        }
+    }
+
+    /// Same as [`is_outdated`], reusing the hash of pages already seen
+    pub fn is_outdated(&mut self, spike: &Spike) -> Option<bool> {
+        let recorded = spike.page_hash.as_deref()?;
+        let file = page_file(&self.dir, spike)?;
+        let current = self
+            .hashes
+            .entry(file)
+            .or_insert_with_key(|file| fs::read(file).ok().map(|bytes| content_hash(&bytes)));
+        Some(current.as_deref()? != recorded)
+    }
+}
+
+/// Serialize a spike, adding `source` (`file:line:col`) when its element
+/// can be located in the site and `outdated` when its page has changed
+pub fn spike_json_with_source(
+    spike: &Spike,
+    site: Option<&Site>,
+    pages: &mut PageHashes,
+) -> serde_json::Result<serde_json::Value> {
+    let mut value = serde_json::to_value(spike)?;
+    if let Some(location) = site.and_then(|site| site.source_location(spike)) {
+        value["source"] = serde_json::json!(location.to_string());
+    }
+    if let Some(outdated) = pages.is_outdated(spike) {
+        value["outdated"] = serde_json::json!(outdated);
+    }
+    Ok(value)
+}
+
+/// Byte offsets of every `<tag` start tag in an HTML source, skipping
+/// comments and the raw text of script, style, textarea and title elements
+fn start_tags(source: &str, tag: &str) -> Vec<usize> {
+    let lower = source.to_ascii_lowercase();
+    let mut offsets = Vec::new();
+    let mut i = 0;
+
+    while let Some(rel) = lower[i..].find('<') {
+        let pos = i + rel;
+        let rest = &lower[pos + 1..];
+        if rest.starts_with("!--") {
+            i = rest.find("-->").map(|e| pos + 1 + e + 3).unwrap_or(lower.len());
+            continue;
+        }
+        let name: String = rest
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .collect();
+        i = pos + 1;
+        if name.is_empty() {
+            continue;
+        }
+        if name == tag {
+            offsets.push(pos);
+        }
+        if matches!(name.as_str(), "script" | "style" | "textarea" | "title") {
+            let close = format!("</{}", name);
+            if let Some(end) = lower[i..].find(&close) {
+                i += end;
+            }
+        }
+    }
+    offsets
+}
+
+fn parse_step(step: &str) -> Option<(String, usize)> {
+    let step = step.to_lowercase();
+    match step.split_once('[') {
+        Some((tag, rest)) => Some((tag.to_string(), rest.strip_suffix(']')?.parse().ok()?)),
+        None => Some((step, 1)),
+    }
+}
+
+/// A CSS selector for an element in the current HTML, in the widget's style:
+/// `#id` when the element has one, otherwise an `nth-child` path from `body`.
+pub fn selector_for(element: ElementRef) -> String {
+    if let Some(id) = element.value().id() {
+        return format!("#{}", id);
+    }
+
+    let mut path = Vec::new();
+    let mut current = element;
+    while !matches!(current.value().name(), "body" | "html") {
+        let Some(parent) = current.parent().and_then(ElementRef::wrap) else {
+            break;
+        };
+        let index = parent
+            .child_elements()
+            .position(|c| c.id() == current.id())
+            .unwrap_or(0)
+            + 1;
+        path.push(format!("{}:nth-child({})", current.value().name(), index));
+        current = parent;
+    }
+    path.reverse();
+
+    if path.is_empty() {
+        current.value().name().to_string()
+    } else {
+        format!("body > {}", path.join(" > "))
+    }
+}
+
+/// Whether an element's text matches the widget's captured element text,
+/// which is truncated to 100 characters with a trailing `...`
+fn text_matches(element: ElementRef, text: &str) -> bool {
+    let actual = normalize_text(&element.text().collect::<String>());
+    match text.strip_suffix("...") {
+        Some(prefix) => actual.starts_with(prefix.trim_end()),
+        None => actual == text,
+    }
+}
+
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Path component of a URL, percent-decoded
+fn url_path(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map(|(_, r)| r)?;
+    let path = rest.find('/').map(|i| &rest[i..]).unwrap_or("/");
+    let path = path.split(['?', '#']).next().unwrap_or("/");
+    Some(percent_decode(path))
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(b) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Reviewer, SpikeType};
+    use tempfile::TempDir;
+
+    const INDEX: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>Home</title></head>
+<body>
+  <header id="top"><nav><a class="brand">Acme</a></nav></header>
+  <main>
+    <h1 class="hero-title">Welcome   to Acme</h1>
+    <section class="pricing"><button class="cta">Buy now</button></section>
+  </main>
+</body>
+</html>"#;
+
+    fn site() -> (TempDir, Site) {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("index.html"), INDEX).unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs/index.html"), "<html><head><title>Docs</title></head><body></body></html>").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not html").unwrap();
+        let site = Site::load(dir.path()).unwrap();
+        (dir, site)
+    }
+
+    fn spike(url: &str, page: &str, selector: &str) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            url: url.to_string(),
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: Some(selector.to_string()),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            ..Spike::test("s1", page, None)
+        }
+    }
+
+    #[test]
+    fn test_load_only_html_files() {
+        let (_dir, site) = site();
+        let paths: Vec<&str> = site.pages.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, vec!["docs/index.html", "index.html"]);
+    }
+
+    #[test]
+    fn test_resolve_by_url_page_and_title() {
+        let (_dir, site) = site();
+        let resolve = |url: &str, page: &str| site.resolve(&spike(url, page, "h1")).map(|p| p.path.as_str());
+
+        assert_eq!(resolve("http://localhost:3847/", "x"), Some("index.html"));
+        assert_eq!(resolve("http://localhost:3847/docs/?q=1", "x"), Some("docs/index.html"));
+        assert_eq!(resolve("http://localhost:3847/docs", "x"), Some("docs/index.html"));
+        assert_eq!(resolve("https://spikes.sh/s/abc/index.html", "x"), Some("index.html"));
+        assert_eq!(resolve("", "/docs/index.html"), Some("docs/index.html"));
+        assert_eq!(resolve("http://remote.example/app", "Home"), Some("index.html"));
+        assert_eq!(resolve("http://remote.example/app", "Missing"), None);
+    }
+
+    #[test]
+    fn test_content_hash_and_outdated() {
+        // FNV-1a reference values: the hash must never change between releases
+        assert_eq!(content_hash(b""), "cbf29ce484222325");
+        assert_eq!(content_hash(b"a"), "af63dc4c8601ec8c");
+
+        let (dir, _site) = site();
+        let mut s = spike("http://localhost:3847/", "Home", "h1");
+        assert_eq!(is_outdated(dir.path(), &s), None);
+
+        s.page_hash = page_hash(dir.path(), &s);
+        assert_eq!(s.page_hash, Some(content_hash(INDEX.as_bytes())));
+        assert_eq!(is_outdated(dir.path(), &s), Some(false));
+
+        // A cache keeps the hash it read first; a fresh one sees the edit
+        let mut pages = PageHashes::new(dir.path());
+        assert_eq!(pages.is_outdated(&s), Some(false));
+        fs::write(dir.path().join("index.html"), INDEX.replace("Buy now", "Start trial")).unwrap();
+        assert_eq!(pages.is_outdated(&s), Some(false));
+        assert_eq!(is_outdated(dir.path(), &s), Some(true));
+    }
+
+    #[test]
+    fn test_page_file_without_loading_the_site() {
+        let (dir, _site) = site();
+        let file = |url: &str, page: &str| {
+            page_file(dir.path(), &spike(url, page, "h1"))
+                .map(|f| f.strip_prefix(dir.path()).unwrap().to_string_lossy().replace('\\', "/"))
+        };
+
+        assert_eq!(file("http://localhost:3847/", "x").as_deref(), Some("index.html"));
+        assert_eq!(file("http://localhost:3847/docs?q=1", "x").as_deref(), Some("docs/index.html"));
+        assert_eq!(file("https://spikes.sh/s/abc/docs/index.html", "x").as_deref(), Some("docs/index.html"));
+        assert_eq!(file("", "/docs/index.html").as_deref(), Some("docs/index.html"));
+        assert_eq!(file("http://localhost:3847/notes.txt", "x"), None);
+    }
+
+    #[test]
+    fn test_locate_live_by_selector() {
+        let (_dir, site) = site();
+        let page = &site.pages[1];
+        match page.locate(&spike("", "", ".hero-title")) {
+            Location::Live(e) => assert_eq!(e.value().name(), "h1"),
+            _ => panic!("expected live"),
+        }
+    }
+
+    #[test]
+    fn test_locate_moved_by_xpath_and_text() {
+        let (_dir, site) = site();
+        let page = &site.pages[1];
+
+        let mut by_xpath = spike("", "", ".old-title");
+        by_xpath.xpath = Some("/html[1]/body[1]/main[1]/h1[1]".to_string());
+        by_xpath.element_text = Some("Welcome to Acme".to_string());
+        match page.locate(&by_xpath) {
+            Location::Moved(e, FoundBy::Xpath) => assert_eq!(selector_for(e), "body > main:nth-child(2) > h1:nth-child(1)"),
+            _ => panic!("expected moved by xpath"),
+        }
+
+        let mut by_text = spike("", "", ".buy");
+        by_text.xpath = Some("/html[1]/body[1]/div[3]".to_string());
+        by_text.element_text = Some("Buy now".to_string());
+        match page.locate(&by_text) {
+            Location::Moved(e, FoundBy::Text) => assert_eq!(e.value().name(), "button"),
+            _ => panic!("expected moved by text"),
+        }
+    }
+
+    #[test]
+    fn test_locate_truncated_text_and_gone() {
+        let (_dir, site) = site();
+        let page = &site.pages[1];
+
+        let mut truncated = spike("", "", ".gone");
+        truncated.element_text = Some("Welcome to...".to_string());
+        assert!(matches!(page.locate(&truncated), Location::Moved(_, FoundBy::Text)));
+
+        let mut gone = spike("", "", ".gone");
+        gone.element_text = Some("Sign up".to_string());
+        assert!(matches!(page.locate(&gone), Location::Gone));
+    }
+
+    #[test]
+    fn test_source_location_of_live_and_moved() {
+        let (_dir, site) = site();
+        let page = &site.pages[1];
+
+        let location = page.source_location(&spike("", "", ".cta")).unwrap();
+        assert_eq!(location.to_string(), "index.html:8:30");
+
+        let mut moved = spike("", "", ".old-title");
+        moved.element_text = Some("Welcome to Acme".to_string());
+        let location = page.source_location(&moved).unwrap();
+        assert_eq!((location.line, location.column), (7, 5));
+
+        assert!(page.source_location(&spike("", "", ".gone")).is_none());
+    }
+
+    #[test]
+    fn test_spike_json_keeps_field_order() {
+        let (dir, site) = site();
+        let mut s = spike("http://localhost:3847/", "Home", ".cta");
+        s.page_hash = Some("0000000000000000".to_string());
+        let value = spike_json_with_source(&s, Some(&site), &mut PageHashes::new(dir.path())).unwrap();
+        let keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            [
+                "id", "type", "projectKey", "page", "url", "reviewer", "selector", "rating",
+                "comments", "timestamp", "viewport", "pageHash", "source", "outdated",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_start_tags_skip_comments_and_raw_text() {
+        let source = "<p>a</p><!-- <p> --><script>var s = '<p>';</script><P class=x>b</P>";
+        assert_eq!(start_tags(source, "p"), vec![0, 51]);
+    }
+
+    #[test]
+    fn test_snippet_marks_line() {
+        let (_dir, site) = site();
+        let snippet = site.pages[1].snippet(7, 1);
+        assert_eq!(
+            snippet,
+            "  6 |   <main>\n> 7 |     <h1 class=\"hero-title\">Welcome   to Acme</h1>\n  8 |     <section class=\"pricing\"><button class=\"cta\">Buy now</button></section>\n"
+        );
+    }
+
+    #[test]
+    fn test_selector_for_prefers_id() {
+        let (_dir, site) = site();
+        let page = &site.pages[1];
+        let sel = Selector::parse("header").unwrap();
+        let header = page.document.select(&sel).next().unwrap();
+        assert_eq!(selector_for(header), "#top");
+    }
+}
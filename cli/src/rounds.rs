@@ -0,0 +1,142 @@
+//! Review rounds (v1 mockups, v2 mockups, ...). While a round is open,
+//! spikes received by `serve` or `pull` are stamped with its name.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::spike::Spike;
+
+const ROUNDS_FILE: &str = ".spikes/rounds.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Round {
+    pub name: String,
+    pub started_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closed_at: Option<String>,
+}
+
+impl Round {
+    pub fn is_open(&self) -> bool {
+        self.closed_at.is_none()
+    }
+}
+
+/// All rounds in start order, stored in `.spikes/rounds.toml`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Rounds {
+    #[serde(default)]
+    pub rounds: Vec<Round>,
+}
+
+impl Rounds {
+    /// Load rounds from .spikes/rounds.toml, or return an empty list
+    pub fn load() -> Result<Self> {
+        Self::load_from(Path::new(ROUNDS_FILE))
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Invalid rounds.toml: {}", e),
+            ))
+        })
+    }
+
+    /// Save rounds to .spikes/rounds.toml
+    pub fn save(&self) -> Result<()> {
+        self.save_to(Path::new(ROUNDS_FILE))
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Failed to serialize rounds.toml: {}", e),
+            ))
+        })?;
+
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// The round currently collecting spikes, if any
+    pub fn open(&self) -> Option<&Round> {
+        self.rounds.iter().find(|r| r.is_open())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Round> {
+        self.rounds.iter().find(|r| r.name == name)
+    }
+
+    /// Open a new round; only one round can be open at a time
+    pub fn start(&mut self, name: &str, now: &str) -> Result<&Round> {
+        if let Some(open) = self.open() {
+            return Err(Error::RoundOpen(open.name.clone()));
+        }
+        if self.get(name).is_some() {
+            return Err(Error::RoundExists(name.to_string()));
+        }
+
+        self.rounds.push(Round {
+            name: name.to_string(),
+            started_at: now.to_string(),
+            closed_at: None,
+        });
+        Ok(&self.rounds[self.rounds.len() - 1])
+    }
+
+    /// Close the open round
+    pub fn close(&mut self, now: &str) -> Result<&Round> {
+        let round = self
+            .rounds
+            .iter_mut()
+            .find(|r| r.is_open())
+            .ok_or(Error::NoOpenRound)?;
+        round.closed_at = Some(now.to_string());
+        Ok(round)
+    }
+
+    /// Stamp a spike with the open round, unless it already has one
+    pub fn stamp(&self, spike: &mut Spike) {
+        if spike.round.is_none() {
+            spike.round = self.open().map(|r| r.name.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_start_close_and_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("rounds.toml");
+        let mut rounds = Rounds::load_from(&path).unwrap();
+        assert!(rounds.open().is_none());
+
+        rounds.start("v1", "2024-01-01T00:00:00Z").unwrap();
+        assert!(matches!(rounds.start("v2", "2024-01-02T00:00:00Z"), Err(Error::RoundOpen(name)) if name == "v1"));
+        rounds.close("2024-01-03T00:00:00Z").unwrap();
+        assert!(matches!(rounds.close("2024-01-03T00:00:00Z"), Err(Error::NoOpenRound)));
+        assert!(matches!(rounds.start("v1", "2024-01-04T00:00:00Z"), Err(Error::RoundExists(_))));
+        rounds.start("v2", "2024-01-04T00:00:00Z").unwrap();
+        rounds.save_to(&path).unwrap();
+
+        let loaded = Rounds::load_from(&path).unwrap();
+        assert_eq!(loaded.rounds.len(), 2);
+        assert_eq!(loaded.get("v1").unwrap().closed_at.as_deref(), Some("2024-01-03T00:00:00Z"));
+        assert_eq!(loaded.open().unwrap().name, "v2");
+    }
+}
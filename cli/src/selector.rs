@@ -0,0 +1,542 @@
+//! Selector normalization and element identity.
+//!
+//! The widget emits whichever selector is unique at capture time (`#id`,
+//! `.class`, `tag.class` or an `nth-child` chain), so the same element can
+//! show up under several spellings. This module canonicalizes selectors and
+//! groups spikes into elements using the selector, xpath and element text.
+
+use std::collections::HashMap;
+
+use crate::spike::{Spike, SpikeType};
+
+/// Canonical form of a CSS selector.
+///
+/// - whitespace and combinators are normalized (`a>b` -> `a > b`)
+/// - tag names are lowercased, class lists are sorted
+/// - `:first-child` becomes `:nth-child(1)`
+/// - a compound with an `#id` drops its tag and every ancestor before it
+/// - a leading `html >` is dropped
+pub fn canonicalize(selector: &str) -> String {
+    let tokens = tokenize(selector.trim());
+
+    // Ancestors of an ID selector add nothing, IDs are unique
+    let start = tokens
+        .iter()
+        .rposition(|t| matches!(t, Token::Compound(c) if has_id(c)))
+        .unwrap_or(0);
+    let mut tokens = &tokens[start..];
+    if let [Token::Compound(first), Token::Combinator('>'), rest @ ..] = tokens {
+        if first.eq_ignore_ascii_case("html") && !rest.is_empty() {
+            tokens = rest;
+        }
+    }
+
+    let mut out = String::new();
+    for token in tokens {
+        match token {
+            Token::Compound(c) => out.push_str(&canonical_compound(c)),
+            Token::Combinator(' ') => out.push(' '),
+            Token::Combinator(c) => {
+                out.push(' ');
+                out.push(*c);
+                out.push(' ');
+            }
+        }
+    }
+    out
+}
+
+/// Canonical form of an XPath: lowercased, with redundant `[1]` steps removed
+pub fn canonicalize_xpath(xpath: &str) -> String {
+    xpath.trim().to_lowercase().replace("[1]", "")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Compound(String),
+    Combinator(char),
+}
+
+fn tokenize(selector: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut pending: Option<char> = None;
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut chars = selector.chars();
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            current.push(c);
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                continue;
+            }
+            '"' | '\'' if depth > 0 => {
+                quote = Some(c);
+                current.push(c);
+                continue;
+            }
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if depth == 0 && (c.is_whitespace() || matches!(c, '>' | '+' | '~')) {
+            if !current.is_empty() {
+                tokens.push(Token::Compound(std::mem::take(&mut current)));
+            }
+            // An explicit combinator wins over the descendant combinator
+            if !c.is_whitespace() || pending.is_none() {
+                pending = Some(if c.is_whitespace() { ' ' } else { c });
+            }
+            continue;
+        }
+        if let Some(comb) = pending.take() {
+            if !tokens.is_empty() {
+                tokens.push(Token::Combinator(comb));
+            }
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        tokens.push(Token::Compound(current));
+    }
+    tokens
+}
+
+fn has_id(compound: &str) -> bool {
+    split_compound(compound).iter().any(|p| p.starts_with('#'))
+}
+
+/// Split a compound selector into its tag and simple selectors
+fn split_compound(compound: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut chars = compound.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            current.push(c);
+            if let Some(next) = chars.next() {
+                current.push(next);
+            }
+            continue;
+        }
+        if depth == 0 && matches!(c, '#' | '.' | '[' | ':') && !current.is_empty() && !current.ends_with(':') {
+            parts.push(std::mem::take(&mut current));
+        }
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+fn canonical_compound(compound: &str) -> String {
+    let parts = split_compound(compound);
+    let mut tag = None;
+    let mut ids = Vec::new();
+    let mut classes = Vec::new();
+    let mut rest = Vec::new();
+
+    for part in parts {
+        if part.starts_with('#') {
+            ids.push(part);
+        } else if part.starts_with('.') {
+            classes.push(part);
+        } else if part.starts_with('[') {
+            rest.push(part);
+        } else if part.starts_with(':') {
+            // Only the pseudo-class name is case-insensitive, not its arguments
+            let (name, args) = part.split_at(part.find('(').unwrap_or(part.len()));
+            let part = format!("{}{}", name.to_lowercase(), args);
+            rest.push(if part == ":first-child" {
+                ":nth-child(1)".to_string()
+            } else {
+                part
+            });
+        } else {
+            tag = Some(part.to_lowercase());
+        }
+    }
+    classes.sort();
+    classes.dedup();
+
+    let mut out = String::new();
+    if ids.is_empty() {
+        if let Some(tag) = tag.filter(|t| t != "*" || (classes.is_empty() && rest.is_empty())) {
+            out.push_str(&tag);
+        }
+    }
+    for part in ids.iter().chain(classes.iter()).chain(rest.iter()) {
+        out.push_str(part);
+    }
+    out
+}
+
+/// Tag name of the element a spike points at, from its xpath or selector
+fn element_tag(spike: &Spike) -> Option<String> {
+    if let Some(xpath) = &spike.xpath {
+        let step = xpath.rsplit('/').next()?;
+        let tag = step.split('[').next()?.to_lowercase();
+        if !tag.is_empty() {
+            return Some(tag);
+        }
+    }
+    let canonical = canonicalize(spike.selector.as_deref()?);
+    let last = canonical.rsplit(' ').next()?;
+    let tag: String = last
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Element spikes that point at the same element
+#[derive(Debug, Clone)]
+pub struct ElementGroup<'a> {
+    /// Most common canonical selector in the group
+    pub selector: String,
+    pub spikes: Vec<&'a Spike>,
+}
+
+/// A canonical selector without the tag of its last compound, so `h1.hero`
+/// and `.hero` compare equal. Empty when the last compound is only a tag.
+fn untagged(canonical: &str) -> String {
+    let (ancestors, last) = canonical.rsplit_once(' ').map_or(("", canonical), |(a, l)| (a, l));
+    let rest = last.trim_start_matches(|c: char| c.is_ascii_alphanumeric() || c == '-');
+    if rest.is_empty() {
+        return String::new();
+    }
+    if ancestors.is_empty() {
+        rest.to_string()
+    } else {
+        format!("{} {}", ancestors, rest)
+    }
+}
+
+/// Group element spikes by element identity.
+///
+/// Two spikes are the same element when their canonical selectors match, or
+/// when they are on the same page and share a canonical xpath. Spikes on the
+/// same page with different xpaths are never the same element. When one of
+/// them has no xpath, the same selector apart from the tag (`h1.hero` and
+/// `.hero`) or the same element text also counts, as long as the tags don't
+/// differ. Groups keep the order of their first spike.
+pub fn group_elements<'a, I>(spikes: I) -> Vec<ElementGroup<'a>>
+where
+    I: IntoIterator<Item = &'a Spike>,
+{
+    let items: Vec<&Spike> = spikes
+        .into_iter()
+        .filter(|s| s.spike_type == SpikeType::Element && s.selector.is_some())
+        .collect();
+    let selectors: Vec<String> = items
+        .iter()
+        .map(|s| canonicalize(s.selector.as_deref().unwrap_or_default()))
+        .collect();
+    let xpaths: Vec<Option<String>> = items
+        .iter()
+        .map(|s| s.xpath.as_deref().map(canonicalize_xpath))
+        .collect();
+    let tags: Vec<Option<String>> = items.iter().map(|s| element_tag(s)).collect();
+    let untagged: Vec<String> = selectors.iter().map(|s| untagged(s)).collect();
+    let texts: Vec<Option<&str>> = items
+        .iter()
+        .map(|s| s.element_text.as_deref().map(str::trim).filter(|t| !t.is_empty()))
+        .collect();
+
+    // Only called for spikes on the same page
+    let same_element = |i: usize, j: usize| match (&xpaths[i], &xpaths[j]) {
+        // The xpath says which element it is; the rest is a fallback without it
+        (Some(a), Some(b)) => a == b,
+        _ => {
+            let tags_agree = match (&tags[i], &tags[j]) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            };
+            tags_agree
+                && ((!untagged[i].is_empty() && untagged[i] == untagged[j])
+                    || texts[i].is_some() && texts[i] == texts[j])
+        }
+    };
+
+    let mut parent: Vec<usize> = (0..items.len()).collect();
+    // For each group root, the xpath its spikes point at on each page
+    let mut pinned: Vec<HashMap<&str, &str>> = items
+        .iter()
+        .zip(&xpaths)
+        .map(|(s, x)| x.as_deref().map(|x| (s.page.as_str(), x)).into_iter().collect())
+        .collect();
+    let mut by_selector: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut by_page: HashMap<&str, Vec<usize>> = HashMap::new();
+    for i in 0..items.len() {
+        let same_selector = by_selector.entry(selectors[i].as_str()).or_default();
+        for &j in same_selector.iter() {
+            union(&mut parent, &mut pinned, i, j);
+        }
+        same_selector.push(i);
+        let on_page = by_page.entry(items[i].page.as_str()).or_default();
+        for &j in on_page.iter() {
+            if same_element(i, j) {
+                union(&mut parent, &mut pinned, i, j);
+            }
+        }
+        on_page.push(i);
+    }
+
+    let mut order: Vec<usize> = Vec::new();
+    let mut members: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..items.len() {
+        let root = find(&mut parent, i);
+        if !members.contains_key(&root) {
+            order.push(root);
+        }
+        members.entry(root).or_default().push(i);
+    }
+
+    order
+        .into_iter()
+        .map(|root| {
+            let indices = &members[&root];
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for &i in indices {
+                *counts.entry(selectors[i].as_str()).or_insert(0) += 1;
+            }
+            let selector = counts
+                .into_iter()
+                .max_by(|a, b| {
+                    a.1.cmp(&b.1)
+                        .then_with(|| b.0.len().cmp(&a.0.len()))
+                        .then_with(|| b.0.cmp(a.0))
+                })
+                .map(|(s, _)| s.to_string())
+                .unwrap_or_default();
+            ElementGroup {
+                selector,
+                spikes: indices.iter().map(|&i| items[i]).collect(),
+            }
+        })
+        .collect()
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[i] = root;
+    root
+}
+
+/// Merge two groups unless they point at different xpaths on the same page
+fn union<'a>(parent: &mut [usize], pinned: &mut [HashMap<&'a str, &'a str>], a: usize, b: usize) {
+    let (a, b) = (find(parent, a), find(parent, b));
+    if a == b || pinned[a].iter().any(|(page, x)| pinned[b].get(page).is_some_and(|y| x != y)) {
+        return;
+    }
+    let (root, child) = (a.min(b), a.max(b));
+    parent[child] = root;
+    let moved = std::mem::take(&mut pinned[child]);
+    pinned[root].extend(moved);
+}
+
+/// Whether a group matches a partial selector, xpath or element text query.
+///
+/// Used as a fallback when no group matches the canonical selector exactly.
+pub fn matches_partial(group: &ElementGroup, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return false;
+    }
+    let canonical_query = canonicalize(&query).to_lowercase();
+    group.spikes.iter().any(|s| {
+        let selector = s.selector.as_deref().map(canonicalize).unwrap_or_default().to_lowercase();
+        selector.contains(&canonical_query)
+            || s.xpath.as_deref().is_some_and(|x| canonicalize_xpath(x).contains(&query))
+            || s.element_text.as_deref().is_some_and(|t| t.to_lowercase().contains(&query))
+    })
+}
+
+/// Find the element groups for a selector: exact canonical match first,
+/// then partial matches against selector, xpath and element text.
+pub fn find_element<'a, 'b>(groups: &'b [ElementGroup<'a>], query: &str) -> Vec<&'b ElementGroup<'a>> {
+    let canonical = canonicalize(query);
+    let xpath = canonicalize_xpath(query);
+    let exact: Vec<&ElementGroup> = groups
+        .iter()
+        .filter(|g| {
+            g.spikes.iter().any(|s| {
+                s.selector.as_deref().map(canonicalize).as_deref() == Some(canonical.as_str())
+                    || s.xpath.as_deref().map(canonicalize_xpath).as_deref() == Some(xpath.as_str())
+            })
+        })
+        .collect();
+    if !exact.is_empty() {
+        return exact;
+    }
+    groups.iter().filter(|g| matches_partial(g, query)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Rating, Reviewer};
+
+    fn spike(id: &str, page: &str, selector: &str, xpath: Option<&str>, text: Option<&str>) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            url: format!("http://test/{}", page),
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Alice".to_string(),
+                email: None,
+            },
+            selector: Some(selector.to_string()),
+            xpath: xpath.map(|x| x.to_string()),
+            element_text: text.map(|t| t.to_string()),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            ..Spike::test(id, page, Some(Rating::Like))
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_whitespace_and_combinators() {
+        assert_eq!(canonicalize("  body>main   >  h1 "), "body > main > h1");
+        assert_eq!(canonicalize("ul li+li~li"), "ul li + li ~ li");
+        assert_eq!(canonicalize("main  .card"), "main .card");
+    }
+
+    #[test]
+    fn test_canonicalize_compounds() {
+        assert_eq!(canonicalize("DIV.b.a"), "div.a.b");
+        assert_eq!(canonicalize("li:first-child"), "li:nth-child(1)");
+        assert_eq!(canonicalize("a:HOVER:not(.Foo)"), "a:hover:not(.Foo)");
+        assert_eq!(canonicalize("*.card"), ".card");
+        assert_eq!(canonicalize("a[href=\"x > y\"]"), "a[href=\"x > y\"]");
+    }
+
+    #[test]
+    fn test_canonicalize_ids() {
+        assert_eq!(canonicalize("div#hero"), "#hero");
+        assert_eq!(canonicalize("body > main > #hero > h1"), "#hero > h1");
+        assert_eq!(canonicalize("#a\\:b"), "#a\\:b");
+    }
+
+    #[test]
+    fn test_canonicalize_drops_html_prefix() {
+        assert_eq!(canonicalize("html > body > main"), "body > main");
+        assert_eq!(canonicalize("html"), "html");
+    }
+
+    #[test]
+    fn test_canonicalize_xpath() {
+        assert_eq!(canonicalize_xpath("/HTML[1]/body[1]/div[2]"), "/html/body/div[2]");
+    }
+
+    #[test]
+    fn test_group_equivalent_selectors() {
+        let spikes = vec![
+            spike("a", "index.html", ".card.featured", None, None),
+            spike("b", "index.html", ".featured.card", None, None),
+            spike("c", "index.html", "div#pricing", None, None),
+            spike("d", "about.html", "#pricing", None, None),
+        ];
+
+        let groups = group_elements(&spikes);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].selector, ".card.featured");
+        assert_eq!(groups[0].spikes.len(), 2);
+        assert_eq!(groups[1].selector, "#pricing");
+    }
+
+    #[test]
+    fn test_group_by_xpath_and_text_on_same_page() {
+        let spikes = vec![
+            spike("a", "index.html", ".cta", Some("/html[1]/body[1]/a[1]"), Some("Buy now")),
+            spike("b", "index.html", "body > a:nth-child(3)", Some("/html/body/a"), None),
+            spike("c", "index.html", "a.button", None, Some("Buy now")),
+            spike("d", "about.html", "a.other", Some("/html/body/a"), Some("Buy now")),
+        ];
+
+        let groups = group_elements(&spikes);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].spikes.len(), 3);
+        assert_eq!(groups[1].spikes[0].id, "d");
+    }
+
+    #[test]
+    fn test_same_selector_with_different_xpaths_stays_apart() {
+        let spikes = vec![
+            spike("a", "index.html", ".cta", Some("/html/body/div[1]/a"), None),
+            spike("b", "index.html", ".cta", Some("/html/body/footer/a"), None),
+            // Could be either one, so it must not bridge the two
+            spike("c", "index.html", ".cta", None, None),
+            spike("d", "about.html", ".cta", Some("/html/body/footer/a"), None),
+        ];
+
+        let groups = group_elements(&spikes);
+        let ids: Vec<Vec<&str>> = groups.iter().map(|g| g.spikes.iter().map(|s| s.id.as_str()).collect()).collect();
+        assert_eq!(ids, vec![vec!["a", "c", "d"], vec!["b"]]);
+    }
+
+    #[test]
+    fn test_text_and_untagged_selector_only_without_xpath() {
+        let spikes = vec![
+            // Same text, different elements
+            spike("a", "index.html", "li.item", Some("/html/body/ul/li[1]"), Some("Read more")),
+            spike("b", "index.html", "li.item:nth-child(2)", Some("/html/body/ul/li[2]"), Some("Read more")),
+            // One spelling with the tag, one without
+            spike("c", "index.html", "h1.hero", Some("/html/body/h1"), None),
+            spike("d", "index.html", ".hero", None, None),
+            spike("e", "index.html", "main h2.hero", None, None),
+            spike("f", "index.html", "main .hero", None, None),
+        ];
+
+        let groups = group_elements(&spikes);
+        let ids: Vec<Vec<&str>> = groups.iter().map(|g| g.spikes.iter().map(|s| s.id.as_str()).collect()).collect();
+        assert_eq!(ids, vec![vec!["a"], vec!["b"], vec!["c", "d"], vec!["e", "f"]]);
+        assert_eq!(untagged("h1"), "");
+        assert_eq!(untagged("main > h1.hero"), "main > .hero");
+    }
+
+    #[test]
+    fn test_text_match_requires_same_tag() {
+        let spikes = vec![
+            spike("a", "index.html", "a.more", None, Some("Learn more")),
+            spike("b", "index.html", "button.more", None, Some("Learn more")),
+        ];
+
+        assert_eq!(group_elements(&spikes).len(), 2);
+    }
+
+    #[test]
+    fn test_find_element_exact_then_partial() {
+        let spikes = vec![
+            spike("a", "index.html", ".hero-title", None, Some("Welcome")),
+            spike("b", "index.html", ".nav-button", None, Some("Menu")),
+        ];
+        let groups = group_elements(&spikes);
+
+        assert_eq!(find_element(&groups, "*.hero-title").len(), 1);
+        assert_eq!(find_element(&groups, "hero")[0].selector, ".hero-title");
+        assert_eq!(find_element(&groups, "menu")[0].selector, ".nav-button");
+        assert!(find_element(&groups, "footer").is_empty());
+    }
+}
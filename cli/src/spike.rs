@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Generic paginated response from the API
@@ -8,13 +9,16 @@ pub struct PaginatedResponse<T> {
     pub next_cursor: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct Reviewer {
     pub id: String,
     pub name: String,
+    /// Captured by the widget when `collect_email` is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BoundingBox {
     pub x: f64,
     pub y: f64,
@@ -22,20 +26,122 @@ pub struct BoundingBox {
     pub height: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Viewport {
     pub width: u32,
     pub height: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Device class of a viewport, by width (breakpoints live in `config.toml`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewportClass {
+    Mobile,
+    Tablet,
+    Desktop,
+}
+
+impl ViewportClass {
+    pub const ALL: [ViewportClass; 3] = [ViewportClass::Mobile, ViewportClass::Tablet, ViewportClass::Desktop];
+}
+
+impl std::fmt::Display for ViewportClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ViewportClass::Mobile => write!(f, "mobile"),
+            ViewportClass::Tablet => write!(f, "tablet"),
+            ViewportClass::Desktop => write!(f, "desktop"),
+        }
+    }
+}
+
+impl std::str::FromStr for ViewportClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mobile" => Ok(ViewportClass::Mobile),
+            "tablet" => Ok(ViewportClass::Tablet),
+            "desktop" => Ok(ViewportClass::Desktop),
+            _ => Err(format!("Invalid viewport: {}. Use mobile, tablet, or desktop", s)),
+        }
+    }
+}
+
+/// Issue tracker a spike can be turned into an issue on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerKind {
+    Github,
+    Gitlab,
+    Linear,
+    Jira,
+}
+
+impl std::fmt::Display for TrackerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrackerKind::Github => write!(f, "github"),
+            TrackerKind::Gitlab => write!(f, "gitlab"),
+            TrackerKind::Linear => write!(f, "linear"),
+            TrackerKind::Jira => write!(f, "jira"),
+        }
+    }
+}
+
+impl std::str::FromStr for TrackerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "github" => Ok(TrackerKind::Github),
+            "gitlab" => Ok(TrackerKind::Gitlab),
+            "linear" => Ok(TrackerKind::Linear),
+            "jira" => Ok(TrackerKind::Jira),
+            _ => Err(format!("Invalid tracker: {}. Use github, gitlab, linear, or jira", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueState {
+    Open,
+    Closed,
+}
+
+impl std::fmt::Display for IssueState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IssueState::Open => write!(f, "open"),
+            IssueState::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// Tracker issue created from a spike with `spikes issues create`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct IssueLink {
+    pub tracker: TrackerKind,
+    /// The tracker's handle for the issue: number (GitHub), iid (GitLab),
+    /// identifier (Linear) or key (Jira)
+    pub id: String,
+    /// Repository (GitHub, GitLab), team key (Linear) or project key (Jira)
+    pub project: String,
+    pub url: String,
+    /// State as of the last create or sync
+    pub state: IssueState,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum SpikeType {
+    #[default]
     Page,
     Element,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Rating {
     Love,
@@ -69,7 +175,34 @@ impl std::str::FromStr for Rating {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Rating {
+    /// Numeric score used when aggregating ratings (no = 0, meh = 1, like = 2, love = 3)
+    pub fn score(&self) -> f64 {
+        match self {
+            Rating::Love => 3.0,
+            Rating::Like => 2.0,
+            Rating::Meh => 1.0,
+            Rating::No => 0.0,
+        }
+    }
+
+    /// Meh and no ratings count as negative feedback
+    pub fn is_negative(&self) -> bool {
+        matches!(self, Rating::Meh | Rating::No)
+    }
+
+    /// Rating closest to an aggregated score
+    pub fn from_score(score: f64) -> Rating {
+        match score.round() as i64 {
+            i64::MIN..=0 => Rating::No,
+            1 => Rating::Meh,
+            2 => Rating::Like,
+            _ => Rating::Love,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Spike {
     pub id: String,
@@ -81,6 +214,9 @@ pub struct Spike {
     pub reviewer: Reviewer,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub selector: Option<String>,
+    /// XPath of the element, as captured by the widget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xpath: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub element_text: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -95,6 +231,27 @@ pub struct Spike {
     /// ISO 8601 timestamp when spike was resolved
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resolved_at: Option<String>,
+    /// ID of the canonical spike this one duplicates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+    /// Set by `spikes check --flag` when the element is gone from the page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale: Option<bool>,
+    /// Free-form labels added with `spikes tag`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Who is responsible for addressing this spike
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// Review round that was open when the spike arrived
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round: Option<String>,
+    /// Hash of the page file when the spike arrived (see `pages::content_hash`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page_hash: Option<String>,
+    /// Tracker issue filed for this spike
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<IssueLink>,
 }
 
 impl Spike {
@@ -121,6 +278,24 @@ impl Spike {
     pub fn is_resolved(&self) -> bool {
         self.resolved.unwrap_or(false)
     }
+
+    /// A page spike for tests; set anything else with `..Spike::test(...)`
+    #[cfg(test)]
+    pub fn test(id: &str, page: &str, rating: Option<Rating>) -> Spike {
+        Spike {
+            id: id.to_string(),
+            project_key: "test".to_string(),
+            page: page.to_string(),
+            reviewer: Reviewer {
+                id: "r1".to_string(),
+                name: "Tester".to_string(),
+                email: None,
+            },
+            rating,
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            ..Default::default()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -144,6 +319,16 @@ mod tests {
         assert_eq!(format!("{}", Rating::No), "no");
     }
 
+    #[test]
+    fn test_rating_score_roundtrip() {
+        for rating in [Rating::Love, Rating::Like, Rating::Meh, Rating::No] {
+            assert_eq!(Rating::from_score(rating.score()), rating);
+        }
+        assert_eq!(Rating::from_score(2.4), Rating::Like);
+        assert_eq!(Rating::from_score(-1.0), Rating::No);
+        assert_eq!(Rating::from_score(9.0), Rating::Love);
+    }
+
     #[test]
     fn test_spike_type_serialization() {
         let page = SpikeType::Page;
@@ -258,8 +443,10 @@ mod tests {
             reviewer: Reviewer {
                 id: "reviewer-1".to_string(),
                 name: "Charlie".to_string(),
+                email: None,
             },
             selector: None,
+            xpath: None,
             element_text: None,
             bounding_box: None,
             rating: Some(Rating::Meh),
@@ -271,6 +458,13 @@ mod tests {
             }),
             resolved: None,
             resolved_at: None,
+            duplicate_of: None,
+            stale: None,
+            tags: None,
+            assignee: None,
+            round: None,
+            page_hash: None,
+            issue: None,
         };
 
         let json = serde_json::to_string(&spike).unwrap();
@@ -290,8 +484,9 @@ mod tests {
             project_key: "p".to_string(),
             page: "page".to_string(),
             url: "url".to_string(),
-            reviewer: Reviewer { id: "r".to_string(), name: "R".to_string() },
+            reviewer: Reviewer { id: "r".to_string(), name: "R".to_string(), email: None },
             selector: None,
+            xpath: None,
             element_text: None,
             bounding_box: None,
             rating: Some(Rating::Love),
@@ -300,6 +495,13 @@ mod tests {
             viewport: None,
             resolved: None,
             resolved_at: None,
+            duplicate_of: None,
+            stale: None,
+            tags: None,
+            assignee: None,
+            round: None,
+            page_hash: None,
+            issue: None,
         };
 
         assert_eq!(spike.rating_str(), "love");
@@ -317,8 +519,9 @@ mod tests {
             project_key: "p".to_string(),
             page: "page".to_string(),
             url: "url".to_string(),
-            reviewer: Reviewer { id: "r".to_string(), name: "R".to_string() },
+            reviewer: Reviewer { id: "r".to_string(), name: "R".to_string(), email: None },
             selector: None,
+            xpath: None,
             element_text: None,
             bounding_box: None,
             rating: None,
@@ -327,6 +530,13 @@ mod tests {
             viewport: None,
             resolved: None,
             resolved_at: None,
+            duplicate_of: None,
+            stale: None,
+            tags: None,
+            assignee: None,
+            round: None,
+            page_hash: None,
+            issue: None,
         };
 
         assert_eq!(spike.type_str(), "page");
@@ -297,8 +297,9 @@ mod tests {
                 project_key: "p".to_string(),
                 page: "index.html".to_string(),
                 url: "http://test".to_string(),
-                reviewer: crate::spike::Reviewer { id: "r1".to_string(), name: "Test".to_string() },
+                reviewer: crate::spike::Reviewer { id: "r1".to_string(), name: "Test".to_string(), email: None },
                 selector: None,
+                xpath: None,
                 element_text: None,
                 bounding_box: None,
                 rating: Some(crate::spike::Rating::Like),
@@ -307,6 +308,13 @@ mod tests {
                 viewport: None,
                 resolved: None,
                 resolved_at: None,
+                duplicate_of: None,
+                stale: None,
+                tags: None,
+                assignee: None,
+                round: None,
+                page_hash: None,
+                issue: None,
             },
         ];
 
@@ -329,8 +337,9 @@ mod tests {
                 project_key: "p".to_string(),
                 page: "index.html".to_string(),
                 url: "http://test".to_string(),
-                reviewer: crate::spike::Reviewer { id: "r1".to_string(), name: "Test".to_string() },
+                reviewer: crate::spike::Reviewer { id: "r1".to_string(), name: "Test".to_string(), email: None },
                 selector: None,
+                xpath: None,
                 element_text: None,
                 bounding_box: None,
                 rating: None,
@@ -339,6 +348,13 @@ mod tests {
                 viewport: None,
                 resolved: None,
                 resolved_at: None,
+                duplicate_of: None,
+                stale: None,
+                tags: None,
+                assignee: None,
+                round: None,
+                page_hash: None,
+                issue: None,
             },
         ];
 
@@ -355,8 +371,9 @@ mod tests {
                 project_key: "p".to_string(),
                 page: "index.html".to_string(),
                 url: "http://test".to_string(),
-                reviewer: crate::spike::Reviewer { id: "r1".to_string(), name: "Test".to_string() },
+                reviewer: crate::spike::Reviewer { id: "r1".to_string(), name: "Test".to_string(), email: None },
                 selector: None,
+                xpath: None,
                 element_text: None,
                 bounding_box: None,
                 rating: None,
@@ -365,6 +382,13 @@ mod tests {
                 viewport: None,
                 resolved: None,
                 resolved_at: None,
+                duplicate_of: None,
+                stale: None,
+                tags: None,
+                assignee: None,
+                round: None,
+                page_hash: None,
+                issue: None,
             },
         ];
 
@@ -390,8 +414,9 @@ mod tests {
                 project_key: "p".to_string(),
                 page: "index.html".to_string(),
                 url: "http://test".to_string(),
-                reviewer: crate::spike::Reviewer { id: "r1".to_string(), name: "Test".to_string() },
+                reviewer: crate::spike::Reviewer { id: "r1".to_string(), name: "Test".to_string(), email: None },
                 selector: None,
+                xpath: None,
                 element_text: None,
                 bounding_box: None,
                 rating: None,
@@ -400,6 +425,13 @@ mod tests {
                 viewport: None,
                 resolved: None,
                 resolved_at: None,
+                duplicate_of: None,
+                stale: None,
+                tags: None,
+                assignee: None,
+                round: None,
+                page_hash: None,
+                issue: None,
             },
             Spike {
                 id: "abcdef789012".to_string(),
@@ -407,8 +439,9 @@ mod tests {
                 project_key: "p".to_string(),
                 page: "page.html".to_string(),
                 url: "http://test".to_string(),
-                reviewer: crate::spike::Reviewer { id: "r1".to_string(), name: "Test".to_string() },
+                reviewer: crate::spike::Reviewer { id: "r1".to_string(), name: "Test".to_string(), email: None },
                 selector: None,
+                xpath: None,
                 element_text: None,
                 bounding_box: None,
                 rating: None,
@@ -417,6 +450,13 @@ mod tests {
                 viewport: None,
                 resolved: None,
                 resolved_at: None,
+                duplicate_of: None,
+                stale: None,
+                tags: None,
+                assignee: None,
+                round: None,
+                page_hash: None,
+                issue: None,
             },
         ];
 
@@ -0,0 +1,290 @@
+//! Extractive summaries of feedback threads.
+//!
+//! No model involved: comments are split into terms, terms are weighted by
+//! how many comments use them, and the comments closest to the thread's
+//! common vocabulary are quoted. The same input always gives the same
+//! summary.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::commands::stats::RatingCounts;
+use crate::selector::group_elements;
+use crate::spike::{Rating, Spike, SpikeType};
+
+/// Keywords reported per summary
+pub const MAX_KEYWORDS: usize = 5;
+/// Representative comments quoted per summary
+pub const MAX_QUOTES: usize = 2;
+/// Quotes longer than this many characters are cut
+const MAX_QUOTE_CHARS: usize = 140;
+/// Comments sharing this share of their terms with a quote are redundant
+const REDUNDANT_OVERLAP: f64 = 0.5;
+
+const STOPWORDS: &[&str] = &[
+    "about", "after", "again", "all", "also", "and", "any", "are", "aren't", "bit", "but", "can",
+    "can't", "could", "did", "does", "doesn't", "don't", "for", "from", "get", "got", "had", "has",
+    "have", "here", "how", "i'm", "into", "it's", "its", "just", "kind", "less", "like", "look",
+    "looks", "make", "more", "much", "need", "needs", "not", "now", "one", "only", "our", "out",
+    "really", "should", "some", "that", "that's", "the", "their", "them", "then", "there", "these",
+    "they", "thing", "think", "this", "those", "too", "very", "was", "way", "were", "what", "when",
+    "where", "which", "while", "who", "why", "will", "with", "would", "you", "your",
+];
+
+/// A comment picked to stand for the thread
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Quote {
+    pub id: String,
+    pub reviewer: String,
+    pub rating: Option<Rating>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+    pub spikes: usize,
+    pub ratings: RatingCounts,
+    /// Terms used in more than one comment, most widespread first
+    pub keywords: Vec<String>,
+    pub quotes: Vec<Quote>,
+}
+
+/// Lowercased terms of a comment, with a light plural stem as the key.
+/// Returns `(stem, surface form)` pairs.
+fn terms(comment: &str) -> Vec<(String, String)> {
+    comment
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|w| w.trim_matches('\''))
+        .filter(|w| w.chars().count() >= 3 && !w.chars().all(|c| c.is_ascii_digit()))
+        .filter(|w| !STOPWORDS.contains(w))
+        .map(|w| (stem(w), w.to_string()))
+        .collect()
+}
+
+fn stem(word: &str) -> String {
+    let keep = ["ss", "us", "is"];
+    if word.len() > 3 && word.ends_with('s') && !keep.iter().any(|k| word.ends_with(k)) {
+        word[..word.len() - 1].to_string()
+    } else {
+        word.to_string()
+    }
+}
+
+fn truncate(text: &str) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= MAX_QUOTE_CHARS {
+        return text;
+    }
+    let cut: String = text.chars().take(MAX_QUOTE_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+impl Summary {
+    /// Summarize a set of spikes: rating mix, keywords and representative
+    /// comments
+    pub fn of(spikes: &[&Spike]) -> Summary {
+        let mut ratings = RatingCounts::default();
+        for spike in spikes {
+            ratings.add(spike.rating.as_ref());
+        }
+
+        let commented: Vec<(&Spike, HashSet<String>)> = spikes
+            .iter()
+            .filter(|s| !s.comments.trim().is_empty())
+            .map(|s| (*s, terms(&s.comments).into_iter().map(|(stem, _)| stem).collect()))
+            .collect();
+
+        // Document frequency per stem, and the most used spelling of each
+        let mut df: BTreeMap<String, usize> = BTreeMap::new();
+        let mut forms: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        for (spike, stems) in &commented {
+            for stem in stems {
+                *df.entry(stem.clone()).or_default() += 1;
+            }
+            for (stem, form) in terms(&spike.comments) {
+                *forms.entry(stem).or_default().entry(form).or_default() += 1;
+            }
+        }
+
+        let mut ranked: Vec<(&String, &usize)> = df.iter().filter(|(_, n)| **n >= 2).collect();
+        ranked.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let keywords = ranked
+            .iter()
+            .take(MAX_KEYWORDS)
+            .map(|(stem, _)| {
+                forms[*stem]
+                    .iter()
+                    .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.len().cmp(&a.0.len())).then_with(|| b.0.cmp(a.0)))
+                    .map(|(form, _)| form.clone())
+                    .unwrap_or_else(|| (*stem).clone())
+            })
+            .collect();
+
+        // Closest to the thread's shared vocabulary first; input order breaks ties
+        let mut scored: Vec<(f64, usize)> = commented
+            .iter()
+            .enumerate()
+            .map(|(i, (_, stems))| {
+                let weight: usize = stems.iter().map(|s| df[s]).sum();
+                let score = if stems.is_empty() {
+                    0.0
+                } else {
+                    weight as f64 / (stems.len() as f64).sqrt()
+                };
+                (score, i)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        let mut chosen: Vec<usize> = Vec::new();
+        for (_, i) in scored {
+            if chosen.len() == MAX_QUOTES {
+                break;
+            }
+            let stems = &commented[i].1;
+            let redundant = chosen.iter().any(|&j| {
+                let other = &commented[j].1;
+                let union = stems.union(other).count();
+                union > 0 && stems.intersection(other).count() as f64 / union as f64 >= REDUNDANT_OVERLAP
+            });
+            if !redundant {
+                chosen.push(i);
+            }
+        }
+
+        let quotes = chosen
+            .into_iter()
+            .map(|i| {
+                let spike = commented[i].0;
+                Quote {
+                    id: spike.id.clone(),
+                    reviewer: spike.reviewer.name.clone(),
+                    rating: spike.rating.clone(),
+                    text: truncate(&spike.comments),
+                }
+            })
+            .collect();
+
+        Summary {
+            spikes: spikes.len(),
+            ratings,
+            keywords,
+            quotes,
+        }
+    }
+
+    /// Non-zero rating counts, e.g. "3 no, 1 love"
+    pub fn ratings_label(&self) -> String {
+        let r = &self.ratings;
+        let parts: Vec<String> = [(r.no, "no"), (r.meh, "meh"), (r.like, "like"), (r.love, "love"), (r.unrated, "unrated")]
+            .iter()
+            .filter(|(n, _)| *n > 0)
+            .map(|(n, label)| format!("{} {}", n, label))
+            .collect();
+        parts.join(", ")
+    }
+
+    /// One line for compact listings: ratings, keywords and the top quote
+    pub fn line(&self) -> String {
+        let mut line = format!(
+            "{} spike{} ({})",
+            self.spikes,
+            if self.spikes == 1 { "" } else { "s" },
+            self.ratings_label()
+        );
+        if !self.keywords.is_empty() {
+            line.push_str(&format!(" · {}", self.keywords.join(", ")));
+        }
+        if let Some(quote) = self.quotes.first() {
+            line.push_str(&format!(" · \"{}\" ({})", quote.text, quote.reviewer));
+        }
+        line
+    }
+}
+
+/// The feedback a spike belongs to: every spike on the same element, or
+/// every page-level spike on the same page. Returns the selector or page
+/// name with the spikes.
+pub fn thread_of<'a>(spikes: &'a [Spike], spike: &Spike) -> (String, Vec<&'a Spike>) {
+    if spike.spike_type == SpikeType::Element {
+        if let Some(group) = group_elements(spikes)
+            .into_iter()
+            .find(|g| g.spikes.iter().any(|s| s.id == spike.id))
+        {
+            return (group.selector, group.spikes);
+        }
+    }
+    let page = spikes
+        .iter()
+        .filter(|s| s.spike_type == SpikeType::Page && s.page == spike.page)
+        .collect();
+    (spike.page.clone(), page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spike::{Reviewer, SpikeType};
+
+    fn spike(id: &str, reviewer: &str, rating: Rating, comments: &str) -> Spike {
+        Spike {
+            spike_type: SpikeType::Element,
+            reviewer: Reviewer {
+                id: reviewer.to_string(),
+                name: reviewer.to_string(),
+                email: None,
+            },
+            selector: Some(".hero".to_string()),
+            comments: comments.to_string(),
+            timestamp: "2024-01-15T10:00:00Z".to_string(),
+            ..Spike::test(id, "index.html", Some(rating))
+        }
+    }
+
+    #[test]
+    fn test_terms_drop_stopwords_and_stem_plurals() {
+        let stems: Vec<String> = terms("The buttons are WAY too small, it's 12px!").into_iter().map(|(s, _)| s).collect();
+        assert_eq!(stems, vec!["button", "small", "12px"]);
+        assert_eq!(stem("glass"), "glass");
+        assert_eq!(stem("status"), "status");
+    }
+
+    #[test]
+    fn test_summary_picks_keywords_and_distinct_quotes() {
+        let spikes = [
+            spike("s1", "Ana", Rating::No, "The headline font is too small to read"),
+            spike("s2", "Ben", Rating::No, "Headline font feels small and the contrast is weak"),
+            spike("s3", "Cy", Rating::Meh, "Headline font small"),
+            spike("s4", "Di", Rating::Love, "Love the illustration!"),
+            spike("s5", "Ed", Rating::Meh, ""),
+        ];
+        let refs: Vec<&Spike> = spikes.iter().collect();
+        let summary = Summary::of(&refs);
+
+        assert_eq!(summary.spikes, 5);
+        assert_eq!(summary.keywords, vec!["font", "headline", "small"]);
+        // s3 is the most central, s1 and s2 repeat it, so the outlier is next
+        assert_eq!(summary.quotes.iter().map(|q| q.id.as_str()).collect::<Vec<_>>(), vec!["s3", "s4"]);
+        assert_eq!(summary.ratings_label(), "2 no, 2 meh, 1 love");
+        assert_eq!(
+            summary.line(),
+            "5 spikes (2 no, 2 meh, 1 love) · font, headline, small · \"Headline font small\" (Cy)"
+        );
+
+        // Deterministic
+        assert_eq!(Summary::of(&refs), summary);
+    }
+
+    #[test]
+    fn test_long_quotes_are_truncated() {
+        let long = "word ".repeat(60);
+        let spikes = [spike("s1", "Ana", Rating::No, &long)];
+        let summary = Summary::of(&[&spikes[0]]);
+
+        assert!(summary.keywords.is_empty());
+        assert_eq!(summary.quotes[0].text.chars().count(), MAX_QUOTE_CHARS);
+        assert!(summary.quotes[0].text.ends_with('…'));
+    }
+}
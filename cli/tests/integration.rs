@@ -381,6 +381,1140 @@ fn test_hotspots_with_element_spikes() {
         .stdout(predicate::str::contains(".hero"));
 }
 
+#[test]
+fn test_hotspots_merges_equivalent_selectors() {
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"elem1\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"selector\":\"div.card.featured\",\"rating\":\"love\",\"comments\":\"Great\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"elem2\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r2\",\"name\":\"Test2\"},\"selector\":\"DIV.featured.card\",\"rating\":\"like\",\"comments\":\"Nice\",\"timestamp\":\"2024-01-01T00:01:00Z\"}");
+    project.add_spike("{\"id\":\"elem3\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r3\",\"name\":\"Test3\"},\"selector\":\"body > div:nth-child(2)\",\"xpath\":\"/html/body/div[2]\",\"rating\":\"meh\",\"comments\":\"OK\",\"timestamp\":\"2024-01-01T00:02:00Z\"}");
+    project.add_spike("{\"id\":\"elem4\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r4\",\"name\":\"Test4\"},\"selector\":\"div.card.featured\",\"xpath\":\"/html[1]/body[1]/div[2]\",\"rating\":\"no\",\"comments\":\"Meh\",\"timestamp\":\"2024-01-01T00:03:00Z\"}");
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["hotspots", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let hotspots: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let hotspots = hotspots.as_array().unwrap();
+    assert_eq!(hotspots.len(), 1);
+    assert_eq!(hotspots[0]["selector"], "div.card.featured");
+    assert_eq!(hotspots[0]["count"], 4);
+}
+
+#[test]
+fn test_check_reports_and_flags_stale_selectors() {
+    let project = TestProject::new();
+    project.add_html_file("index.html", "<html><body><h1 class=\"hero\">Welcome</h1><p id=\"lede\">Our pitch</p></body></html>");
+    project.add_spike("{\"id\":\"live1\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost:3847/index.html\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"selector\":\".hero\",\"rating\":\"love\",\"comments\":\"Great\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"moved1\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost:3847/index.html\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"selector\":\".intro\",\"elementText\":\"Our pitch\",\"rating\":\"meh\",\"comments\":\"Vague\",\"timestamp\":\"2024-01-01T00:01:00Z\"}");
+    project.add_spike("{\"id\":\"gone1\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost:3847/index.html\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"selector\":\".signup\",\"elementText\":\"Sign up\",\"rating\":\"no\",\"comments\":\"Broken\",\"timestamp\":\"2024-01-01T00:02:00Z\"}");
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["check", "--json", "--flag"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let results: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let statuses: Vec<&str> = results.as_array().unwrap().iter().map(|r| r["status"].as_str().unwrap()).collect();
+    assert_eq!(statuses, vec!["live", "moved", "gone"]);
+    assert_eq!(results[1]["new_selector"], "#lede");
+
+    let spikes = project.read_spikes();
+    assert!(!spikes[0].contains("\"stale\""));
+    assert!(spikes[2].contains("\"stale\":true"));
+}
+
+#[test]
+fn test_show_and_list_report_source_location() {
+    let project = TestProject::new();
+    project.add_html_file("index.html", "<html>\n<body>\n  <h1 class=\"hero\">Welcome</h1>\n</body>\n</html>\n");
+    project.add_spike("{\"id\":\"srcloc1\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost:3847/index.html\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"selector\":\".hero\",\"rating\":\"meh\",\"comments\":\"Bland\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["show", "srcloc1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Source:     index.html:3:3"));
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let spikes: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(spikes[0]["source"], "index.html:3:3");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_watch_prints_new_spikes_and_runs_exec() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"old1\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"no\",\"comments\":\"Old\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("spikes"))
+        .current_dir(project.path())
+        .args(["watch", "--json", "--rating", "no", "--exec", "echo \"$SPIKE_ID\" >> hook.log"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    project.add_spike("{\"id\":\"new1\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r2\",\"name\":\"Test2\"},\"rating\":\"love\",\"comments\":\"Filtered out\",\"timestamp\":\"2024-01-01T00:01:00Z\"}");
+    project.add_spike("{\"id\":\"new2\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r3\",\"name\":\"Test3\"},\"rating\":\"no\",\"comments\":\"Broken\",\"timestamp\":\"2024-01-01T00:02:00Z\"}");
+
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().unwrap()).read_line(&mut line).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let spike: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(spike["id"], "new2");
+    let hook_log = std::fs::read_to_string(project.path().join("hook.log")).unwrap();
+    assert_eq!(hook_log.trim(), "new2");
+}
+
+#[test]
+fn test_resolve_where_previews_and_confirms() {
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"bulk-001\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"old-pricing.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"no\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"bulk-002\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"old-pricing.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"meh\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"bulk-003\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"no\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["resolve", "--where", "page=old-pricing.html"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("bulk-001"))
+        .stdout(predicate::str::contains("Resolve 2 spikes? [y/N]"))
+        .stdout(predicate::str::contains("Resolved 2 spikes."));
+
+    let spikes = project.read_spikes();
+    assert!(spikes[0].contains("\"resolved\":true"));
+    assert!(spikes[1].contains("\"resolved\":true"));
+    assert!(!spikes[2].contains("\"resolved\""), "index.html should be untouched");
+}
+
+#[test]
+fn test_delete_where_cancel_and_force() {
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"bulk-001\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"old-pricing.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"no\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"bulk-002\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"old-pricing.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"meh\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"bulk-003\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"no\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["delete", "--where", "rating=no"])
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cancelled."));
+    assert_eq!(project.read_spikes().len(), 3);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["delete", "--where", "rating=no", "--where", "page~=index", "--force", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"count\": 1"))
+        .stdout(predicate::str::contains("bulk-003"));
+    assert_eq!(project.read_spikes().len(), 2);
+}
+
+#[test]
+fn test_tag_and_assign_where() {
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"bulk-001\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"old-pricing.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"no\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"bulk-002\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"old-pricing.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"meh\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"bulk-003\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"no\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["tag", "--where", "page=old-pricing.html", "--add", "redesign", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tagged 2 spikes."));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["assign", "--where", "tag=redesign", "--to", "dana", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Assigned 2 spikes."));
+
+    let spikes = project.read_spikes();
+    assert!(spikes[0].contains("\"tags\":[\"redesign\"]"));
+    assert!(spikes[1].contains("\"assignee\":\"dana\""));
+    assert!(!spikes[2].contains("\"assignee\""));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["resolve", "--where", "colour=red"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid filter: unknown key 'colour'"));
+}
+
+#[test]
+fn test_reviewers_merge_and_canonical_list_filter() {
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"rev-001\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"laptop-id\",\"name\":\"Sam\",\"email\":\"sam@example.com\"},\"rating\":\"like\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"rev-002\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"phone-id\",\"name\":\"S.\",\"email\":\"sam@example.com\"},\"rating\":\"like\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"rev-003\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"tablet-id\",\"name\":\"Sammy (tablet)\"},\"rating\":\"like\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"rev-004\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"alex-id\",\"name\":\"Alex\"},\"rating\":\"like\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+
+    // Same email: already one person, and the email survives a round trip
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["reviewers", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"email\": \"sam@example.com\""))
+        .stdout(predicate::str::contains("\"count\": 2"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["reviewers", "merge", "Sammy (tablet)", "sam@example.com"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Merged Sammy (tablet) into"))
+        .stdout(predicate::str::contains("3 spikes across 3 reviewer IDs"));
+
+    let aliases = std::fs::read_to_string(project.path().join(".spikes/reviewers.toml")).unwrap();
+    assert!(aliases.contains("tablet-id"));
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list", "--reviewer", "sam@example.com", "--json"])
+        .output()
+        .unwrap();
+    let spikes: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    let ids: Vec<&str> = spikes.iter().map(|s| s["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["rev-001", "rev-002", "rev-003"]);
+    // Filters follow the merge, but output keeps the reviewer as stored
+    assert_eq!(spikes[2]["reviewer"]["id"], "tablet-id");
+    assert_eq!(spikes[2]["reviewer"]["name"], "Sammy (tablet)");
+
+    for args in [vec!["export", "--format", "json"], vec!["show", "rev-003", "--json"]] {
+        cargo_bin_cmd!("spikes")
+            .current_dir(project.path())
+            .args(&args)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"id\": \"tablet-id\""))
+            .stdout(predicate::str::contains("\"name\": \"Sammy (tablet)\""));
+    }
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["reviewers", "merge", "nobody", "Alex"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Reviewer not found: nobody"));
+}
+
+#[test]
+fn test_round_stamps_served_spikes() {
+    let project = TestProject::new();
+    project.add_html_file("index.html", minimal_html());
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["round", "start", "v1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Started round 'v1'"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["round", "start", "v2"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Round 'v1' is still open"));
+
+    let port = "3981";
+    let mut server = std::process::Command::new(assert_cmd::cargo::cargo_bin!("spikes"))
+        .args(["serve", "--port", port, "--dir"])
+        .arg(project.path())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://localhost:{}/spikes", port);
+    let body = "{\"id\":\"served-1\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"like\",\"comments\":\"Nice\",\"timestamp\":\"2024-01-01T00:00:00Z\"}";
+    let mut saved = false;
+    for _ in 0..50 {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let response = client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send();
+        if response.is_ok_and(|r| r.status().is_success()) {
+            saved = true;
+            break;
+        }
+    }
+    server.kill().unwrap();
+    server.wait().unwrap();
+    assert!(saved, "Server should accept the spike");
+
+    let spikes = project.read_spikes();
+    assert!(spikes[0].contains("\"round\":\"v1\""), "got: {}", spikes[0]);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["round", "close"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Closed round 'v1' (1 spike)."));
+}
+
+#[test]
+fn test_served_spikes_flag_outdated_pages() {
+    let project = TestProject::new();
+    project.add_html_file("index.html", minimal_html());
+
+    let port = "3982";
+    let mut server = std::process::Command::new(assert_cmd::cargo::cargo_bin!("spikes"))
+        .args(["serve", "--port", port, "--dir"])
+        .arg(project.path())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://localhost:{}/spikes", port);
+    let body = "{\"id\":\"hashed-1\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"Home\",\"url\":\"http://localhost/index.html\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"no\",\"comments\":\"Too busy\",\"timestamp\":\"2024-01-01T00:00:00Z\"}";
+    let mut saved = false;
+    for _ in 0..50 {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let response = client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send();
+        if response.is_ok_and(|r| r.status().is_success()) {
+            saved = true;
+            break;
+        }
+    }
+    server.kill().unwrap();
+    server.wait().unwrap();
+    assert!(saved, "Server should accept the spike");
+
+    let spikes = project.read_spikes();
+    assert!(spikes[0].contains("\"pageHash\":\""), "got: {}", spikes[0]);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(outdated)").not());
+
+    project.add_html_file("index.html", &minimal_html().replace("</body>", "<p>New copy</p></body>"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Home (outdated)"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"outdated\": true"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["show", "hashed-1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Outdated:   page changed since this feedback was given"));
+}
+
+#[test]
+fn test_outdated_pages_resolve_against_configured_serve_dir() {
+    let project = TestProject::new();
+    project.add_html_file("public/index.html", minimal_html());
+    project.add_spike("{\"id\":\"hashed-2\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"Home\",\"url\":\"http://localhost/index.html\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"no\",\"comments\":\"Too busy\",\"timestamp\":\"2024-01-01T00:00:00Z\",\"pageHash\":\"0000000000000000\"}");
+
+    // Not under the project root, so it can't be compared
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(outdated)").not());
+
+    std::fs::write(project.path().join(".spikes/config.toml"), "[serve]\ndir = \"public\"\n").unwrap();
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Home (outdated)"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["show", "hashed-2", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"outdated\": true"));
+}
+
+#[test]
+fn test_trends_by_week() {
+    let project = TestProject::new();
+    for (id, page, rating, timestamp) in [
+        ("tr-001", "pricing.html", "no", "2024-01-08T10:00:00Z"),
+        ("tr-002", "pricing.html", "meh", "2024-01-09T10:00:00Z"),
+        ("tr-003", "pricing.html", "no", "2024-01-15T10:00:00Z"),
+        ("tr-004", "pricing.html", "love", "2024-01-16T10:00:00Z"),
+        ("tr-005", "index.html", "no", "2024-01-16T10:00:00Z"),
+    ] {
+        project.add_spike(&format!(
+            "{{\"id\":\"{}\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"{}\",\"url\":\"http://localhost\",\"reviewer\":{{\"id\":\"r1\",\"name\":\"Test\"}},\"rating\":\"{}\",\"comments\":\"Test\",\"timestamp\":\"{}\"}}",
+            id, page, rating, timestamp
+        ));
+    }
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["trends", "--page", "pricing", "--bucket", "week", "--json"])
+        .output()
+        .unwrap();
+    let points: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(points.as_array().unwrap().len(), 2);
+    assert_eq!(points[0]["bucket"], "2024-W02");
+    assert_eq!(points[0]["negative_ratio"], 1.0);
+    assert_eq!(points[1]["total"], 2);
+    assert_eq!(points[1]["negative_ratio"], 0.5);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["trends", "--page", "pricing", "--bucket", "week", "--chart"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2024-W03"))
+        .stdout(predicate::str::contains("(1/2 negative)"))
+        .stdout(predicate::str::contains("Negative ratio: 100% → 50% (improving)"));
+}
+
+#[test]
+fn test_viewport_filter_and_breakdown() {
+    let project = TestProject::new();
+    std::fs::write(project.path().join(".spikes/config.toml"), "[viewport]\nmobile_max = 480\n").unwrap();
+    for (id, rating, width) in [("vp-001", "no", 390), ("vp-002", "meh", 600), ("vp-003", "love", 1440)] {
+        project.add_spike(&format!(
+            "{{\"id\":\"{}\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{{\"id\":\"r1\",\"name\":\"Test\"}},\"rating\":\"{}\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\",\"viewport\":{{\"width\":{},\"height\":800}}}}",
+            id, rating, width
+        ));
+    }
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list", "--viewport", "mobile"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("vp-001"))
+        .stdout(predicate::str::contains("vp-002").not());
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--viewport", "tablet", "--format", "jsonl"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("vp-002"))
+        .stdout(predicate::str::contains("vp-001").not());
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list", "--viewport", "watch"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid viewport"));
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["stats", "--json"])
+        .output()
+        .unwrap();
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["viewports"][0]["viewport"], "mobile");
+    assert_eq!(stats["viewports"][0]["range"], "≤480px");
+    assert_eq!(stats["viewports"][1]["negative"], 1);
+    assert_eq!(stats["viewports"][2]["viewport"], "desktop");
+}
+
+#[test]
+fn test_hotspots_consensus_flags_polarizing_elements() {
+    let project = TestProject::new();
+    for (id, reviewer, selector, rating) in [
+        ("cs-001", "r1", ".hero", "love"),
+        ("cs-002", "r2", ".hero", "no"),
+        ("cs-003", "r3", ".hero", "like"),
+        ("cs-004", "r4", ".hero", "meh"),
+        ("cs-005", "r1", ".cta", "no"),
+        ("cs-006", "r2", ".cta", "no"),
+    ] {
+        project.add_spike(&format!(
+            "{{\"id\":\"{}\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{{\"id\":\"{}\",\"name\":\"{}\"}},\"selector\":\"{}\",\"rating\":\"{}\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}}",
+            id, reviewer, reviewer, selector, rating
+        ));
+    }
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["hotspots", "--consensus", "--json"])
+        .output()
+        .unwrap();
+    let scores: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(scores[0]["selector"], ".hero");
+    assert_eq!(scores[0]["polarizing"], true);
+    assert_eq!(scores[1]["selector"], ".cta");
+    assert_eq!(scores[1]["agreement"], 1.0);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["hotspots", "--consensus"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 love/like · 2 meh/no (polarizing)"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "claude-context"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("## ⚖️ CONTESTED ELEMENTS"))
+        .stdout(predicate::str::contains("| `.hero` | 4 | 2 love/like vs 2 meh/no |"));
+}
+
+#[test]
+fn test_heatmap_writes_svg_and_png() {
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"hm-001\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"selector\":\".hero\",\"boundingBox\":{\"x\":0,\"y\":100,\"width\":390,\"height\":200},\"viewport\":{\"width\":390,\"height\":844},\"rating\":\"no\",\"comments\":\"Cramped\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"hm-002\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"about.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"selector\":\".team\",\"boundingBox\":{\"x\":0,\"y\":0,\"width\":100,\"height\":100},\"viewport\":{\"width\":1280,\"height\":800},\"rating\":\"love\",\"comments\":\"Nice\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["heatmap", "--page", "index.html", "--out", "heat.svg", "--width", "1000"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote heat.svg (1000x802, 1 spike)."));
+    let svg = std::fs::read_to_string(project.path().join("heat.svg")).unwrap();
+    assert!(svg.contains("<title>.hero — no: Cramped</title>"));
+    assert!(!svg.contains(".team"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["heatmap", "--page", "index.html", "--out", "heat.png"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote heat.png (1280x985, 1 spike)."));
+    let png = std::fs::read(project.path().join("heat.png")).unwrap();
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["heatmap", "--page", "contact.html", "--out", "none.svg"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No element spikes with a bounding box and viewport on 'contact.html'"));
+    assert!(!project.path().join("none.svg").exists());
+}
+
+#[test]
+fn test_show_summary_and_brief_export() {
+    let project = TestProject::new();
+    for (id, reviewer, rating, comments) in [
+        ("sum-001", "Ana", "no", "Headline font too small"),
+        ("sum-002", "Ben", "no", "The headline font is small on phones"),
+        ("sum-003", "Cy", "love", "Love the illustration"),
+    ] {
+        project.add_spike(&format!(
+            "{{\"id\":\"{}\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{{\"id\":\"{}\",\"name\":\"{}\"}},\"selector\":\".hero\",\"rating\":\"{}\",\"comments\":\"{}\",\"timestamp\":\"2024-01-15T10:00:00Z\"}}",
+            id, reviewer, reviewer, rating, comments
+        ));
+    }
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["show", "sum-003", "--summary"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Summary of .hero (3 spikes)"))
+        .stdout(predicate::str::contains("Ratings:  2 no, 1 love"))
+        .stdout(predicate::str::contains("Keywords: font, headline"));
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["show", "sum-001", "--summary", "--json"])
+        .output()
+        .unwrap();
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["summary"]["target"], ".hero");
+    assert_eq!(value["summary"]["spikes"], 3);
+    assert_eq!(value["summary"]["quotes"].as_array().unwrap().len(), 2);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "brief"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# Spikes brief: test"))
+        .stdout(predicate::str::contains("- `.hero` on index.html: 3 spikes (2 no, 1 love) · font, headline"));
+}
+
+#[test]
+fn test_export_html_report() {
+    let project = TestProject::new();
+    project.add_spike(r#"{"id":"html-001","type":"element","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{"id":"r1","name":"Ana"},"selector":".hero","boundingBox":{"x":10,"y":20,"width":300,"height":100},"viewport":{"width":1280,"height":800},"rating":"no","comments":"Fix <this>","timestamp":"2024-01-15T10:00:00Z"}"#);
+    project.add_spike(r#"{"id":"html-002","type":"page","projectKey":"test","page":"about.html","url":"http://localhost","reviewer":{"id":"r1","name":"Ana"},"rating":"love","comments":"Great","timestamp":"2024-01-15T10:00:00Z","resolved":true}"#);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "html"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("<!DOCTYPE html>"))
+        .stdout(predicate::str::contains("id=\"page-index-html\""))
+        .stdout(predicate::str::contains("<svg"))
+        .stdout(predicate::str::contains("Fix &lt;this&gt;"))
+        .stdout(predicate::str::contains("data-status=\"resolved\""));
+}
+
+#[test]
+fn test_export_sarif_points_at_element_lines() {
+    let project = TestProject::new();
+    project.add_html_file("index.html", "<html>\n<body>\n  <h1 class=\"hero\">Welcome</h1>\n</body>\n</html>\n");
+    project.add_spike(r#"{"id":"sarif-001","type":"element","projectKey":"test","page":"index.html","url":"http://localhost:3847/index.html","reviewer":{"id":"r1","name":"Ana"},"selector":".hero","rating":"no","comments":"Too loud","timestamp":"2024-01-15T10:00:00Z"}"#);
+    project.add_spike(r#"{"id":"sarif-002","type":"page","projectKey":"test","page":"index.html","url":"http://localhost:3847/index.html","reviewer":{"id":"r1","name":"Ana"},"rating":"meh","comments":"Done","timestamp":"2024-01-15T10:00:00Z","resolved":true}"#);
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "sarif"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["level"], "error");
+    let physical = &results[0]["locations"][0]["physicalLocation"];
+    assert_eq!(physical["artifactLocation"]["uri"], "index.html");
+    assert_eq!(physical["region"]["startLine"], 3);
+}
+
+#[test]
+fn test_export_junit_fails_blocking_spikes() {
+    let project = TestProject::new();
+    project.add_spike(r#"{"id":"junit-001","type":"page","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{"id":"r1","name":"Ana"},"rating":"no","comments":"Broken","timestamp":"2024-01-15T10:00:00Z"}"#);
+    project.add_spike(r#"{"id":"junit-002","type":"page","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{"id":"r1","name":"Ana"},"rating":"like","comments":"Fine","timestamp":"2024-01-15T10:00:00Z"}"#);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "junit"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<testsuite name=\"index.html\" tests=\"2\" failures=\"1\""))
+        .stdout(predicate::str::contains("<failure type=\"no\" message=\"Broken\">"))
+        .stdout(predicate::str::contains("name=\"page (junit-002)\"/>"));
+}
+
+#[test]
+fn test_export_markdown_respects_filters() {
+    let project = TestProject::new();
+    project.add_spike(r#"{"id":"md-001","type":"element","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{"id":"r1","name":"Ana"},"selector":".hero","rating":"no","comments":"Too loud","timestamp":"2024-01-15T10:00:00Z"}"#);
+    project.add_spike(r#"{"id":"md-002","type":"page","projectKey":"test","page":"about.html","url":"http://localhost","reviewer":{"id":"r2","name":"Ben"},"rating":"love","comments":"Lovely","timestamp":"2024-01-15T10:00:00Z"}"#);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "markdown", "--rating", "no"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Filtered by rating=no."))
+        .stdout(predicate::str::contains("- [index.html](#indexhtml) — 1 spike · 1 open · 0 resolved"))
+        .stdout(predicate::str::contains("### `.hero`"))
+        .stdout(predicate::str::contains("- **no** — Ana, 2024-01-15: Too loud"))
+        .stdout(predicate::str::contains("about.html").not());
+}
+
+#[test]
+fn test_export_with_user_template() {
+    let project = TestProject::new();
+    project.add_spike(r#"{"id":"tpl-001","type":"element","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{"id":"r1","name":"Ana"},"selector":".hero","rating":"no","comments":"Too loud","timestamp":"2024-01-15T10:00:00Z"}"#);
+    std::fs::create_dir_all(project.path().join(".spikes/templates")).unwrap();
+    std::fs::write(
+        project.path().join(".spikes/templates/standup.md.tera"),
+        "{{ project }}: {{ blocking | length }} blocking\n{% for h in hotspots %}- {{ h.selector }} ({{ h.count }})\n{% endfor %}",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--template", "standup.md"])
+        .assert()
+        .success()
+        .stdout("test: 1 blocking\n- .hero (1)\n");
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--list-templates"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("standup.md.tera"))
+        .stdout(predicate::str::contains("cursor-context"));
+
+    // Bundled templates render like their formats
+    let by_template = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--template", "claude-context"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&by_template.stdout).contains("### ID: `tpl-001`"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--template", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Template not found: missing"));
+}
+
+#[test]
+fn test_import_round_trips_csv_export_and_maps_columns() {
+    let source = TestProject::new();
+    source.add_spike(r#"{"id":"imp-001","type":"element","projectKey":"test","page":"index.html","url":"http://localhost/index.html","reviewer":{"id":"r1","name":"Ana"},"selector":".hero","rating":"no","comments":"Too loud, \"really\"","timestamp":"2024-01-15T10:00:00Z","viewport":{"width":390,"height":844}}"#);
+    let csv = cargo_bin_cmd!("spikes")
+        .current_dir(source.path())
+        .args(["export", "--format", "csv"])
+        .output()
+        .unwrap();
+    std::fs::write(source.path().join("feedback.csv"), &csv.stdout).unwrap();
+
+    let project = TestProject::new();
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["import", source.path().join("feedback.csv").to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 spike"));
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "json"])
+        .output()
+        .unwrap();
+    let spikes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(spikes[0]["id"], "imp-001");
+    assert_eq!(spikes[0]["comments"], "Too loud, \"really\"");
+    assert_eq!(spikes[0]["viewport"]["width"], 390);
+
+    // Importing again skips what is already stored
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["import", source.path().join("feedback.csv").to_str().unwrap(), "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"imported\": 0"))
+        .stdout(predicate::str::contains("\"skipped\": 1"));
+
+    // Another tool's columns, mapped onto spike fields
+    std::fs::write(
+        project.path().join("other.csv"),
+        "Feedback,Score,Page\nBigger button,meh,pricing.html\nBad,awful,pricing.html\n",
+    )
+    .unwrap();
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["import", "other.csv", "--map", "Feedback=comments", "--map", "Score=rating"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("line 3: Invalid rating: awful"));
+
+    std::fs::write(
+        project.path().join("other.csv"),
+        "Feedback,Score,Page\nBigger button,meh,pricing.html\n",
+    )
+    .unwrap();
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["import", "other.csv", "--map", "Feedback=comments", "--map", "Score=rating"])
+        .assert()
+        .success();
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list", "--page", "pricing.html"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Bigger button"));
+}
+
+#[test]
+fn test_export_claude_context_with_token_budget() {
+    let project = TestProject::new();
+    for i in 0..30 {
+        project.add_spike(&format!(
+            r#"{{"id":"budget-{:03}","type":"element","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{{"id":"r{}","name":"Reviewer {}"}},"selector":".card-{}","rating":"{}","comments":"Spacing and contrast on this card need another pass before launch","timestamp":"2024-01-{:02}T10:00:00Z"}}"#,
+            i,
+            i,
+            i,
+            i % 5,
+            if i % 3 == 0 { "no" } else { "like" },
+            i % 28 + 1
+        ));
+    }
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "claude-context", "--max-tokens", "500"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let pack = String::from_utf8_lossy(&output.stdout);
+    assert!(pack.chars().count() <= 500 * 4, "pack is {} chars", pack.chars().count());
+    assert!(pack.contains("## ⚠️ CRITICAL ISSUES"));
+    assert!(pack.contains("## ✂️ OMITTED"));
+    assert!(pack.contains("older open spikes"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "json", "--max-tokens", "500"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--max-tokens only applies to --format claude-context"));
+}
+
+#[test]
+fn test_context_write_and_check() {
+    let project = TestProject::new();
+    project.add_spike(r#"{"id":"ctx-0001","type":"element","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{"id":"r1","name":"Ana"},"selector":".hero","rating":"no","comments":"Too loud","timestamp":"2024-01-15T10:00:00Z"}"#);
+    std::fs::write(project.path().join("AGENTS.md"), "# Agents\n\nRun cargo test.\n").unwrap();
+
+    // Nothing written yet
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["context", "write", "--check"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Agent context is out of date: AGENTS.md, CLAUDE.md, .cursor/rules/spikes.mdc"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["context", "write"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("updated      AGENTS.md"))
+        .stdout(predicate::str::contains("created      CLAUDE.md"));
+
+    let agents = std::fs::read_to_string(project.path().join("AGENTS.md")).unwrap();
+    assert!(agents.starts_with("# Agents\n\nRun cargo test.\n\n<!-- spikes:begin -->"));
+    assert!(agents.contains("- `.hero` — **no** from Ana: \"Too loud\" (`ctx-0001`)"));
+    assert!(project.path().join(".cursor/rules/spikes.mdc").exists());
+
+    // Writing again changes nothing, so the check passes
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["context", "write"])
+        .assert()
+        .success();
+    assert_eq!(std::fs::read_to_string(project.path().join("AGENTS.md")).unwrap(), agents);
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["context", "write", "--check"])
+        .assert()
+        .success();
+
+    // New blocking feedback makes the files stale
+    project.add_spike(r#"{"id":"ctx-0002","type":"element","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{"id":"r2","name":"Ben"},"selector":".cta","rating":"meh","comments":"Hard to find","timestamp":"2024-01-16T10:00:00Z"}"#);
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["context", "write", "--check", "--file", "AGENTS.md"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("stale        AGENTS.md"));
+}
+
+#[tokio::test]
+async fn test_issues_create_once_and_sync_state_back() {
+    use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+    let server = MockServer::start().await;
+    Mock::given(matchers::method("POST"))
+        .and(matchers::path("/repos/acme/site/issues"))
+        .and(matchers::header("Authorization", "Bearer test-token"))
+        .and(matchers::body_partial_json(serde_json::json!({
+            "title": "[spikes] no on .hero: Too loud",
+            "labels": ["copy", "feedback"],
+        })))
+        .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+            "number": 12,
+            "html_url": "https://github.com/acme/site/issues/12",
+            "state": "open",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/repos/acme/site/issues/12"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "number": 12, "state": "closed" })))
+        .mount(&server)
+        .await;
+
+    let project = TestProject::new();
+    project.add_spike(r#"{"id":"iss-0001","type":"element","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{"id":"r1","name":"Ana"},"selector":".hero","rating":"no","comments":"Too loud","timestamp":"2024-01-15T10:00:00Z","tags":["copy"]}"#);
+    project.add_spike(r#"{"id":"iss-0002","type":"page","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{"id":"r2","name":"Ben"},"rating":"love","comments":"Great","timestamp":"2024-01-15T11:00:00Z"}"#);
+    std::fs::write(
+        project.path().join(".spikes/config.toml"),
+        format!("[issues]\ntracker = \"github\"\nproject = \"acme/site\"\nurl = \"{}\"\nlabels = [\"feedback\"]\n", server.uri()),
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .env("GITHUB_TOKEN", "test-token")
+        .args(["issues", "create"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Filed iss-0001 → https://github.com/acme/site/issues/12"))
+        .stdout(predicate::str::contains("Filed 1 issue on github (acme/site)."));
+
+    // The link is stored, so a second run files nothing
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .env("GITHUB_TOKEN", "test-token")
+        .args(["issues", "create", "iss-0001"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped iss-0001: already filed as https://github.com/acme/site/issues/12"));
+
+    // No remote is configured, but sync still picks up the closed issue
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .env("GITHUB_TOKEN", "test-token")
+        .arg("sync")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Resolved iss-0001"));
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["show", "iss-0001", "--json"])
+        .output()
+        .unwrap();
+    let spike: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(spike["resolved"], true);
+    assert_eq!(spike["issue"]["url"], "https://github.com/acme/site/issues/12");
+    assert_eq!(spike["issue"]["state"], "closed");
+}
+
+#[test]
+fn test_export_csv_fields_select_and_order_columns() {
+    let project = TestProject::new();
+    project.add_spike(r#"{"id":"csv-0001","type":"element","projectKey":"test","page":"index.html","url":"http://localhost","reviewer":{"id":"r1","name":"Ana"},"selector":".hero","boundingBox":{"x":10.0,"y":20.0,"width":300.0,"height":40.0},"rating":"no","comments":"Too loud","timestamp":"2024-01-15T10:00:00Z","resolved":true,"resolvedAt":"2024-01-16T10:00:00Z","tags":["copy","hero"]}"#);
+
+    // Every field is a column, including the ones the old fixed header left out
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "csv"])
+        .output()
+        .unwrap();
+    let csv = String::from_utf8(output.stdout).unwrap();
+    let header = csv.lines().next().unwrap();
+    assert!(header.starts_with("id,type,project_key,page,url,reviewer_id,reviewer_name,reviewer_email,"), "got: {}", header);
+    assert!(header.contains(",bounding_box_x,bounding_box_y,bounding_box_width,bounding_box_height,"));
+    assert!(header.contains(",resolved,resolved_at,"));
+    assert!(csv.contains(",true,2024-01-16T10:00:00Z,"), "got: {}", csv);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "csv", "--fields", "rating,id,bounding_box,tags"])
+        .assert()
+        .success()
+        .stdout(predicate::str::diff(
+            "rating,id,bounding_box_x,bounding_box_y,bounding_box_width,bounding_box_height,tags\n\
+             no,csv-0001,10.0,20.0,300.0,40.0,\"[\"\"copy\"\",\"\"hero\"\"]\"\n",
+        ));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "csv", "--fields", "id,nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown field: nope. Fields: id, type,"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "json", "--fields", "id"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--fields only applies to --format csv"));
+}
+
+#[test]
+fn test_where_reviewer_matches_aliases() {
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"alias-001\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"laptop\",\"name\":\"Sam\",\"email\":\"sam@example.com\"},\"rating\":\"no\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"alias-002\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"phone\",\"name\":\"sam b\",\"email\":\"sam@example.com\"},\"rating\":\"no\",\"comments\":\"Test\",\"timestamp\":\"2024-01-02T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"alias-003\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"phone\",\"name\":\"sam b\"},\"rating\":\"no\",\"comments\":\"Test\",\"timestamp\":\"2024-01-03T00:00:00Z\"}");
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["resolve", "--where", "reviewer=sam@example.com", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Resolved 3 spikes."));
+
+    // The stored reviewer is left as captured
+    let spikes = project.read_spikes();
+    assert!(spikes[2].contains("\"resolved\":true"));
+    assert!(spikes[2].contains("\"reviewer\":{\"id\":\"phone\",\"name\":\"sam b\"}"));
+}
+
+#[test]
+fn test_round_compare_and_round_filters() {
+    let project = TestProject::new();
+    std::fs::write(
+        project.path().join(".spikes/rounds.toml"),
+        "[[rounds]]\nname = \"v1\"\nstarted_at = \"2024-01-01T00:00:00Z\"\nclosed_at = \"2024-01-02T00:00:00Z\"\n\n[[rounds]]\nname = \"v2\"\nstarted_at = \"2024-01-03T00:00:00Z\"\n",
+    )
+    .unwrap();
+    project.add_spike("{\"id\":\"cmp-001\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"pricing.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"selector\":\".cta\",\"rating\":\"no\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\",\"round\":\"v1\"}");
+    project.add_spike("{\"id\":\"cmp-002\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"pricing.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"meh\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\",\"round\":\"v1\"}");
+    project.add_spike("{\"id\":\"cmp-003\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"pricing.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"selector\":\".cta\",\"rating\":\"love\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\",\"round\":\"v2\"}");
+    project.add_spike("{\"id\":\"cmp-004\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"pricing.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"like\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\",\"round\":\"v2\"}");
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["round", "compare", "v1", "v2", "--json"])
+        .output()
+        .unwrap();
+    let comparison: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(comparison["pages"][0]["page"], "pricing.html");
+    assert_eq!(comparison["pages"][0]["delta"], 2.0);
+    assert_eq!(comparison["elements"][0]["selector"], ".cta");
+    assert_eq!(comparison["elements"][0]["delta"], 3.0);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["round", "compare", "v1", "v3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Round not found: v3"));
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["list", "--round", "v2", "--json"])
+        .output()
+        .unwrap();
+    let spikes: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(spikes.len(), 2);
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["stats", "--round", "v1", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"total\": 2"))
+        .stdout(predicate::str::contains("\"meh\": 1"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["export", "--format", "jsonl", "--round", "v2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("cmp-003"))
+        .stdout(predicate::str::contains("cmp-001").not());
+}
+
+#[test]
+fn test_clusters_groups_nearby_elements() {
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"card1\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"selector\":\".card\",\"boundingBox\":{\"x\":100,\"y\":100,\"width\":300,\"height\":200},\"rating\":\"no\",\"comments\":\"Cramped\",\"timestamp\":\"2024-01-01T00:00:00Z\",\"viewport\":{\"width\":1000,\"height\":800}}");
+    project.add_spike("{\"id\":\"card2\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r2\",\"name\":\"Test2\"},\"selector\":\".card h2\",\"boundingBox\":{\"x\":120,\"y\":110,\"width\":200,\"height\":40},\"rating\":\"meh\",\"comments\":\"Title too small\",\"timestamp\":\"2024-01-01T00:01:00Z\",\"viewport\":{\"width\":1000,\"height\":800}}");
+
+    let output = cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["clusters", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let clusters: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let clusters = clusters.as_array().unwrap();
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0]["count"], 2);
+    assert_eq!(clusters[0]["selectors"][1], ".card h2");
+}
+
+#[test]
+fn test_dedupe_links_duplicates_and_show_reports_agreement() {
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"dup1\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Alice\"},\"selector\":\".hero\",\"rating\":\"no\",\"comments\":\"Font too small\",\"timestamp\":\"2024-01-01T00:00:00Z\"}");
+    project.add_spike("{\"id\":\"dup2\",\"type\":\"element\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r2\",\"name\":\"Bob\"},\"selector\":\".hero\",\"rating\":\"no\",\"comments\":\"font is too small\",\"timestamp\":\"2024-01-01T00:01:00Z\"}");
+
+    // Preview does not modify storage
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .arg("dedupe")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("duplicate  dup2"));
+    assert!(!project.read_spikes()[1].contains("duplicateOf"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["dedupe", "--apply"])
+        .assert()
+        .success();
+    assert!(project.read_spikes()[1].contains("\"duplicateOf\":\"dup1\""));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["show", "dup1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+1 reviewer agrees"));
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["dedupe", "--unlink", "dup1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Unlinked 1 spike: dup2"));
+    assert!(!project.read_spikes()[1].contains("duplicateOf"));
+}
+
 #[test]
 fn test_reviewers() {
     let project = TestProject::new();
@@ -527,6 +1661,22 @@ fn test_resolve_spike() {
     assert!(spikes[0].contains("\"resolvedAt\""), "Spike should have resolvedAt timestamp");
 }
 
+#[test]
+fn test_resolve_keeps_original_resolved_at() {
+    let project = TestProject::new();
+    project.add_spike("{\"id\":\"resolve-test-123\",\"type\":\"page\",\"projectKey\":\"test\",\"page\":\"index.html\",\"url\":\"http://localhost\",\"reviewer\":{\"id\":\"r1\",\"name\":\"Test\"},\"rating\":\"like\",\"comments\":\"Test\",\"timestamp\":\"2024-01-01T00:00:00Z\",\"resolved\":true,\"resolvedAt\":\"2024-02-01T00:00:00Z\"}");
+
+    cargo_bin_cmd!("spikes")
+        .current_dir(project.path())
+        .args(["resolve", "resolve-test-123"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Resolved spike resolve-test-123."));
+
+    let spikes = project.read_spikes();
+    assert!(spikes[0].contains("\"resolvedAt\":\"2024-02-01T00:00:00Z\""), "got: {}", spikes[0]);
+}
+
 #[test]
 fn test_resolve_spike_json_output() {
     let project = TestProject::new();
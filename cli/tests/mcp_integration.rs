@@ -282,19 +282,23 @@ fn test_mcp_http_tools_list() {
         .or_else(|| serde_json::from_str(&body).ok())
         .expect("Response should contain valid JSON");
 
-    // Should list 9 tools - UNCONDITIONAL assertion (test must fail if tools not present)
+    // Should list 10 tools - UNCONDITIONAL assertion (test must fail if tools not present)
     assert!(
         json["result"]["tools"].is_array(),
         "Response must contain tools array"
     );
     let tools = json["result"]["tools"].as_array().unwrap();
-    assert_eq!(tools.len(), 9, "Should have 9 MCP tools");
+    assert_eq!(tools.len(), 13, "Should have 13 MCP tools");
 
     // Verify tool names
     let tool_names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
     assert!(tool_names.contains(&"get_spikes"), "Must have get_spikes tool");
     assert!(tool_names.contains(&"submit_spike"), "Must have submit_spike tool");
     assert!(tool_names.contains(&"get_usage"), "Must have get_usage tool");
+    assert!(tool_names.contains(&"get_clusters"), "Must have get_clusters tool");
+    assert!(tool_names.contains(&"get_spike_source_context"), "Must have get_spike_source_context tool");
+    assert!(tool_names.contains(&"bulk_resolve"), "Must have bulk_resolve tool");
+    assert!(tool_names.contains(&"get_context_pack"), "Must have get_context_pack tool");
 }
 
 #[test]